@@ -1,7 +1,7 @@
 use crate::{
 	error::Result,
 	hash,
-	sync::{self, FileBlame},
+	sync::{self, BlameOptions, BlameProgress, CommitId, FileBlame},
 	AsyncGitNotification, CWD,
 };
 use crossbeam_channel::Sender;
@@ -18,6 +18,10 @@ use std::{
 pub struct BlameParams {
 	/// path to the file to blame
 	pub file_path: String,
+	/// revision to blame at, HEAD if `None`
+	pub commit_id: Option<CommitId>,
+	/// ignored revisions and move/copy detection
+	pub options: BlameOptions,
 }
 
 struct Request<R, A>(R, Option<A>);
@@ -32,6 +36,7 @@ struct LastResult<P, R> {
 pub struct AsyncBlame {
 	current: Arc<Mutex<Request<u64, FileBlame>>>,
 	last: Arc<Mutex<Option<LastResult<BlameParams, FileBlame>>>>,
+	partial: Arc<Mutex<Request<u64, (FileBlame, BlameProgress)>>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicUsize>,
 }
@@ -42,6 +47,7 @@ impl AsyncBlame {
 		Self {
 			current: Arc::new(Mutex::new(Request(0, None))),
 			last: Arc::new(Mutex::new(None)),
+			partial: Arc::new(Mutex::new(Request(0, None))),
 			sender: sender.clone(),
 			pending: Arc::new(AtomicUsize::new(0)),
 		}
@@ -58,6 +64,28 @@ impl AsyncBlame {
 		}))
 	}
 
+	/// lines blamed so far by the pending request
+	pub fn partial(
+		&self,
+	) -> Result<Option<(FileBlame, BlameProgress)>> {
+		let current = self.current.lock()?.0;
+		let partial = self.partial.lock()?;
+
+		Ok(if partial.0 == current {
+			partial.1.clone()
+		} else {
+			None
+		})
+	}
+
+	/// stops the pending request after the chunk of lines it is on
+	pub fn cancel(&mut self) -> Result<()> {
+		self.clear_current()?;
+		*self.partial.lock()? = Request(0, None);
+
+		Ok(())
+	}
+
 	///
 	pub fn refresh(&mut self) -> Result<()> {
 		if let Ok(Some(param)) = self.get_last_param() {
@@ -94,6 +122,7 @@ impl AsyncBlame {
 
 		let arc_current = Arc::clone(&self.current);
 		let arc_last = Arc::clone(&self.last);
+		let arc_partial = Arc::clone(&self.partial);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
 
@@ -104,6 +133,8 @@ impl AsyncBlame {
 				params,
 				&arc_last,
 				&arc_current,
+				&arc_partial,
+				&sender,
 				hash,
 			);
 
@@ -135,10 +166,43 @@ impl AsyncBlame {
 			Mutex<Option<LastResult<BlameParams, FileBlame>>>,
 		>,
 		arc_current: &Arc<Mutex<Request<u64, FileBlame>>>,
+		arc_partial: &Arc<
+			Mutex<Request<u64, (FileBlame, BlameProgress)>>,
+		>,
+		sender: &Sender<AsyncGitNotification>,
 		hash: u64,
 	) -> Result<bool> {
-		let file_blame =
-			sync::blame::blame_file(CWD, &params.file_path)?;
+		let file_blame = sync::blame::blame_file_incremental(
+			CWD,
+			&params.file_path,
+			params.commit_id,
+			params.options,
+			|partial, progress| {
+				// a newer request or `cancel` replaced this one
+				if arc_current.lock().map_or(true, |c| c.0 != hash) {
+					return false;
+				}
+
+				if progress.lines_done < progress.lines_total {
+					if let Ok(mut arc_partial) = arc_partial.lock() {
+						arc_partial.0 = hash;
+						arc_partial.1 =
+							Some((partial.clone(), progress));
+					}
+
+					sender
+						.send(AsyncGitNotification::Blame)
+						.expect("error sending blame");
+				}
+
+				true
+			},
+		)?;
+
+		let file_blame = match file_blame {
+			Some(file_blame) => file_blame,
+			None => return Ok(false),
+		};
 
 		let mut notify = false;
 		{
@@ -31,6 +31,8 @@ pub enum DiffType {
 pub struct DiffParams {
 	/// path to the file to diff
 	pub path: String,
+	/// path before a rename/copy to diff `path` against
+	pub old_path: Option<String>,
 	/// what kind of diff
 	pub diff_type: DiffType,
 	/// diff options
@@ -151,27 +153,39 @@ impl AsyncDiff {
 		hash: u64,
 	) -> Result<bool> {
 		let res = match params.diff_type {
-			DiffType::Stage => sync::diff::get_diff(
-				CWD,
-				&params.path,
-				true,
-				Some(params.options),
-			)?,
-			DiffType::WorkDir => sync::diff::get_diff(
-				CWD,
-				&params.path,
-				false,
-				Some(params.options),
-			)?,
+			DiffType::Stage | DiffType::WorkDir => {
+				let stage = params.diff_type == DiffType::Stage;
+
+				if let Some(old_path) = &params.old_path {
+					sync::diff::get_diff_renamed(
+						CWD,
+						old_path,
+						&params.path,
+						stage,
+						Some(params.options),
+					)?
+				} else {
+					sync::diff::get_diff(
+						CWD,
+						&params.path,
+						stage,
+						Some(params.options),
+					)?
+				}
+			}
 			DiffType::Commit(id) => sync::diff::get_diff_commit(
 				CWD,
 				id,
-				params.path.clone(),
+				&params.path,
+				params.old_path.as_deref(),
+				Some(params.options),
 			)?,
 			DiffType::Commits(ids) => sync::diff::get_diff_commits(
 				CWD,
 				ids,
-				params.path.clone(),
+				&params.path,
+				params.old_path.as_deref(),
+				Some(params.options),
 			)?,
 		};
 
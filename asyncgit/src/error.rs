@@ -61,6 +61,10 @@ pub enum Error {
 	///
 	#[error("EasyCast error:{0}")]
 	EasyCast(#[from] easy_cast::Error),
+
+	///
+	#[error("regex error:{0}")]
+	Regex(#[from] regex::Error),
 }
 
 ///
@@ -0,0 +1,110 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{grep_files, CommitId, GrepResult, GREP_MAX_MATCHES},
+	AsyncGitNotification, ProgressPercent, CWD,
+};
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc, Mutex,
+};
+
+enum JobState {
+	Request {
+		commit_id: Option<CommitId>,
+		pattern: String,
+	},
+	/// `None` if cancelled
+	Response(Result<Option<GrepResult>>),
+}
+
+///
+#[derive(Clone)]
+pub struct AsyncGrepJob {
+	state: Arc<Mutex<Option<JobState>>>,
+	cancelled: Arc<AtomicBool>,
+}
+
+///
+impl AsyncGrepJob {
+	/// searches `commit_id`, or the working tree if `None`
+	pub fn new(commit_id: Option<CommitId>, pattern: &str) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				commit_id,
+				pattern: pattern.to_string(),
+			}))),
+			cancelled: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	/// stops the search after the file currently searched,
+	/// the result is `Ok(None)` then
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<Option<GrepResult>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncGrepJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let cancelled = &self.cancelled;
+
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { commit_id, pattern } => {
+					let mut last = ProgressPercent::empty();
+
+					let result = grep_files(
+						CWD,
+						commit_id,
+						&pattern,
+						GREP_MAX_MATCHES,
+						|done, total| {
+							let progress =
+								ProgressPercent::new(done, total);
+							if progress != last {
+								last = progress;
+								params.set_progress(progress).ok();
+								params
+									.send(AsyncGitNotification::Grep)
+									.ok();
+							}
+
+							!cancelled.load(Ordering::Relaxed)
+						},
+					);
+
+					JobState::Response(result)
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::Grep)
+	}
+}
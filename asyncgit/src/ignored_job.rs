@@ -0,0 +1,74 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{get_ignored_files, IgnoredFile},
+	AsyncGitNotification, CWD,
+};
+
+use std::sync::{Arc, Mutex};
+
+enum JobState {
+	Request,
+	Response(Result<Vec<IgnoredFile>>),
+}
+
+/// finds the ignored files of the workdir and their ignore rules
+#[derive(Clone)]
+pub struct AsyncIgnoredJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncIgnoredJob {
+	///
+	pub fn new() -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<Vec<IgnoredFile>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl Default for AsyncIgnoredJob {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl AsyncJob for AsyncIgnoredJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request => {
+					JobState::Response(get_ignored_files(CWD))
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::Ignored)
+	}
+}
@@ -29,6 +29,8 @@ mod commit_files;
 mod diff;
 mod error;
 mod fetch_job;
+mod grep_job;
+mod ignored_job;
 mod progress;
 mod pull;
 mod push;
@@ -46,6 +48,8 @@ pub use crate::{
 	diff::{AsyncDiff, DiffParams, DiffType},
 	error::{Error, Result},
 	fetch_job::AsyncFetchJob,
+	grep_job::AsyncGrepJob,
+	ignored_job::AsyncIgnoredJob,
 	progress::ProgressPercent,
 	pull::{AsyncPull, FetchRequest},
 	push::{AsyncPush, PushRequest},
@@ -92,6 +96,10 @@ pub enum AsyncGitNotification {
 	RemoteTags,
 	///
 	Fetch,
+	///
+	Grep,
+	///
+	Ignored,
 }
 
 /// current working directory `./`
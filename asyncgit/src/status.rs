@@ -1,7 +1,10 @@
 use crate::{
 	error::Result,
 	hash,
-	sync::{self, status::StatusType, ShowUntrackedFilesConfig},
+	sync::{
+		self, diff::DiffOptions, status::StatusType,
+		ShowUntrackedFilesConfig,
+	},
 	AsyncGitNotification, StatusItem, CWD,
 };
 use crossbeam_channel::Sender;
@@ -32,18 +35,21 @@ pub struct StatusParams {
 	tick: u128,
 	status_type: StatusType,
 	config: Option<ShowUntrackedFilesConfig>,
+	options: DiffOptions,
 }
 
 impl StatusParams {
-	///
+	/// `options` provides the rename/copy detection thresholds
 	pub fn new(
 		status_type: StatusType,
 		config: Option<ShowUntrackedFilesConfig>,
+		options: DiffOptions,
 	) -> Self {
 		Self {
 			tick: current_tick(),
 			status_type,
 			config,
+			options,
 		}
 	}
 }
@@ -115,6 +121,7 @@ impl AsyncStatus {
 		let arc_pending = Arc::clone(&self.pending);
 		let status_type = params.status_type;
 		let config = params.config;
+		let options = params.options;
 
 		self.pending.fetch_add(1, Ordering::Relaxed);
 
@@ -122,6 +129,7 @@ impl AsyncStatus {
 			let ok = Self::fetch_helper(
 				status_type,
 				config,
+				options,
 				hash_request,
 				&arc_current,
 				&arc_last,
@@ -143,11 +151,12 @@ impl AsyncStatus {
 	fn fetch_helper(
 		status_type: StatusType,
 		config: Option<ShowUntrackedFilesConfig>,
+		options: DiffOptions,
 		hash_request: u64,
 		arc_current: &Arc<Mutex<Request<u64, Status>>>,
 		arc_last: &Arc<Mutex<Status>>,
 	) -> Result<()> {
-		let res = Self::get_status(status_type, config)?;
+		let res = Self::get_status(status_type, config, options)?;
 		log::trace!(
 			"status fetched: {} (type: {:?})",
 			hash_request,
@@ -172,12 +181,14 @@ impl AsyncStatus {
 	fn get_status(
 		status_type: StatusType,
 		config: Option<ShowUntrackedFilesConfig>,
+		options: DiffOptions,
 	) -> Result<Status> {
 		Ok(Status {
-			items: sync::status::get_status(
+			items: sync::status::get_status_with_renames(
 				CWD,
 				status_type,
 				config,
+				options,
 			)?,
 		})
 	}
@@ -0,0 +1,357 @@
+//! apply patch files (like `git apply`)
+
+use super::utils::{repo, work_dir};
+use crate::error::{Error, Result};
+use git2::{ApplyOptions, Diff, Patch, Repository};
+use scopetime::scope_time;
+use std::{cell::Cell, fs, path::Path};
+
+/// where a patch is applied to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ApplyLocation {
+	///
+	WorkDir,
+	///
+	Index,
+	/// workdir and index (like `git apply --index`)
+	Both,
+}
+
+impl Default for ApplyLocation {
+	fn default() -> Self {
+		Self::WorkDir
+	}
+}
+
+impl From<ApplyLocation> for git2::ApplyLocation {
+	fn from(location: ApplyLocation) -> Self {
+		match location {
+			ApplyLocation::WorkDir => Self::WorkDir,
+			ApplyLocation::Index => Self::Index,
+			ApplyLocation::Both => Self::Both,
+		}
+	}
+}
+
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchHunk {
+	/// hunk header line (`@@ -1,2 +1,3 @@`)
+	pub header: String,
+	///
+	pub added: usize,
+	///
+	pub removed: usize,
+	/// hunk can be applied at the chosen location
+	pub applies: bool,
+}
+
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchFile {
+	///
+	pub path: String,
+	/// the file change applies (for changes without hunks like a new empty file)
+	pub applies: bool,
+	///
+	pub hunks: Vec<PatchHunk>,
+}
+
+impl PatchFile {
+	fn rejected(&self) -> bool {
+		!self.applies || self.hunks.iter().any(|h| !h.applies)
+	}
+}
+
+/// parses patch file `patch_file` and checks which of its hunks
+/// apply at `location` without touching anything
+pub fn patch_preview(
+	repo_path: &str,
+	patch_file: &Path,
+	location: ApplyLocation,
+) -> Result<Vec<PatchFile>> {
+	scope_time!("patch_preview");
+
+	let repo = repo(repo_path)?;
+	let diff = read_patch(&repo, patch_file)?;
+
+	check_patch(&repo, &diff, location)
+}
+
+/// applies all hunks of `patch_file` that apply cleanly at `location`
+/// (like `git apply --reject`).
+///
+/// returns the files containing rejected hunks (only listing those)
+pub fn apply_patch(
+	repo_path: &str,
+	patch_file: &Path,
+	location: ApplyLocation,
+) -> Result<Vec<PatchFile>> {
+	scope_time!("apply_patch");
+
+	let repo = repo(repo_path)?;
+	let diff = read_patch(&repo, patch_file)?;
+
+	let files = check_patch(&repo, &diff, location)?;
+
+	let any_applies = files
+		.iter()
+		.any(|f| f.applies && f.hunks.iter().any(|h| h.applies))
+		|| files.iter().any(|f| f.applies && f.hunks.is_empty());
+
+	if !any_applies {
+		return Err(Error::Generic(String::from(
+			"patch does not apply",
+		)));
+	}
+
+	{
+		let delta_idx = Cell::new(0_usize);
+		let current_delta = Cell::new(0_usize);
+		let hunk_idx = Cell::new(0_usize);
+
+		let mut opt = ApplyOptions::new();
+		opt.delta_callback(|_| {
+			let idx = delta_idx.get();
+			delta_idx.set(idx + 1);
+			current_delta.set(idx);
+			hunk_idx.set(0);
+
+			files.get(idx).map_or(false, |f| {
+				f.applies
+					&& (f.hunks.is_empty()
+						|| f.hunks.iter().any(|h| h.applies))
+			})
+		});
+		opt.hunk_callback(|_| {
+			let idx = hunk_idx.get();
+			hunk_idx.set(idx + 1);
+
+			files
+				.get(current_delta.get())
+				.and_then(|f| f.hunks.get(idx))
+				.map_or(false, |h| h.applies)
+		});
+
+		repo.apply(&diff, location.into(), Some(&mut opt))?;
+	}
+
+	Ok(files
+		.into_iter()
+		.filter(PatchFile::rejected)
+		.map(|mut f| {
+			f.hunks.retain(|h| !h.applies);
+			f
+		})
+		.collect())
+}
+
+/// reads a patch file, relative paths are interpreted relative to the repo workdir
+fn read_patch(
+	repo: &Repository,
+	patch_file: &Path,
+) -> Result<Diff<'static>> {
+	let patch_file = if patch_file.is_relative() {
+		work_dir(repo)?.join(patch_file)
+	} else {
+		patch_file.to_path_buf()
+	};
+
+	let content = fs::read(patch_file)?;
+
+	Ok(Diff::from_buffer(&content)?)
+}
+
+fn check_patch(
+	repo: &Repository,
+	diff: &Diff,
+	location: ApplyLocation,
+) -> Result<Vec<PatchFile>> {
+	let mut files = Vec::with_capacity(diff.deltas().len());
+
+	for (delta_idx, delta) in diff.deltas().enumerate() {
+		let path = delta
+			.new_file()
+			.path()
+			.or_else(|| delta.old_file().path())
+			.map(|p| p.to_string_lossy().into_owned())
+			.unwrap_or_default();
+
+		let mut hunks = Vec::new();
+
+		if let Some(patch) = Patch::from_diff(diff, delta_idx)? {
+			for hunk_idx in 0..patch.num_hunks() {
+				let (hunk, lines) = patch.hunk(hunk_idx)?;
+
+				let mut added = 0;
+				let mut removed = 0;
+				for line_idx in 0..lines {
+					match patch
+						.line_in_hunk(hunk_idx, line_idx)?
+						.origin()
+					{
+						'+' => added += 1,
+						'-' => removed += 1,
+						_ => (),
+					}
+				}
+
+				hunks.push(PatchHunk {
+					header: String::from_utf8_lossy(hunk.header())
+						.trim_end()
+						.to_string(),
+					added,
+					removed,
+					applies: check_applies(
+						repo,
+						diff,
+						location,
+						delta_idx,
+						Some(hunk_idx),
+					),
+				});
+			}
+		}
+
+		let applies = if hunks.is_empty() {
+			check_applies(repo, diff, location, delta_idx, None)
+		} else {
+			true
+		};
+
+		files.push(PatchFile {
+			path,
+			applies,
+			hunks,
+		});
+	}
+
+	Ok(files)
+}
+
+/// checks if a single delta (or a single hunk of it) applies
+fn check_applies(
+	repo: &Repository,
+	diff: &Diff,
+	location: ApplyLocation,
+	delta_idx: usize,
+	hunk_idx: Option<usize>,
+) -> bool {
+	let mut delta_count = 0;
+	let mut hunk_count = 0;
+
+	let mut opt = ApplyOptions::new();
+	opt.check(true);
+	opt.delta_callback(move |_| {
+		let take = delta_count == delta_idx;
+		delta_count += 1;
+		take
+	});
+	opt.hunk_callback(move |_| {
+		let take = hunk_idx.map_or(true, |idx| idx == hunk_count);
+		hunk_count += 1;
+		take
+	});
+
+	repo.apply(diff, location.into(), Some(&mut opt)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{apply_patch, patch_preview, ApplyLocation};
+	use crate::sync::{
+		commit, stage_add_file,
+		tests::{get_statuses, repo_init},
+	};
+	use std::{fs, path::Path};
+
+	const ORIGINAL: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+
+	const PATCH: &str = "diff --git a/foo.txt b/foo.txt
+--- a/foo.txt
++++ b/foo.txt
+@@ -1,3 +1,3 @@
+-a
++A
+ b
+ c
+@@ -11,3 +11,3 @@
+ k
+-l
++x
+ m
+";
+
+	#[test]
+	fn test_apply_patch_with_rejects() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("foo.txt"), ORIGINAL).unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		commit(repo_path, "add foo").unwrap();
+
+		// make the second hunk fail
+		fs::write(root.join("foo.txt"), ORIGINAL.replace('l', "L"))
+			.unwrap();
+		fs::write(root.join("p.diff"), PATCH).unwrap();
+
+		let preview = patch_preview(
+			repo_path,
+			Path::new("p.diff"),
+			ApplyLocation::WorkDir,
+		)
+		.unwrap();
+
+		assert_eq!(preview.len(), 1);
+		assert_eq!(preview[0].path, "foo.txt");
+		assert_eq!(preview[0].hunks.len(), 2);
+		assert!(preview[0].hunks[0].applies);
+		assert!(!preview[0].hunks[1].applies);
+		assert_eq!(preview[0].hunks[0].added, 1);
+		assert_eq!(preview[0].hunks[0].removed, 1);
+
+		let rejected = apply_patch(
+			repo_path,
+			Path::new("p.diff"),
+			ApplyLocation::WorkDir,
+		)
+		.unwrap();
+
+		assert_eq!(rejected.len(), 1);
+		assert_eq!(rejected[0].hunks.len(), 1);
+		assert_eq!(rejected[0].hunks[0].header, "@@ -11,3 +11,3 @@");
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			ORIGINAL.replace('l', "L").replace('a', "A")
+		);
+	}
+
+	#[test]
+	fn test_apply_patch_to_index() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("foo.txt"), ORIGINAL).unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		commit(repo_path, "add foo").unwrap();
+
+		let patch_file = root.join("p.diff");
+		fs::write(&patch_file, PATCH).unwrap();
+
+		let rejected =
+			apply_patch(repo_path, &patch_file, ApplyLocation::Index)
+				.unwrap();
+
+		assert!(rejected.is_empty());
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			ORIGINAL
+		);
+		// staged change and its reverse in workdir (plus `p.diff`)
+		assert_eq!(get_statuses(repo_path), (2, 1));
+	}
+}
@@ -1,15 +1,95 @@
 //! Sync git API for fetching a file blame
 
-use super::{utils, CommitId};
+use super::{config::get_config_string_repo, utils, CommitId};
 use crate::{
 	error::{Error, Result},
 	sync::get_commits_info,
 };
+use git2::{Blame, Commit, Patch, Repository};
 use scopetime::scope_time;
 use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
+/// minimum number of alphanumeric chars a line needs to be followed
+/// as a move or copy, mirrors the default score `git blame -M` uses
+const MOVED_LINE_MIN_ALNUM: usize = 20;
+
+/// upper bound of commits a single line is followed through
+const MAX_LINE_PASSES: usize = 64;
+
+/// chars of the commit summary kept per hunk
+const SUMMARY_LENGTH: usize = 100;
+
+/// lines resolved per step of `blame_file_incremental`
+const BLAME_CHUNK_LINES: usize = 500;
+
+/// how far `blame_file_incremental` got
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlameProgress {
+	///
+	pub lines_done: usize,
+	///
+	pub lines_total: usize,
+}
+
+impl BlameProgress {
+	/// share of blamed lines in percent
+	pub fn percent(&self) -> usize {
+		(self.lines_done * 100)
+			.checked_div(self.lines_total)
+			.unwrap_or(100)
+	}
+}
+
+/// how far `blame_file` follows lines that were moved or copied
+#[derive(Clone, Copy, Hash, Debug, PartialEq, Eq)]
+pub enum BlameDetection {
+	/// attribute lines to the commit that last touched them
+	None,
+	/// follow lines moved within the file (like `git blame -M`)
+	Moves,
+	/// also follow lines moved or copied from other files changed
+	/// in the same commit (like `git blame -C`)
+	Copies,
+}
+
+impl Default for BlameDetection {
+	fn default() -> Self {
+		Self::None
+	}
+}
+
+impl BlameDetection {
+	/// next mode in the cycle `None` -> `Moves` -> `Copies`
+	#[must_use]
+	pub const fn next(self) -> Self {
+		match self {
+			Self::None => Self::Moves,
+			Self::Moves => Self::Copies,
+			Self::Copies => Self::None,
+		}
+	}
+}
+
+/// options for `blame_file`
+#[derive(Clone, Copy, Hash, Debug, PartialEq, Eq)]
+pub struct BlameOptions {
+	/// skip commits listed in the file `blame.ignoreRevsFile` points to
+	pub ignore_revs: bool,
+	///
+	pub detection: BlameDetection,
+}
+
+impl Default for BlameOptions {
+	fn default() -> Self {
+		Self {
+			ignore_revs: true,
+			detection: BlameDetection::default(),
+		}
+	}
+}
+
 /// A `BlameHunk` contains all the information that will be shown to the user.
 #[derive(Clone, Hash, Debug, PartialEq, Eq)]
 pub struct BlameHunk {
@@ -19,12 +99,30 @@ pub struct BlameHunk {
 	pub author: String,
 	///
 	pub time: i64,
+	/// first line of the commit message
+	pub summary: String,
 	/// `git2::BlameHunk::final_start_line` returns 1-based indices, but
 	/// `start_line` is 0-based because the `Vec` storing the lines starts at
 	/// index 0.
 	pub start_line: usize,
 	///
 	pub end_line: usize,
+	/// path of the file in `commit_id`
+	pub orig_path: String,
+	/// 0-based first line of the hunk in `commit_id`
+	pub orig_start_line: usize,
+}
+
+/// where a blamed line came from in the parent of the commit that
+/// last changed it, used to continue blaming further back in history
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlameOrigin {
+	/// parent commit to blame
+	pub commit_id: CommitId,
+	/// path of the file in the changing commit
+	pub path: String,
+	/// 0-based line in the changing commit
+	pub line: usize,
 }
 
 /// A `BlameFile` represents a collection of lines. This is targeted at how the
@@ -52,16 +150,134 @@ fn fixup_windows_path(path: &str) -> String {
 	}
 }
 
-///
+/// blames `file_path` as of `commit_id` (HEAD if `None`)
 pub fn blame_file(
 	repo_path: &str,
 	file_path: &str,
+	commit_id: Option<CommitId>,
+	options: BlameOptions,
 ) -> Result<FileBlame> {
 	scope_time!("blame_file");
 
+	blame_file_incremental(
+		repo_path,
+		file_path,
+		commit_id,
+		options,
+		|_, _| true,
+	)?
+	.ok_or_else(|| Error::Generic("blame cancelled".into()))
+}
+
+/// blames `file_path` in a single pass
+///
+/// the result is resolved in chunks of `BLAME_CHUNK_LINES` lines, after
+/// each chunk `progress` gets the partial blame and cancels by
+/// returning `false`, in which case `None` is returned
+pub fn blame_file_incremental<F>(
+	repo_path: &str,
+	file_path: &str,
+	commit_id: Option<CommitId>,
+	options: BlameOptions,
+	mut progress: F,
+) -> Result<Option<FileBlame>>
+where
+	F: FnMut(&FileBlame, BlameProgress) -> bool,
+{
+	scope_time!("blame_file_incremental");
+
 	let repo = utils::repo(repo_path)?;
+	let mut file_blame = unblamed_file(&repo, file_path, commit_id)?;
+
+	let ignored = if options.ignore_revs {
+		ignored_revs(&repo)?
+	} else {
+		HashSet::new()
+	};
 
-	let commit_id = utils::get_head_repo(&repo)?;
+	let mut tracker = (!ignored.is_empty()
+		|| options.detection != BlameDetection::None)
+		.then(|| LineTracker {
+			repo: &repo,
+			repo_path,
+			detection: options.detection,
+			ignored,
+			blames: HashMap::new(),
+			changes: HashMap::new(),
+		});
+
+	let blame = blame_commit(&repo, &file_blame)?;
+
+	let lines_total = file_blame.lines.len();
+	let mut start = 0;
+	while start < lines_total {
+		let end = (start + BLAME_CHUNK_LINES).min(lines_total);
+
+		blame_lines(repo_path, &blame, &mut file_blame, start, end)?;
+
+		if let Some(tracker) = tracker.as_mut() {
+			for (index, (hunk, content)) in file_blame
+				.lines
+				.iter_mut()
+				.enumerate()
+				.take(end)
+				.skip(start)
+			{
+				if let Some(current) = hunk {
+					if let Some(tracked) =
+						tracker.follow(current, index, content)?
+					{
+						*current = tracked;
+					}
+				}
+			}
+		}
+
+		start = end;
+
+		if !progress(
+			&file_blame,
+			BlameProgress {
+				lines_done: end,
+				lines_total,
+			},
+		) {
+			return Ok(None);
+		}
+	}
+
+	Ok(Some(file_blame))
+}
+
+/// blame as libgit2 reports it, without ignored revisions or
+/// move/copy detection
+fn blame_file_plain(
+	repo_path: &str,
+	file_path: &str,
+	commit_id: Option<CommitId>,
+) -> Result<FileBlame> {
+	scope_time!("blame_file_plain");
+
+	let repo = utils::repo(repo_path)?;
+	let mut file_blame = unblamed_file(&repo, file_path, commit_id)?;
+	let blame = blame_commit(&repo, &file_blame)?;
+	let lines_total = file_blame.lines.len();
+
+	blame_lines(repo_path, &blame, &mut file_blame, 0, lines_total)?;
+
+	Ok(file_blame)
+}
+
+/// lines of `file_path` as of `commit_id` without any blame yet
+fn unblamed_file(
+	repo: &Repository,
+	file_path: &str,
+	commit_id: Option<CommitId>,
+) -> Result<FileBlame> {
+	let commit_id = match commit_id {
+		Some(id) => id,
+		None => utils::get_head_repo(repo)?,
+	};
 
 	let spec = format!(
 		"{}:{}",
@@ -76,67 +292,476 @@ pub fn blame_file(
 		return Err(Error::NoBlameOnBinaryFile);
 	}
 
-	let blame = repo.blame_file(Path::new(file_path), None)?;
-
 	let reader = BufReader::new(blob.content());
 
-	let unique_commit_ids: HashSet<_> = blame
-		.iter()
+	Ok(FileBlame {
+		commit_id,
+		path: file_path.into(),
+		lines: reader
+			.lines()
+			.map(|line| (None, line.unwrap_or_default()))
+			.collect(),
+	})
+}
+
+/// libgit2 blame of `file_blame` as of its commit
+fn blame_commit<'a>(
+	repo: &'a Repository,
+	file_blame: &FileBlame,
+) -> Result<Blame<'a>> {
+	let mut opts = git2::BlameOptions::new();
+	opts.newest_commit(file_blame.commit_id.into());
+
+	Ok(repo
+		.blame_file(Path::new(&file_blame.path), Some(&mut opts))?)
+}
+
+/// fills the lines `start..end` (0-based) of `file_blame` from `blame`
+fn blame_lines(
+	repo_path: &str,
+	blame: &Blame,
+	file_blame: &mut FileBlame,
+	start: usize,
+	end: usize,
+) -> Result<()> {
+	// Line indices in a `Blame` are 1-based.
+	let unique_commit_ids: HashSet<_> = (start + 1..=end)
+		.filter_map(|line| blame.get_line(line))
 		.map(|hunk| CommitId::new(hunk.final_commit_id()))
 		.collect();
 	let mut commit_ids = Vec::with_capacity(unique_commit_ids.len());
 	commit_ids.extend(unique_commit_ids);
 
-	let commit_infos = get_commits_info(repo_path, &commit_ids, 0)?;
+	let commit_infos =
+		get_commits_info(repo_path, &commit_ids, SUMMARY_LENGTH)?;
 	let unique_commit_infos: HashMap<_, _> = commit_infos
 		.iter()
 		.map(|commit_info| (commit_info.id, commit_info))
 		.collect();
 
-	let lines: Vec<(Option<BlameHunk>, String)> = reader
-		.lines()
+	let file_path = file_blame.path.clone();
+	for (i, (blame_hunk, _)) in file_blame
+		.lines
+		.iter_mut()
 		.enumerate()
-		.map(|(i, line)| {
-			// Line indices in a `FileBlame` are 1-based.
-			let corresponding_hunk = blame.get_line(i + 1);
-
-			if let Some(hunk) = corresponding_hunk {
-				let commit_id = CommitId::new(hunk.final_commit_id());
-				// Line indices in a `BlameHunk` are 1-based.
-				let start_line =
-					hunk.final_start_line().saturating_sub(1);
-				let end_line =
-					start_line.saturating_add(hunk.lines_in_hunk());
-
-				if let Some(commit_info) =
-					unique_commit_infos.get(&commit_id)
-				{
-					let hunk = BlameHunk {
-						commit_id,
-						author: commit_info.author.clone(),
-						time: commit_info.time,
-						start_line,
-						end_line,
-					};
-
-					return (
-						Some(hunk),
-						line.unwrap_or_else(|_| "".into()),
-					);
+		.take(end)
+		.skip(start)
+	{
+		// Line indices in a `FileBlame` are 1-based.
+		let hunk = match blame.get_line(i + 1) {
+			Some(hunk) => hunk,
+			None => continue,
+		};
+
+		let commit_id = CommitId::new(hunk.final_commit_id());
+		// Line indices in a `BlameHunk` are 1-based.
+		let start_line = hunk.final_start_line().saturating_sub(1);
+		let end_line =
+			start_line.saturating_add(hunk.lines_in_hunk());
+
+		if let Some(commit_info) = unique_commit_infos.get(&commit_id)
+		{
+			*blame_hunk = Some(BlameHunk {
+				commit_id,
+				author: commit_info.author.clone(),
+				time: commit_info.time,
+				summary: commit_info.message.clone(),
+				start_line,
+				end_line,
+				orig_path: hunk.path().map_or_else(
+					|| file_path.clone(),
+					|p| p.to_string_lossy().to_string(),
+				),
+				orig_start_line: hunk
+					.orig_start_line()
+					.saturating_sub(1),
+			});
+		}
+	}
+
+	Ok(())
+}
+
+/// commits listed in the file `blame.ignoreRevsFile` points to,
+/// relative paths are resolved against the working directory
+fn ignored_revs(repo: &Repository) -> Result<HashSet<CommitId>> {
+	let file =
+		match get_config_string_repo(repo, "blame.ignoreRevsFile")? {
+			Some(file) if !file.is_empty() => file,
+			_ => return Ok(HashSet::new()),
+		};
+
+	let content = match std::fs::read_to_string(
+		utils::work_dir(repo)?.join(file),
+	) {
+		Ok(content) => content,
+		Err(_) => return Ok(HashSet::new()),
+	};
+
+	Ok(content
+		.lines()
+		.filter_map(|line| {
+			let rev =
+				line.split('#').next().unwrap_or_default().trim();
+			if rev.is_empty() {
+				return None;
+			}
+			repo.revparse_single(rev)
+				.and_then(|object| object.peel_to_commit())
+				.ok()
+				.map(|commit| CommitId::new(commit.id()))
+		})
+		.collect())
+}
+
+/// a hunk of a zero-context diff between a commit and its parent,
+/// line numbers are 0-based
+struct ChangedHunk {
+	old_path: String,
+	new_path: String,
+	/// first line at or after the hunk in the old file
+	old_begin: usize,
+	old_lines: usize,
+	/// first line at or after the hunk in the new file
+	new_begin: usize,
+	new_lines: usize,
+	removed: Vec<(usize, String)>,
+	added: Vec<usize>,
+}
+
+fn normalize_line(line: &str) -> String {
+	line.split_whitespace().collect()
+}
+
+fn changed_hunks(
+	repo: &Repository,
+	commit: &Commit,
+	parent: &Commit,
+	pathspec: Option<&str>,
+) -> Result<Vec<ChangedHunk>> {
+	let mut opts = git2::DiffOptions::new();
+	opts.context_lines(0);
+	if let Some(pathspec) = pathspec {
+		opts.pathspec(pathspec).disable_pathspec_match(true);
+	}
+
+	let diff = repo.diff_tree_to_tree(
+		Some(&parent.tree()?),
+		Some(&commit.tree()?),
+		Some(&mut opts),
+	)?;
+
+	let mut hunks = Vec::new();
+	for delta_index in 0..diff.deltas().len() {
+		let patch = match Patch::from_diff(&diff, delta_index)? {
+			Some(patch) => patch,
+			None => continue,
+		};
+
+		let delta = patch.delta();
+		let path_of = |file: git2::DiffFile| {
+			file.path().map_or_else(String::new, |p| {
+				p.to_string_lossy().to_string()
+			})
+		};
+		let old_path = path_of(delta.old_file());
+		let new_path = path_of(delta.new_file());
+
+		for hunk_index in 0..patch.num_hunks() {
+			let (hunk, line_count) = patch.hunk(hunk_index)?;
+
+			// an empty side of a hunk starts *after* the given line
+			let begin = |start: u32, lines: u32| {
+				if lines == 0 {
+					start as usize
+				} else {
+					start.saturating_sub(1) as usize
+				}
+			};
+
+			let mut changed = ChangedHunk {
+				old_path: old_path.clone(),
+				new_path: new_path.clone(),
+				old_begin: begin(hunk.old_start(), hunk.old_lines()),
+				old_lines: hunk.old_lines() as usize,
+				new_begin: begin(hunk.new_start(), hunk.new_lines()),
+				new_lines: hunk.new_lines() as usize,
+				removed: Vec::new(),
+				added: Vec::new(),
+			};
+
+			for line_index in 0..line_count {
+				let line =
+					patch.line_in_hunk(hunk_index, line_index)?;
+				match (
+					line.origin(),
+					line.old_lineno(),
+					line.new_lineno(),
+				) {
+					('-', Some(old), _) => changed.removed.push((
+						old.saturating_sub(1) as usize,
+						String::from_utf8_lossy(line.content())
+							.to_string(),
+					)),
+					('+', _, Some(new)) => changed
+						.added
+						.push(new.saturating_sub(1) as usize),
+					_ => (),
 				}
 			}
 
-			(None, line.unwrap_or_else(|_| "".into()))
-		})
-		.collect();
+			hunks.push(changed);
+		}
+	}
 
-	let file_blame = FileBlame {
-		commit_id,
-		path: file_path.into(),
-		lines,
+	Ok(hunks)
+}
+
+/// line in the parent that `line` of `path` corresponds to, lines the
+/// commit changed are matched by content and otherwise by position
+fn map_line_to_parent(
+	hunks: &[ChangedHunk],
+	path: &str,
+	line: usize,
+	content: &str,
+) -> Option<(String, usize)> {
+	let mut old_path = path.to_string();
+	let mut added = 0;
+	let mut removed = 0;
+
+	for hunk in hunks.iter().filter(|hunk| hunk.new_path == path) {
+		old_path.clone_from(&hunk.old_path);
+
+		if line < hunk.new_begin {
+			break;
+		}
+
+		if line < hunk.new_begin + hunk.new_lines {
+			let normalized = normalize_line(content);
+			let old_line = hunk
+				.removed
+				.iter()
+				.find(|(_, removed)| {
+					normalize_line(removed) == normalized
+				})
+				.map(|(old_line, _)| *old_line)
+				.or_else(|| {
+					(hunk.old_lines > 0).then(|| {
+						hunk.old_begin
+							+ (line - hunk.new_begin)
+								.min(hunk.old_lines - 1)
+					})
+				})?;
+
+			return Some((old_path, old_line));
+		}
+
+		added += hunk.new_lines;
+		removed += hunk.old_lines;
+	}
+
+	Some((old_path, (line + removed).saturating_sub(added)))
+}
+
+/// removed line that `line` of `path` was moved or copied from, other
+/// files are only searched if there is no match in `path` itself
+fn find_moved_line(
+	hunks: &[ChangedHunk],
+	path: &str,
+	line: usize,
+	content: &str,
+) -> Option<(String, usize)> {
+	let normalized = normalize_line(content);
+	if normalized.chars().filter(|c| c.is_alphanumeric()).count()
+		< MOVED_LINE_MIN_ALNUM
+	{
+		return None;
+	}
+
+	let added = hunks.iter().any(|hunk| {
+		hunk.new_path == path && hunk.added.contains(&line)
+	});
+	if !added {
+		return None;
+	}
+
+	let find_in = |same_file: bool| {
+		hunks
+			.iter()
+			.filter(|hunk| (hunk.new_path == path) == same_file)
+			.find_map(|hunk| {
+				hunk.removed
+					.iter()
+					.find(|(_, removed)| {
+						normalize_line(removed) == normalized
+					})
+					.map(|(old_line, _)| {
+						(hunk.old_path.clone(), *old_line)
+					})
+			})
 	};
 
-	Ok(file_blame)
+	find_in(true).or_else(|| find_in(false))
+}
+
+/// follows lines past ignored commits and moves/copies
+struct LineTracker<'a> {
+	repo: &'a Repository,
+	repo_path: &'a str,
+	detection: BlameDetection,
+	ignored: HashSet<CommitId>,
+	blames: HashMap<(CommitId, String), Option<FileBlame>>,
+	changes: HashMap<(CommitId, Option<String>), Vec<ChangedHunk>>,
+}
+
+impl LineTracker<'_> {
+	/// hunk for `line` once followed as far back as possible,
+	/// `None` if `hunk` already is the origin
+	fn follow(
+		&mut self,
+		hunk: &BlameHunk,
+		line: usize,
+		content: &str,
+	) -> Result<Option<BlameHunk>> {
+		let mut commit_id = hunk.commit_id;
+		let mut path = hunk.orig_path.clone();
+		let mut orig_line = hunk.orig_start_line
+			+ line.saturating_sub(hunk.start_line);
+		let mut content = content.to_string();
+		let mut tracked = None;
+
+		for _ in 0..MAX_LINE_PASSES {
+			let origin = match self
+				.origin(commit_id, &path, orig_line, &content)?
+			{
+				Some(origin) => origin,
+				None => break,
+			};
+
+			let parent_line = self
+				.blame(origin.commit_id, &origin.path)
+				.and_then(|blame| blame.lines.get(origin.line))
+				.and_then(|(hunk, text)| {
+					hunk.clone().map(|hunk| (hunk, text.clone()))
+				});
+
+			let (parent_hunk, text) = match parent_line {
+				Some(parent_line) => parent_line,
+				None => break,
+			};
+
+			commit_id = parent_hunk.commit_id;
+			path.clone_from(&parent_hunk.orig_path);
+			orig_line = parent_hunk.orig_start_line
+				+ origin.line.saturating_sub(parent_hunk.start_line);
+			content = text;
+
+			tracked = Some(BlameHunk {
+				start_line: line,
+				end_line: line + 1,
+				orig_path: path.clone(),
+				orig_start_line: orig_line,
+				..parent_hunk
+			});
+		}
+
+		Ok(tracked)
+	}
+
+	/// where in the parent of `commit_id` to continue blaming `line`
+	fn origin(
+		&mut self,
+		commit_id: CommitId,
+		path: &str,
+		line: usize,
+		content: &str,
+	) -> Result<Option<BlameOrigin>> {
+		let ignored = self.ignored.contains(&commit_id);
+		let scope = match (ignored, self.detection) {
+			(false, BlameDetection::None) => return Ok(None),
+			(true, _) | (false, BlameDetection::Moves) => {
+				Some(path.to_string())
+			}
+			(false, BlameDetection::Copies) => None,
+		};
+
+		let commit = self.repo.find_commit(commit_id.into())?;
+		let parent = match commit.parent(0) {
+			Ok(parent) => parent,
+			Err(_) => return Ok(None),
+		};
+
+		let key = (commit_id, scope);
+		if !self.changes.contains_key(&key) {
+			let hunks = changed_hunks(
+				self.repo,
+				&commit,
+				&parent,
+				key.1.as_deref(),
+			)?;
+			self.changes.insert(key.clone(), hunks);
+		}
+		let hunks = &self.changes[&key];
+
+		let origin = if ignored {
+			map_line_to_parent(hunks, path, line, content)
+		} else {
+			find_moved_line(hunks, path, line, content)
+		};
+
+		Ok(origin.map(|(path, line)| BlameOrigin {
+			commit_id: CommitId::new(parent.id()),
+			path,
+			line,
+		}))
+	}
+
+	fn blame(
+		&mut self,
+		commit_id: CommitId,
+		path: &str,
+	) -> Option<&FileBlame> {
+		let key = (commit_id, path.to_string());
+		if !self.blames.contains_key(&key) {
+			let blame = blame_file_plain(
+				self.repo_path,
+				path,
+				Some(commit_id),
+			)
+			.ok();
+			self.blames.insert(key.clone(), blame);
+		}
+
+		self.blames[&key].as_ref()
+	}
+}
+
+/// position of `line` (0-based) of `file_blame` in the parent of the
+/// commit that last changed it, `None` if that commit has no parent
+pub fn blame_origin_in_parent(
+	repo_path: &str,
+	file_blame: &FileBlame,
+	line: usize,
+) -> Result<Option<BlameOrigin>> {
+	scope_time!("blame_origin_in_parent");
+
+	let hunk = file_blame
+		.lines
+		.get(line)
+		.and_then(|(hunk, _)| hunk.as_ref())
+		.ok_or_else(|| {
+			Error::Generic(format!("no blame for line {}", line))
+		})?;
+
+	let repo = utils::repo(repo_path)?;
+	let commit = repo.find_commit(hunk.commit_id.into())?;
+
+	Ok(commit.parent_id(0).ok().map(|parent| BlameOrigin {
+		commit_id: CommitId::new(parent),
+		path: hunk.orig_path.clone(),
+		line: hunk.orig_start_line
+			+ line.saturating_sub(hunk.start_line),
+	}))
 }
 
 #[cfg(test)]
@@ -144,7 +769,8 @@ mod tests {
 	use super::*;
 	use crate::error::Result;
 	use crate::sync::{
-		commit, stage_add_file, tests::repo_init_empty,
+		commit, stage_add_file,
+		tests::{repo_init_empty, write_commit_file},
 	};
 	use std::{
 		fs::{File, OpenOptions},
@@ -159,7 +785,15 @@ mod tests {
 		let root = repo.path().parent().unwrap();
 		let repo_path = root.as_os_str().to_str().unwrap();
 
-		assert!(matches!(blame_file(&repo_path, "foo"), Err(_)));
+		assert!(matches!(
+			blame_file(
+				&repo_path,
+				"foo",
+				None,
+				BlameOptions::default()
+			),
+			Err(_)
+		));
 
 		File::create(&root.join(file_path))?
 			.write_all(b"line 1\n")?;
@@ -167,7 +801,12 @@ mod tests {
 		stage_add_file(repo_path, file_path)?;
 		commit(repo_path, "first commit")?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(
+			&repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)?;
 
 		assert!(matches!(
 			blame.lines.as_slice(),
@@ -191,7 +830,12 @@ mod tests {
 		stage_add_file(repo_path, file_path)?;
 		commit(repo_path, "second commit")?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(
+			&repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)?;
 
 		assert!(matches!(
 			blame.lines.as_slice(),
@@ -218,20 +862,285 @@ mod tests {
 
 		file.write(b"line 3\n")?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(
+			&repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)?;
 
 		assert_eq!(blame.lines.len(), 2);
 
 		stage_add_file(repo_path, file_path)?;
 		commit(repo_path, "third commit")?;
 
-		let blame = blame_file(&repo_path, "foo")?;
+		let blame = blame_file(
+			&repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)?;
 
 		assert_eq!(blame.lines.len(), 3);
 
 		Ok(())
 	}
 
+	#[test]
+	fn test_blame_parent() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "foo", "a\nb\n", "c1");
+		let c2 = write_commit_file(&repo, "foo", "a\nB\n", "c2");
+		write_commit_file(&repo, "foo", "x\na\nB\n", "c3");
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(blame.lines[2].0.as_ref().unwrap().commit_id, c2);
+		assert_eq!(blame.lines[2].0.as_ref().unwrap().summary, "c2");
+		assert_eq!(
+			blame.lines[2].0.as_ref().unwrap().orig_start_line,
+			1
+		);
+
+		let origin =
+			blame_origin_in_parent(repo_path, &blame, 2).unwrap();
+		assert_eq!(
+			origin,
+			Some(BlameOrigin {
+				commit_id: c1,
+				path: String::from("foo"),
+				line: 1,
+			})
+		);
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			Some(c1),
+			BlameOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(blame.commit_id, c1);
+		assert_eq!(blame.lines[1].1, "b");
+		assert_eq!(
+			blame_origin_in_parent(repo_path, &blame, 1).unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn test_blame_ignore_revs() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 =
+			write_commit_file(&repo, "foo", "a\nb(x,y)\nc\n", "c1");
+		let c2 = write_commit_file(
+			&repo,
+			"foo",
+			"new\na\nb(x, y)\nc\n",
+			"c2",
+		);
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(blame.lines[2].0.as_ref().unwrap().commit_id, c2);
+
+		std::fs::write(
+			root.join(".git-blame-ignore-revs"),
+			format!("# reformat\n{}\n", c2.to_string()),
+		)
+		.unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("blame.ignoreRevsFile", ".git-blame-ignore-revs")
+			.unwrap();
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+		)
+		.unwrap();
+		let hunk = blame.lines[2].0.as_ref().unwrap();
+		assert_eq!(hunk.commit_id, c1);
+		assert_eq!(hunk.orig_start_line, 1);
+		// lines only added by an ignored commit stay with it
+		assert_eq!(blame.lines[0].0.as_ref().unwrap().commit_id, c2);
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			None,
+			BlameOptions {
+				ignore_revs: false,
+				..BlameOptions::default()
+			},
+		)
+		.unwrap();
+		assert_eq!(blame.lines[2].0.as_ref().unwrap().commit_id, c2);
+	}
+
+	#[test]
+	fn test_blame_detect_moves_and_copies() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let first = "let first_value = compute_something();\n";
+		let second = "let second_value = compute_other_thing();\n";
+
+		let c1 = write_commit_file(
+			&repo,
+			"foo",
+			&format!("{}{}", first, second),
+			"c1",
+		);
+		let c2 = write_commit_file(
+			&repo,
+			"foo",
+			&format!("{}{}", second, first),
+			"c2",
+		);
+
+		let options = |detection| BlameOptions {
+			ignore_revs: false,
+			detection,
+		};
+		let commits = |blame: &FileBlame| {
+			blame
+				.lines
+				.iter()
+				.map(|(hunk, _)| hunk.as_ref().unwrap().commit_id)
+				.collect::<Vec<_>>()
+		};
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			None,
+			options(BlameDetection::None),
+		)
+		.unwrap();
+		assert!(commits(&blame).contains(&c2));
+
+		let blame = blame_file(
+			repo_path,
+			"foo",
+			None,
+			options(BlameDetection::Moves),
+		)
+		.unwrap();
+		assert_eq!(commits(&blame), vec![c1, c1]);
+
+		write_commit_file(&repo, "bar", "}\n", "c3");
+		std::fs::write(root.join("bar"), format!("{}}}\n", first))
+			.unwrap();
+		std::fs::write(root.join("foo"), second).unwrap();
+		stage_add_file(repo_path, Path::new("bar")).unwrap();
+		stage_add_file(repo_path, Path::new("foo")).unwrap();
+		let c4 = commit(repo_path, "c4").unwrap();
+
+		let blame = blame_file(
+			repo_path,
+			"bar",
+			None,
+			options(BlameDetection::Moves),
+		)
+		.unwrap();
+		assert_eq!(blame.lines[0].0.as_ref().unwrap().commit_id, c4);
+
+		let blame = blame_file(
+			repo_path,
+			"bar",
+			None,
+			options(BlameDetection::Copies),
+		)
+		.unwrap();
+		let hunk = blame.lines[0].0.as_ref().unwrap();
+		assert_eq!(hunk.commit_id, c1);
+		assert_eq!(hunk.orig_path, "foo");
+		assert_eq!(hunk.orig_start_line, 0);
+	}
+
+	#[test]
+	fn test_blame_incremental() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let content: String =
+			(0..1200).map(|i| i.to_string() + "\n").collect();
+		let c1 = write_commit_file(&repo, "foo", &content, "c1");
+		let c2 = write_commit_file(
+			&repo,
+			"foo",
+			&content.replace("\n700\n", "\nchanged\n"),
+			"c2",
+		);
+
+		let mut steps = Vec::new();
+		let blame = blame_file_incremental(
+			repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+			|partial, progress| {
+				steps.push(progress.lines_done);
+				assert!(partial.lines[..progress.lines_done]
+					.iter()
+					.all(|(hunk, _)| hunk.is_some()));
+				assert!(partial.lines[progress.lines_done..]
+					.iter()
+					.all(|(hunk, _)| hunk.is_none()));
+				true
+			},
+		)
+		.unwrap()
+		.unwrap();
+
+		assert_eq!(steps, vec![500, 1000, 1200]);
+		assert_eq!(
+			blame.lines[700].0.as_ref().unwrap().commit_id,
+			c2
+		);
+		assert_eq!(
+			blame.lines[1199].0.as_ref().unwrap().commit_id,
+			c1
+		);
+
+		let mut calls = 0;
+		let cancelled = blame_file_incremental(
+			repo_path,
+			"foo",
+			None,
+			BlameOptions::default(),
+			|_, _| {
+				calls += 1;
+				false
+			},
+		)
+		.unwrap();
+
+		assert!(cancelled.is_none());
+		assert_eq!(calls, 1);
+	}
+
 	#[test]
 	fn test_blame_windows_path_dividers() {
 		let file_path = Path::new("bar\\foo");
@@ -249,6 +1158,12 @@ mod tests {
 		stage_add_file(repo_path, file_path).unwrap();
 		commit(repo_path, "first commit").unwrap();
 
-		assert!(blame_file(&repo_path, "bar\\foo").is_ok());
+		assert!(blame_file(
+			&repo_path,
+			"bar\\foo",
+			None,
+			BlameOptions::default()
+		)
+		.is_ok());
 	}
 }
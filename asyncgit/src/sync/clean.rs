@@ -0,0 +1,284 @@
+//! remove untracked and ignored files (like `git clean`)
+
+use super::{
+	status::{
+		get_ignored, get_status, StatusItem, StatusItemType,
+		StatusType,
+	},
+	utils::{repo, work_dir},
+	ShowUntrackedFilesConfig,
+};
+use crate::error::{Error, Result};
+use scopetime::scope_time;
+use std::{
+	fs,
+	path::{Component, Path},
+};
+
+/// untracked files (and optionally ignored files and folders) that
+/// `clean_paths` would remove, sorted by path
+pub fn get_clean_candidates(
+	repo_path: &str,
+	include_ignored: bool,
+) -> Result<Vec<StatusItem>> {
+	scope_time!("get_clean_candidates");
+
+	let mut res = get_status(
+		repo_path,
+		StatusType::WorkingDir,
+		Some(ShowUntrackedFilesConfig::All),
+	)?
+	.into_iter()
+	.filter(|item| item.status == StatusItemType::New)
+	.collect::<Vec<_>>();
+
+	if include_ignored {
+		res.extend(get_ignored(repo_path)?);
+
+		res.sort_by(|a, b| {
+			Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+		});
+	}
+
+	Ok(res)
+}
+
+/// deletes untracked/ignored `paths` from the workdir and removes
+/// directories left empty by that.
+///
+/// tracked paths, folders containing tracked files and nested
+/// repositories are refused (like `git clean` without `-ff`),
+/// nothing is deleted then
+pub fn clean_paths(repo_path: &str, paths: &[String]) -> Result<()> {
+	scope_time!("clean_paths");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let index = repo.index()?;
+
+	for path in paths {
+		let rel_path = Path::new(path);
+
+		if !rel_path
+			.components()
+			.all(|c| matches!(c, Component::Normal(_)))
+		{
+			return Err(Error::Generic(format!(
+				"invalid path to clean: '{}'",
+				path
+			)));
+		}
+
+		let dir_prefix = format!("{}/", path.trim_end_matches('/'));
+		if index.get_path(rel_path, 0).is_some()
+			|| index.iter().any(|entry| {
+				entry.path.starts_with(dir_prefix.as_bytes())
+			}) {
+			return Err(Error::Generic(format!(
+				"refusing to clean tracked file: '{}'",
+				path
+			)));
+		}
+
+		let full_path = work_dir.join(rel_path);
+		if full_path.is_dir() && contains_repo(&full_path)? {
+			return Err(Error::Generic(format!(
+				"refusing to clean nested repository: '{}'",
+				path
+			)));
+		}
+	}
+
+	for path in paths {
+		let full_path = work_dir.join(path);
+
+		if full_path.is_dir() {
+			fs::remove_dir_all(&full_path)?;
+		} else if full_path.symlink_metadata().is_ok() {
+			fs::remove_file(&full_path)?;
+		}
+
+		remove_empty_parents(work_dir, &full_path);
+	}
+
+	Ok(())
+}
+
+/// `dir` or any folder below it has a `.git` (without following
+/// symlinks)
+fn contains_repo(dir: &Path) -> Result<bool> {
+	for entry in fs::read_dir(dir)? {
+		let entry = entry?;
+
+		if entry.file_name() == ".git" {
+			return Ok(true);
+		}
+
+		if entry.file_type()?.is_dir()
+			&& contains_repo(&entry.path())?
+		{
+			return Ok(true);
+		}
+	}
+
+	Ok(false)
+}
+
+fn remove_empty_parents(work_dir: &Path, path: &Path) {
+	let mut parent = path.parent();
+
+	while let Some(dir) = parent {
+		if dir == work_dir || !dir.starts_with(work_dir) {
+			break;
+		}
+
+		// fails if the folder is not empty
+		if fs::remove_dir(dir).is_err() {
+			break;
+		}
+
+		parent = dir.parent();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use std::fs;
+
+	#[test]
+	fn test_clean_candidates() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(
+			&repo,
+			".gitignore",
+			"target/\n*.log",
+			"c1",
+		);
+		fs::create_dir_all(root.join("target/debug")).unwrap();
+		repo_write_file(&repo, "target/debug/a", "a").unwrap();
+		repo_write_file(&repo, "foo.log", "log").unwrap();
+		fs::create_dir(root.join("src")).unwrap();
+		repo_write_file(&repo, "src/new.rs", "new").unwrap();
+
+		let res = get_clean_candidates(repo_path, false).unwrap();
+		assert_eq!(
+			res.iter().map(|i| i.path.as_str()).collect::<Vec<_>>(),
+			vec!["src/new.rs"]
+		);
+
+		let res = get_clean_candidates(repo_path, true).unwrap();
+		assert_eq!(
+			res.iter()
+				.map(|i| (i.path.as_str(), i.status))
+				.collect::<Vec<_>>(),
+			vec![
+				("foo.log", StatusItemType::Ignored),
+				("src/new.rs", StatusItemType::New),
+				("target", StatusItemType::Ignored),
+			]
+		);
+	}
+
+	#[test]
+	fn test_clean_paths() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, ".gitignore", "target/", "c1");
+		fs::create_dir_all(root.join("target/debug")).unwrap();
+		repo_write_file(&repo, "target/debug/a", "a").unwrap();
+		fs::create_dir_all(root.join("sub/dir")).unwrap();
+		repo_write_file(&repo, "sub/dir/new.txt", "new").unwrap();
+		repo_write_file(&repo, "keep.txt", "keep").unwrap();
+
+		clean_paths(
+			repo_path,
+			&[
+				String::from("target"),
+				String::from("sub/dir/new.txt"),
+			],
+		)
+		.unwrap();
+
+		assert!(!root.join("target").exists());
+		assert!(!root.join("sub").exists());
+		assert!(root.join("keep.txt").exists());
+		assert_eq!(
+			get_clean_candidates(repo_path, true)
+				.unwrap()
+				.into_iter()
+				.map(|i| i.path)
+				.collect::<Vec<_>>(),
+			vec![String::from("keep.txt")]
+		);
+	}
+
+	#[test]
+	fn test_clean_refuses_tracked() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "tracked.txt", "a", "c1");
+
+		assert!(clean_paths(
+			repo_path,
+			&[String::from("tracked.txt")]
+		)
+		.is_err());
+		assert!(
+			clean_paths(repo_path, &[String::from("../x")]).is_err()
+		);
+		assert!(root.join("tracked.txt").exists());
+	}
+
+	#[test]
+	fn test_clean_refuses_dirs_with_tracked_files() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir(root.join("dir")).unwrap();
+		write_commit_file(&repo, "dir/tracked.txt", "a", "c1");
+		repo_write_file(&repo, "dir/new.txt", "new").unwrap();
+
+		assert!(
+			clean_paths(repo_path, &[String::from("dir")]).is_err()
+		);
+		assert!(
+			clean_paths(repo_path, &[String::from("dir/")]).is_err()
+		);
+		assert!(root.join("dir/tracked.txt").exists());
+		assert!(root.join("dir/new.txt").exists());
+	}
+
+	#[test]
+	fn test_clean_refuses_nested_repo() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir_all(root.join("outer/nested")).unwrap();
+		git2::Repository::init(root.join("outer/nested")).unwrap();
+		repo_write_file(&repo, "outer/nested/a.txt", "a").unwrap();
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+
+		assert!(clean_paths(
+			repo_path,
+			&[String::from("new.txt"), String::from("outer")]
+		)
+		.is_err());
+		assert!(root.join("outer/nested/.git").exists());
+		// nothing is cleaned if any path is refused
+		assert!(root.join("new.txt").exists());
+	}
+}
@@ -1,10 +1,12 @@
 use std::cmp::Ordering;
 
-use super::{stash::is_stash_commit, utils::repo, CommitId};
+use super::{
+	diff::DiffOptions, stash::is_stash_commit, utils::repo, CommitId,
+};
 use crate::{
 	error::Error, error::Result, StatusItem, StatusItemType,
 };
-use git2::{Diff, DiffOptions, Repository};
+use git2::{Diff, Repository};
 use scopetime::scope_time;
 
 /// get all files that are part of a commit
@@ -18,9 +20,9 @@ pub fn get_commit_files(
 	let repo = repo(repo_path)?;
 
 	let diff = if let Some(other) = other {
-		get_compare_commits_diff(&repo, (id, other), None)?
+		get_compare_commits_diff(&repo, (id, other), &[], None)?
 	} else {
-		get_commit_diff(&repo, id, None)?
+		get_commit_diff(&repo, id, &[], None)?
 	};
 
 	let res = diff
@@ -34,6 +36,15 @@ pub fn get_commit_files(
 					.path()
 					.map(|p| p.to_str().unwrap_or("").to_string())
 					.unwrap_or_default(),
+				old_path: match status {
+					StatusItemType::Renamed
+					| StatusItemType::Copied => delta
+						.old_file()
+						.path()
+						.and_then(|p| p.to_str())
+						.map(String::from),
+					_ => None,
+				},
 				status,
 			}
 		})
@@ -42,12 +53,13 @@ pub fn get_commit_files(
 	Ok(res)
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn get_compare_commits_diff(
-	repo: &Repository,
+/// diff between two commits limited to `pathspecs` (if any)
+pub fn get_compare_commits_diff<'a>(
+	repo: &'a Repository,
 	ids: (CommitId, CommitId),
-	pathspec: Option<String>,
-) -> Result<Diff<'_>> {
+	pathspecs: &[String],
+	options: Option<DiffOptions>,
+) -> Result<Diff<'a>> {
 	// scope_time!("get_compare_commits_diff");
 
 	let commits = (
@@ -65,27 +77,43 @@ pub fn get_compare_commits_diff(
 
 	let trees = (commits.0.tree()?, commits.1.tree()?);
 
-	let mut opts = DiffOptions::new();
-	if let Some(p) = &pathspec {
-		opts.pathspec(p.clone());
-	}
-	opts.show_binary(true);
+	let mut opts = diff_options(pathspecs, options);
 
-	let diff = repo.diff_tree_to_tree(
+	let mut diff = repo.diff_tree_to_tree(
 		Some(&trees.0),
 		Some(&trees.1),
 		Some(&mut opts),
 	)?;
 
+	if let Some(options) = options {
+		options.find_similar(&mut diff, false)?;
+	}
+
 	Ok(diff)
 }
 
+fn diff_options(
+	pathspecs: &[String],
+	options: Option<DiffOptions>,
+) -> git2::DiffOptions {
+	let mut opts = git2::DiffOptions::new();
+	if let Some(options) = options {
+		options.apply(&mut opts);
+	}
+	for p in pathspecs {
+		opts.pathspec(p);
+	}
+	opts.show_binary(true);
+	opts
+}
+
 #[allow(clippy::redundant_pub_crate)]
-pub(crate) fn get_commit_diff(
-	repo: &Repository,
+pub(crate) fn get_commit_diff<'a>(
+	repo: &'a Repository,
 	id: CommitId,
-	pathspec: Option<String>,
-) -> Result<Diff<'_>> {
+	pathspecs: &[String],
+	options: Option<DiffOptions>,
+) -> Result<Diff<'a>> {
 	// scope_time!("get_commit_diff");
 
 	let commit = repo.find_commit(id.into())?;
@@ -99,11 +127,7 @@ pub(crate) fn get_commit_diff(
 		None
 	};
 
-	let mut opts = DiffOptions::new();
-	if let Some(p) = &pathspec {
-		opts.pathspec(p.clone());
-	}
-	opts.show_binary(true);
+	let mut opts = diff_options(pathspecs, options);
 
 	let mut diff = repo.diff_tree_to_tree(
 		parent.as_ref(),
@@ -122,13 +146,18 @@ pub(crate) fn get_commit_diff(
 			let untracked_diff = get_commit_diff(
 				repo,
 				CommitId::new(untracked_commit),
-				pathspec,
+				pathspecs,
+				options,
 			)?;
 
 			diff.merge(&untracked_diff)?;
 		}
 	}
 
+	if let Some(options) = options {
+		options.find_similar(&mut diff, false)?;
+	}
+
 	Ok(diff)
 }
 
@@ -1,4 +1,4 @@
-use super::utils::repo;
+use super::{diff::DiffAlgorithm, utils::repo};
 use crate::error::Result;
 use git2::Repository;
 use scopetime::scope_time;
@@ -63,6 +63,26 @@ pub fn untracked_files_config(
 	untracked_files_config_repo(&repo)
 }
 
+// see https://git-scm.com/docs/git-config#Documentation/git-config.txt-diffalgorithm
+/// read `diff.algorithm` git config, `None` if not set or unknown.
+/// libgit2 has no histogram implementation so `histogram` maps to
+/// its closest relative `Patience`
+pub fn diff_algorithm_config(
+	repo_path: &str,
+) -> Result<Option<DiffAlgorithm>> {
+	let repo = repo(repo_path)?;
+	let algorithm = get_config_string_repo(&repo, "diff.algorithm")?;
+
+	Ok(algorithm.and_then(|algorithm| {
+		match algorithm.to_lowercase().as_str() {
+			"default" | "myers" => Some(DiffAlgorithm::Myers),
+			"minimal" => Some(DiffAlgorithm::Minimal),
+			"patience" | "histogram" => Some(DiffAlgorithm::Patience),
+			_ => None,
+		}
+	}))
+}
+
 /// get string from config
 pub fn get_config_string(
 	repo_path: &str,
@@ -118,4 +138,33 @@ mod tests {
 		assert!(good_cfg.is_ok());
 		assert!(good_cfg.unwrap().is_some());
 	}
+
+	#[test]
+	fn test_diff_algorithm_config() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		assert_eq!(diff_algorithm_config(repo_path).unwrap(), None);
+
+		repo.config()
+			.unwrap()
+			.set_str("diff.algorithm", "histogram")
+			.unwrap();
+
+		assert_eq!(
+			diff_algorithm_config(repo_path).unwrap(),
+			Some(DiffAlgorithm::Patience)
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("diff.algorithm", "minimal")
+			.unwrap();
+
+		assert_eq!(
+			diff_algorithm_config(repo_path).unwrap(),
+			Some(DiffAlgorithm::Minimal)
+		);
+	}
 }
@@ -8,7 +8,8 @@ use super::{
 use crate::{error::Error, error::Result, hash};
 use easy_cast::Conv;
 use git2::{
-	Delta, Diff, DiffDelta, DiffFormat, DiffHunk, Patch, Repository,
+	Delta, Diff, DiffDelta, DiffFormat, DiffHunk, FileMode, Patch,
+	Repository,
 };
 use scopetime::scope_time;
 use std::{cell::RefCell, fs, path::Path, rc::Rc};
@@ -109,6 +110,107 @@ pub struct Hunk {
 	pub lines: Vec<DiffLine>,
 }
 
+impl Hunk {
+	/// splits the hunk at unchanged lines into sub-hunks containing
+	/// one block of changes each (like `s` in `git add -p`).
+	/// unchanged lines are kept with the changes following them.
+	///
+	/// returns `None` if there is nothing to split
+	pub fn split(&self) -> Option<Vec<Self>> {
+		let body = self
+			.lines
+			.iter()
+			.skip_while(|l| l.line_type == DiffLineType::Header)
+			.collect::<Vec<_>>();
+
+		let is_change = |l: &DiffLine| {
+			l.line_type == DiffLineType::Add
+				|| l.line_type == DiffLineType::Delete
+		};
+
+		// indices into `body` where a new sub-hunk starts
+		let mut starts = vec![0];
+		for (idx, pair) in body.windows(2).enumerate() {
+			if is_change(pair[0]) && !is_change(pair[1]) {
+				starts.push(idx + 1);
+			}
+		}
+		// trailing context stays with the last block of changes
+		while starts.len() > 1
+			&& !body[starts[starts.len() - 1]..]
+				.iter()
+				.any(|l| is_change(l))
+		{
+			starts.pop();
+		}
+
+		if starts.len() < 2 {
+			return None;
+		}
+
+		let mut last_old = 0;
+		let mut last_new = 0;
+		let mut res = Vec::with_capacity(starts.len());
+
+		for (idx, start) in starts.iter().enumerate() {
+			let end =
+				starts.get(idx + 1).copied().unwrap_or(body.len());
+			let lines = &body[*start..end];
+
+			let old_lines = lines
+				.iter()
+				.filter(|l| l.position.old_lineno.is_some());
+			let new_lines = lines
+				.iter()
+				.filter(|l| l.position.new_lineno.is_some());
+
+			let header = HunkHeader {
+				old_start: old_lines
+					.clone()
+					.find_map(|l| l.position.old_lineno)
+					.unwrap_or(last_old),
+				old_lines: u32::try_conv(old_lines.count())
+					.unwrap_or_default(),
+				new_start: new_lines
+					.clone()
+					.find_map(|l| l.position.new_lineno)
+					.unwrap_or(last_new),
+				new_lines: u32::try_conv(new_lines.count())
+					.unwrap_or_default(),
+			};
+
+			for line in lines {
+				last_old =
+					line.position.old_lineno.unwrap_or(last_old);
+				last_new =
+					line.position.new_lineno.unwrap_or(last_new);
+			}
+
+			let mut hunk_lines = Vec::with_capacity(lines.len() + 1);
+			hunk_lines.push(DiffLine {
+				content: format!(
+					"@@ -{},{} +{},{} @@",
+					header.old_start,
+					header.old_lines,
+					header.new_start,
+					header.new_lines
+				)
+				.into(),
+				line_type: DiffLineType::Header,
+				position: DiffLinePosition::default(),
+			});
+			hunk_lines.extend(lines.iter().map(|l| (*l).clone()));
+
+			res.push(Self {
+				header_hash: hash(&header),
+				lines: hunk_lines,
+			});
+		}
+
+		Some(res)
+	}
+}
+
 /// collection of hunks, sum of all diff lines
 #[derive(Default, Clone, Hash, Debug)]
 pub struct FileDiff {
@@ -122,26 +224,112 @@ pub struct FileDiff {
 	pub sizes: (u64, u64),
 	/// size delta in bytes
 	pub size_delta: i64,
+	/// old and new unix file mode if the mode changed
+	pub modes: Option<(u32, u32)>,
 }
 
+/// diff algorithm to use when generating a diff
+/// (see `diff.algorithm` in git-config)
+#[derive(Debug, Hash, Clone, Copy, PartialEq)]
+pub enum DiffAlgorithm {
+	/// the basic greedy diff algorithm (git default)
+	Myers,
+	/// spend extra time to find the smallest possible diff
+	Minimal,
+	/// use the patience diff algorithm
+	Patience,
+}
+
+impl Default for DiffAlgorithm {
+	fn default() -> Self {
+		Self::Myers
+	}
+}
+
+/// git's default similarity threshold (in percent) for renames/copies
+pub const DEFAULT_SIMILARITY_THRESHOLD: u16 = 50;
+
 /// see <https://libgit2.org/libgit2/#HEAD/type/git_diff_options>
 #[derive(Debug, Hash, Clone, Copy, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct DiffOptions {
 	/// see <https://libgit2.org/libgit2/#HEAD/type/git_diff_options>
 	pub ignore_whitespace: bool,
+	/// ignore changes in amount of whitespace
+	pub ignore_whitespace_change: bool,
+	/// ignore whitespace changes at end of line
+	pub ignore_whitespace_eol: bool,
+	/// ignore changes whose lines are all blank
+	pub ignore_blank_lines: bool,
 	/// see <https://libgit2.org/libgit2/#HEAD/type/git_diff_options>
 	pub context: u32,
 	/// see <https://libgit2.org/libgit2/#HEAD/type/git_diff_options>
 	pub interhunk_lines: u32,
+	/// diff algorithm
+	pub algorithm: DiffAlgorithm,
+	/// similarity threshold (in percent) for rename detection,
+	/// `None` disables rename detection
+	pub rename_threshold: Option<u16>,
+	/// similarity threshold (in percent) for copy detection,
+	/// `None` disables copy detection
+	pub copy_threshold: Option<u16>,
 }
 
 impl Default for DiffOptions {
 	fn default() -> Self {
 		Self {
 			ignore_whitespace: false,
+			ignore_whitespace_change: false,
+			ignore_whitespace_eol: false,
+			ignore_blank_lines: false,
 			context: 3,
 			interhunk_lines: 0,
+			algorithm: DiffAlgorithm::default(),
+			rename_threshold: None,
+			copy_threshold: None,
+		}
+	}
+}
+
+impl DiffOptions {
+	pub(crate) fn apply(&self, opt: &mut git2::DiffOptions) {
+		opt.context_lines(self.context);
+		opt.ignore_whitespace(self.ignore_whitespace);
+		opt.ignore_whitespace_change(self.ignore_whitespace_change);
+		opt.ignore_whitespace_eol(self.ignore_whitespace_eol);
+		opt.ignore_blank_lines(self.ignore_blank_lines);
+		opt.interhunk_lines(self.interhunk_lines);
+		opt.patience(self.algorithm == DiffAlgorithm::Patience);
+		opt.minimal(self.algorithm == DiffAlgorithm::Minimal);
+	}
+
+	/// `copies_from_unmodified` needs unmodified files in `diff`
+	pub(crate) fn find_similar(
+		&self,
+		diff: &mut Diff,
+		copies_from_unmodified: bool,
+	) -> Result<()> {
+		if self.rename_threshold.is_none()
+			&& self.copy_threshold.is_none()
+		{
+			return Ok(());
+		}
+
+		let mut opt = git2::DiffFindOptions::new();
+		if let Some(threshold) = self.rename_threshold {
+			opt.renames(true);
+			opt.rename_threshold(threshold);
+		}
+		if let Some(threshold) = self.copy_threshold {
+			opt.copies(true);
+			opt.copy_threshold(threshold);
+			opt.copies_from_unmodified(copies_from_unmodified);
 		}
+		opt.for_untracked(true);
+
+		diff.find_similar(Some(&mut opt))?;
+
+		Ok(())
 	}
 }
 
@@ -151,19 +339,36 @@ pub(crate) fn get_diff_raw<'a>(
 	stage: bool,
 	reverse: bool,
 	options: Option<DiffOptions>,
+) -> Result<Diff<'a>> {
+	get_diff_raw_paths(repo, p, None, stage, reverse, options)
+}
+
+/// diff of `p` and (if it is a rename/copy) its source `old_path`,
+/// similarity detection only runs if `old_path` is passed as a
+/// single path has nothing to be paired with
+fn get_diff_raw_paths<'a>(
+	repo: &'a Repository,
+	p: &str,
+	old_path: Option<&str>,
+	stage: bool,
+	reverse: bool,
+	options: Option<DiffOptions>,
 ) -> Result<Diff<'a>> {
 	// scope_time!("get_diff_raw");
 
 	let mut opt = git2::DiffOptions::new();
 	if let Some(options) = options {
-		opt.context_lines(options.context);
-		opt.ignore_whitespace(options.ignore_whitespace);
-		opt.interhunk_lines(options.interhunk_lines);
+		options.apply(&mut opt);
 	}
 	opt.pathspec(p);
+	if let Some(old_path) = old_path {
+		opt.pathspec(old_path);
+	}
 	opt.reverse(reverse);
+	// copy sources are usually unmodified
+	opt.include_unmodified(old_path.is_some());
 
-	let diff = if stage {
+	let mut diff = if stage {
 		// diff against head
 		if let Ok(id) = get_head_repo(repo) {
 			let parent = repo.find_commit(id.into())?;
@@ -187,9 +392,28 @@ pub(crate) fn get_diff_raw<'a>(
 		repo.diff_index_to_workdir(None, Some(&mut opt))?
 	};
 
+	if old_path.is_some() {
+		similarity_options(options).find_similar(&mut diff, true)?;
+	}
+
 	Ok(diff)
 }
 
+/// options for the diff of a detected rename/copy, making sure
+/// it is paired up even if detection is disabled in `options`
+fn similarity_options(options: Option<DiffOptions>) -> DiffOptions {
+	let mut options = options.unwrap_or_default();
+	if options.rename_threshold.is_none()
+		&& options.copy_threshold.is_none()
+	{
+		options.rename_threshold = Some(DEFAULT_SIMILARITY_THRESHOLD);
+	}
+	if options.copy_threshold.is_none() {
+		options.copy_threshold = options.rename_threshold;
+	}
+	options
+}
+
 /// returns diff of a specific file either in `stage` or workdir
 pub fn get_diff(
 	repo_path: &str,
@@ -206,18 +430,69 @@ pub fn get_diff(
 	raw_diff_to_file_diff(&diff, work_dir)
 }
 
+/// returns diff of a renamed/copied file `p` against its source
+/// `old_path` either in `stage` or workdir
+pub fn get_diff_renamed(
+	repo_path: &str,
+	old_path: &str,
+	p: &str,
+	stage: bool,
+	options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+	scope_time!("get_diff_renamed");
+
+	let repo = utils::repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let diff = get_diff_raw_paths(
+		&repo,
+		p,
+		Some(old_path),
+		stage,
+		false,
+		options,
+	)?;
+
+	raw_diff_to_file_diff(&diff, work_dir)
+}
+
+/// pathspecs and diff options of a single file diff of a commit,
+/// `old_path` is the source of `p` if it was renamed/copied
+fn commit_diff_params(
+	p: &str,
+	old_path: Option<&str>,
+	options: Option<DiffOptions>,
+) -> (Vec<String>, Option<DiffOptions>) {
+	let mut paths = vec![p.to_string()];
+	paths.extend(old_path.map(String::from));
+
+	let options = if old_path.is_some() {
+		Some(similarity_options(options))
+	} else {
+		options.map(|options| DiffOptions {
+			rename_threshold: None,
+			copy_threshold: None,
+			..options
+		})
+	};
+
+	(paths, options)
+}
+
 /// returns diff of a specific file inside a commit
 /// see `get_commit_diff`
 pub fn get_diff_commit(
 	repo_path: &str,
 	id: CommitId,
-	p: String,
+	p: &str,
+	old_path: Option<&str>,
+	options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
 	scope_time!("get_diff_commit");
 
 	let repo = utils::repo(repo_path)?;
 	let work_dir = work_dir(&repo)?;
-	let diff = get_commit_diff(&repo, id, Some(p))?;
+	let (paths, options) = commit_diff_params(p, old_path, options);
+	let diff = get_commit_diff(&repo, id, &paths, options)?;
 
 	raw_diff_to_file_diff(&diff, work_dir)
 }
@@ -226,14 +501,21 @@ pub fn get_diff_commit(
 pub fn get_diff_commits(
 	repo_path: &str,
 	ids: (CommitId, CommitId),
-	p: String,
+	p: &str,
+	old_path: Option<&str>,
+	options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
 	scope_time!("get_diff_commits");
 
 	let repo = utils::repo(repo_path)?;
 	let work_dir = work_dir(&repo)?;
-	let diff =
-		get_compare_commits_diff(&repo, (ids.0, ids.1), Some(p))?;
+	let (paths, options) = commit_diff_params(p, old_path, options);
+	let diff = get_compare_commits_diff(
+		&repo,
+		(ids.0, ids.1),
+		&paths,
+		options,
+	)?;
 
 	raw_diff_to_file_diff(&diff, work_dir)
 }
@@ -372,12 +654,31 @@ fn raw_diff_to_file_diff<'a>(
 		if new_file_diff {
 			res.borrow_mut().untracked = true;
 		}
+
+		res.borrow_mut().modes = mode_change(diff);
 	}
 	let res = Rc::try_unwrap(res)
 		.map_err(|_| Error::Generic("rc unwrap error".to_owned()))?;
 	Ok(res.into_inner())
 }
 
+/// modes of the changed file if both sides exist and differ
+fn mode_change(diff: &Diff) -> Option<(u32, u32)> {
+	let delta = diff
+		.deltas()
+		.find(|delta| delta.status() != Delta::Unmodified)?;
+
+	let old = u32::from(delta.old_file().mode());
+	let new = u32::from(delta.new_file().mode());
+	let unreadable = u32::from(FileMode::Unreadable);
+
+	if old == new || old == unreadable || new == unreadable {
+		None
+	} else {
+		Some((old, new))
+	}
+}
+
 const fn is_newline(c: char) -> bool {
 	c == '\n' || c == '\r'
 }
@@ -402,12 +703,19 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-	use super::{get_diff, get_diff_commit};
+	use super::{
+		get_diff, get_diff_commit, get_diff_renamed, DiffLineType,
+		DiffOptions,
+	};
 	use crate::error::Result;
 	use crate::sync::{
 		commit, stage_add_file,
 		status::{get_status, StatusType},
-		tests::{get_statuses, repo_init, repo_init_empty},
+		tests::{
+			get_statuses, repo_init, repo_init_empty,
+			write_commit_file,
+		},
+		utils::repo_write_file,
 	};
 	use std::{
 		fs::{self, File},
@@ -561,6 +869,81 @@ mod tests {
 		assert_eq!(&*diff.hunks[0].lines[1].content, "test");
 	}
 
+	#[test]
+	fn test_diff_renamed() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n", "c1");
+		fs::remove_file(root.join("a.txt")).unwrap();
+		repo_write_file(&repo, "b.txt", "1\n2\n3\n4\n5\n6\n")
+			.unwrap();
+
+		let diff = get_diff_renamed(
+			repo_path, "a.txt", "b.txt", false, None,
+		)
+		.unwrap();
+
+		assert_eq!(diff.hunks.len(), 1);
+		assert!(!diff.untracked);
+		assert_eq!(
+			diff.hunks[0]
+				.lines
+				.iter()
+				.filter(|l| l.line_type != DiffLineType::None)
+				.map(|l| (l.line_type, &*l.content))
+				.collect::<Vec<_>>(),
+			vec![
+				(DiffLineType::Header, "@@ -3,3 +3,4 @@"),
+				(DiffLineType::Add, "6"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_diff_commit_renamed() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n", "c1");
+		fs::remove_file(root.join("a.txt")).unwrap();
+		repo_write_file(&repo, "b.txt", "1\n2\n3 \n4\n5\n6\n")
+			.unwrap();
+		let mut index = repo.index().unwrap();
+		index.remove_path(Path::new("a.txt")).unwrap();
+		index.write().unwrap();
+		stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+		let id = commit(repo_path, "c2").unwrap();
+
+		let diff = get_diff_commit(
+			repo_path,
+			id,
+			"b.txt",
+			Some("a.txt"),
+			Some(DiffOptions {
+				ignore_whitespace: true,
+				..DiffOptions::default()
+			}),
+		)
+		.unwrap();
+
+		assert_eq!(diff.hunks.len(), 1);
+		assert_eq!(
+			diff.hunks[0]
+				.lines
+				.iter()
+				.filter(|l| l.line_type != DiffLineType::None)
+				.map(|l| (l.line_type, &*l.content))
+				.collect::<Vec<_>>(),
+			vec![
+				(DiffLineType::Header, "@@ -3,3 +3,4 @@"),
+				(DiffLineType::Add, "6"),
+			]
+		);
+	}
+
 	#[test]
 	fn test_diff_delta_size() -> Result<()> {
 		let file_path = Path::new("bar");
@@ -638,7 +1021,7 @@ mod tests {
 		let id = commit(repo_path, "").unwrap();
 
 		let diff =
-			get_diff_commit(repo_path, id, String::new()).unwrap();
+			get_diff_commit(repo_path, id, "", None, None).unwrap();
 
 		dbg!(&diff);
 		assert_eq!(diff.sizes, (1, 2));
@@ -646,4 +1029,75 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_diff_ignore_whitespace_options() -> Result<()> {
+		let file_path = Path::new("bar.txt");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		File::create(&root.join(file_path))?
+			.write_all(b"a b\nc\n")?;
+
+		stage_add_file(repo_path, file_path).unwrap();
+
+		commit(repo_path, "").unwrap();
+
+		File::create(&root.join(file_path))?
+			.write_all(b"a  b\n\nc  \n")?;
+
+		let diff =
+			get_diff(repo_path, "bar.txt", false, None).unwrap();
+		assert_eq!(diff.hunks.len(), 1);
+
+		let diff = get_diff(
+			repo_path,
+			"bar.txt",
+			false,
+			Some(DiffOptions {
+				ignore_whitespace_change: true,
+				ignore_whitespace_eol: true,
+				ignore_blank_lines: true,
+				..DiffOptions::default()
+			}),
+		)
+		.unwrap();
+		assert_eq!(diff.hunks.len(), 0);
+
+		Ok(())
+	}
+	#[test]
+	fn test_split_hunk() -> Result<()> {
+		let file_path = Path::new("bar.txt");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		File::create(&root.join(file_path))?
+			.write_all(b"a\nb\nc\nd\ne\nf\n")?;
+
+		stage_add_file(repo_path, file_path).unwrap();
+
+		commit(repo_path, "").unwrap();
+
+		File::create(&root.join(file_path))?
+			.write_all(b"a\nB\nc\nd\nx\ne\nf\n")?;
+
+		let diff =
+			get_diff(repo_path, "bar.txt", false, None).unwrap();
+		assert_eq!(diff.hunks.len(), 1);
+
+		let split = diff.hunks[0].split().unwrap();
+		assert_eq!(split.len(), 2);
+		assert_eq!(&*split[0].lines[0].content, "@@ -1,2 +1,2 @@");
+		assert_eq!(&*split[1].lines[0].content, "@@ -3,4 +3,5 @@");
+		assert!(split[1].split().is_none());
+		assert_eq!(
+			split.iter().map(|h| h.lines.len()).sum::<usize>(),
+			diff.lines + 1
+		);
+
+		Ok(())
+	}
 }
@@ -0,0 +1,91 @@
+//! change the mode of files in the index
+//! (like `git update-index --chmod=(+|-)x`)
+
+use super::utils::repo;
+use crate::error::{Error, Result};
+use git2::FileMode;
+use scopetime::scope_time;
+use std::path::Path;
+
+/// flips the executable bit of the regular file `path` in the index
+/// (the workdir file is not touched), returns whether it is
+/// executable now
+pub fn toggle_executable(
+	repo_path: &str,
+	path: &str,
+) -> Result<bool> {
+	scope_time!("toggle_executable");
+
+	let repo = repo(repo_path)?;
+	let mut index = repo.index()?;
+	index.read(true)?;
+
+	let mut entry =
+		index.get_path(Path::new(path), 0).ok_or_else(|| {
+			Error::Generic(format!("'{}' is not in the index", path))
+		})?;
+
+	let executable = if entry.mode == u32::from(FileMode::Blob) {
+		true
+	} else if entry.mode == u32::from(FileMode::BlobExecutable) {
+		false
+	} else {
+		return Err(Error::Generic(format!(
+			"'{}' is not a regular file",
+			path
+		)));
+	};
+
+	entry.mode = if executable {
+		FileMode::BlobExecutable.into()
+	} else {
+		FileMode::Blob.into()
+	};
+
+	index.add(&entry)?;
+	index.write()?;
+
+	Ok(executable)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		diff::get_diff,
+		status::{get_status, StatusItemType, StatusType},
+		tests::{repo_init, write_commit_file},
+	};
+
+	#[test]
+	fn test_toggle_executable() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "run.sh", "echo", "c1");
+
+		assert!(toggle_executable(repo_path, "run.sh").unwrap());
+
+		let staged = get_status(repo_path, StatusType::Stage, None)
+			.unwrap()
+			.into_iter()
+			.map(|i| (i.path, i.status))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			staged,
+			vec![(String::from("run.sh"), StatusItemType::Modified)]
+		);
+
+		let diff = get_diff(repo_path, "run.sh", true, None).unwrap();
+		assert!(diff.hunks.is_empty());
+		assert_eq!(diff.modes, Some((0o100_644, 0o100_755)));
+
+		assert!(!toggle_executable(repo_path, "run.sh").unwrap());
+		assert!(get_status(repo_path, StatusType::Stage, None)
+			.unwrap()
+			.is_empty());
+
+		assert!(toggle_executable(repo_path, "missing").is_err());
+	}
+}
@@ -0,0 +1,648 @@
+//! gitignore pattern matching to find the rule ignoring a path
+//! (like `git check-ignore -v`)
+
+use super::{
+	status::get_ignored,
+	utils::{repo, work_dir},
+};
+use crate::error::Result;
+use git2::Repository;
+use scopetime::scope_time;
+use std::{
+	collections::HashMap,
+	env, fs,
+	path::{Path, PathBuf},
+};
+
+/// rule of an ignore file that ignores a path
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IgnoreRule {
+	/// pattern as written in the ignore file
+	pub pattern: String,
+	/// ignore file containing the rule (relative to the workdir if
+	/// inside of it)
+	pub source: String,
+	/// line in `source` (1-based)
+	pub line: usize,
+}
+
+/// ignored file or folder together with the rule ignoring it
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IgnoredFile {
+	///
+	pub path: String,
+	/// `None` if the deciding rule could not be determined
+	pub rule: Option<IgnoreRule>,
+}
+
+/// single parsed line of an ignore file
+#[derive(Clone, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct IgnorePattern {
+	glob: String,
+	negated: bool,
+	dir_only: bool,
+	anchored: bool,
+	ignore_case: bool,
+}
+
+impl IgnorePattern {
+	/// parses a line of an ignore file, `None` for blanks/comments
+	pub fn parse(line: &str) -> Option<Self> {
+		let line = trim_trailing_spaces(line);
+
+		if line.is_empty() || line.starts_with('#') {
+			return None;
+		}
+
+		let (negated, line) = line
+			.strip_prefix('!')
+			.map_or((false, line), |rest| (true, rest));
+
+		let (dir_only, line) = line
+			.strip_suffix('/')
+			.map_or((false, line), |rest| (true, rest));
+
+		let anchored = line.contains('/');
+		let glob = line.trim_start_matches('/');
+
+		if glob.is_empty() {
+			return None;
+		}
+
+		Some(Self {
+			glob: glob.to_string(),
+			negated,
+			dir_only,
+			anchored,
+			ignore_case: false,
+		})
+	}
+
+	/// match case insensitively (like with `core.ignorecase`)
+	#[must_use]
+	pub fn ignore_case(mut self, ignore_case: bool) -> Self {
+		if ignore_case {
+			self.glob = self.glob.to_lowercase();
+		}
+		self.ignore_case = ignore_case;
+		self
+	}
+
+	///
+	pub const fn is_negated(&self) -> bool {
+		self.negated
+	}
+
+	/// `path` is relative to the folder of the ignore file
+	pub fn matches(&self, path: &str, is_dir: bool) -> bool {
+		if self.dir_only && !is_dir {
+			return false;
+		}
+
+		let lowercase;
+		let path = if self.ignore_case {
+			lowercase = path.to_lowercase();
+			&lowercase
+		} else {
+			path
+		};
+
+		if self.anchored {
+			wildmatch(self.glob.as_bytes(), path.as_bytes())
+		} else {
+			let name = path.rsplit('/').next().unwrap_or(path);
+			wildmatch(self.glob.as_bytes(), name.as_bytes())
+		}
+	}
+}
+
+/// patterns of one ignore file applying to paths below `base`
+struct IgnoreFile {
+	/// folder relative to the workdir (empty for root and for the
+	/// non-gitignore sources)
+	base: String,
+	source: String,
+	patterns: Vec<(usize, String, IgnorePattern)>,
+}
+
+impl IgnoreFile {
+	fn load(
+		path: &Path,
+		base: String,
+		source: String,
+		ignore_case: bool,
+	) -> Option<Self> {
+		let content = fs::read_to_string(path).ok()?;
+
+		let patterns = content
+			.lines()
+			.enumerate()
+			.filter_map(|(idx, line)| {
+				IgnorePattern::parse(line).map(|pattern| {
+					(
+						idx + 1,
+						trim_trailing_spaces(line).to_string(),
+						pattern.ignore_case(ignore_case),
+					)
+				})
+			})
+			.collect();
+
+		Some(Self {
+			base,
+			source,
+			patterns,
+		})
+	}
+
+	/// last rule matching `path`, if `path` is inside of `base`
+	fn last_match(
+		&self,
+		path: &str,
+		is_dir: bool,
+	) -> Option<(&IgnorePattern, IgnoreRule)> {
+		let rel_path = if self.base.is_empty() {
+			path
+		} else {
+			path.strip_prefix(&self.base)?.strip_prefix('/')?
+		};
+
+		self.patterns
+			.iter()
+			.rev()
+			.find(|(_, _, p)| p.matches(rel_path, is_dir))
+			.map(|(line, pattern, p)| {
+				(
+					p,
+					IgnoreRule {
+						pattern: pattern.clone(),
+						source: self.source.clone(),
+						line: *line,
+					},
+				)
+			})
+	}
+}
+
+/// all ignore files of a repo, `.gitignore` files are loaded lazily
+pub struct IgnoreFiles {
+	work_dir: PathBuf,
+	ignore_case: bool,
+	/// global excludes and `info/exclude`
+	base_files: Vec<IgnoreFile>,
+	gitignores: HashMap<String, Option<IgnoreFile>>,
+}
+
+impl IgnoreFiles {
+	///
+	pub fn new(repo: &Repository) -> Result<Self> {
+		let work_dir = work_dir(repo)?.to_path_buf();
+		let ignore_case = ignore_case(repo);
+
+		let mut base_files = Vec::new();
+
+		if let Some(global) = global_excludes_file(repo) {
+			let source = global.to_string_lossy().to_string();
+			base_files.extend(IgnoreFile::load(
+				&global,
+				String::new(),
+				source,
+				ignore_case,
+			));
+		}
+
+		let exclude = repo.path().join("info").join("exclude");
+		let source = exclude.strip_prefix(&work_dir).map_or_else(
+			|_| exclude.to_string_lossy().to_string(),
+			|p| p.to_string_lossy().to_string(),
+		);
+		base_files.extend(IgnoreFile::load(
+			&exclude,
+			String::new(),
+			source,
+			ignore_case,
+		));
+
+		Ok(Self {
+			work_dir,
+			ignore_case,
+			base_files,
+			gitignores: HashMap::new(),
+		})
+	}
+
+	fn gitignore(&mut self, dir: &str) -> Option<&IgnoreFile> {
+		let work_dir = &self.work_dir;
+		let ignore_case = self.ignore_case;
+
+		self.gitignores
+			.entry(dir.to_string())
+			.or_insert_with(|| {
+				let source = if dir.is_empty() {
+					String::from(".gitignore")
+				} else {
+					format!("{}/.gitignore", dir)
+				};
+
+				IgnoreFile::load(
+					&work_dir.join(&source),
+					dir.to_string(),
+					source,
+					ignore_case,
+				)
+			})
+			.as_ref()
+	}
+
+	/// deciding rule for `path` itself (ignoring its parents), the
+	/// pattern tells whether it ignores or re-includes
+	fn deciding_rule(
+		&mut self,
+		path: &str,
+		is_dir: bool,
+	) -> Option<(bool, IgnoreRule)> {
+		// `.gitignore` files of all folders containing `path`,
+		// deepest (highest precedence) first
+		let dirs = Path::new(path)
+			.ancestors()
+			.skip(1)
+			.map(|p| p.to_string_lossy().to_string())
+			.collect::<Vec<_>>();
+
+		for dir in &dirs {
+			if let Some((pattern, rule)) = self
+				.gitignore(dir)
+				.and_then(|file| file.last_match(path, is_dir))
+			{
+				return Some((pattern.is_negated(), rule));
+			}
+		}
+
+		self.base_files.iter().rev().find_map(|file| {
+			file.last_match(path, is_dir)
+				.map(|(pattern, rule)| (pattern.is_negated(), rule))
+		})
+	}
+
+	/// rule ignoring `path` or one of its parent folders
+	pub fn ignoring_rule(
+		&mut self,
+		path: &str,
+		is_dir: bool,
+	) -> Option<IgnoreRule> {
+		let mut parents = Path::new(path)
+			.ancestors()
+			.skip(1)
+			.filter(|p| !p.as_os_str().is_empty())
+			.map(|p| p.to_string_lossy().to_string())
+			.collect::<Vec<_>>();
+		parents.reverse();
+
+		// a file cannot be re-included if a parent folder is ignored
+		for parent in &parents {
+			if let Some((false, rule)) =
+				self.deciding_rule(parent, true)
+			{
+				return Some(rule);
+			}
+		}
+
+		match self.deciding_rule(path, is_dir) {
+			Some((false, rule)) => Some(rule),
+			_ => None,
+		}
+	}
+}
+
+/// rule (and its source) ignoring `path`, `None` if not ignored
+pub fn check_ignore(
+	repo_path: &str,
+	path: &str,
+) -> Result<Option<IgnoreRule>> {
+	scope_time!("check_ignore");
+
+	let repo = repo(repo_path)?;
+	let mut files = IgnoreFiles::new(&repo)?;
+	let is_dir = work_dir(&repo)?.join(path).is_dir();
+
+	Ok(files.ignoring_rule(path.trim_end_matches('/'), is_dir))
+}
+
+/// ignored files and folders of the workdir along with the rule
+/// ignoring them
+pub fn get_ignored_files(
+	repo_path: &str,
+) -> Result<Vec<IgnoredFile>> {
+	scope_time!("get_ignored_files");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let mut files = IgnoreFiles::new(&repo)?;
+
+	Ok(get_ignored(repo_path)?
+		.into_iter()
+		.map(|item| {
+			let is_dir = work_dir.join(&item.path).is_dir();
+			let rule = files.ignoring_rule(&item.path, is_dir);
+
+			IgnoredFile {
+				path: item.path,
+				rule,
+			}
+		})
+		.collect())
+}
+
+/// `core.ignorecase`, set by git for case insensitive file systems
+pub fn ignore_case(repo: &Repository) -> bool {
+	repo.config()
+		.and_then(|config| config.get_bool("core.ignorecase"))
+		.unwrap_or_default()
+}
+
+/// `core.excludesFile` or its default `$XDG_CONFIG_HOME/git/ignore`
+pub fn global_excludes_file(repo: &Repository) -> Option<PathBuf> {
+	if let Ok(path) = repo
+		.config()
+		.and_then(|config| config.get_path("core.excludesFile"))
+	{
+		return Some(path);
+	}
+
+	env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.filter(|p| p.is_absolute())
+		.or_else(|| {
+			env::var_os("HOME")
+				.map(|home| Path::new(&home).join(".config"))
+		})
+		.map(|config| config.join("git").join("ignore"))
+}
+
+fn trim_trailing_spaces(line: &str) -> &str {
+	let trimmed = line.trim_end_matches(' ');
+
+	// an escaped trailing space is kept
+	if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+		&line[..=trimmed.len()]
+	} else {
+		trimmed
+	}
+}
+
+/// glob matching as used by gitignore: `*`, `?` and `[..]` do not
+/// match `/` but `**` matches across folders
+fn wildmatch(pattern: &[u8], text: &[u8]) -> bool {
+	match pattern.first() {
+		None => text.is_empty(),
+		Some(b'*') => {
+			let stars =
+				pattern.iter().take_while(|c| **c == b'*').count();
+			let rest = &pattern[stars..];
+
+			if stars > 1 {
+				if rest.is_empty() {
+					return true;
+				}
+
+				if rest[0] == b'/' {
+					// `**/` matches zero or more folders
+					let rest = &rest[1..];
+					return wildmatch(rest, text)
+						|| text.iter().enumerate().any(
+							|(idx, c)| {
+								*c == b'/'
+									&& wildmatch(
+										rest,
+										&text[idx + 1..],
+									)
+							},
+						);
+				}
+			}
+
+			(0..=text.len())
+				.take_while(|idx| *idx == 0 || text[*idx - 1] != b'/')
+				.any(|idx| wildmatch(rest, &text[idx..]))
+		}
+		Some(b'?') => match text.first() {
+			Some(c) if *c != b'/' => {
+				wildmatch(&pattern[1..], &text[1..])
+			}
+			_ => false,
+		},
+		Some(b'[') => {
+			match (text.first(), match_class(&pattern[1..])) {
+				(Some(c), Some((class, rest))) if *c != b'/' => {
+					class_contains(class, *c)
+						&& wildmatch(rest, &text[1..])
+				}
+				(Some(c), None) if *c == b'[' => {
+					wildmatch(&pattern[1..], &text[1..])
+				}
+				_ => false,
+			}
+		}
+		Some(b'\\') if pattern.len() > 1 => match text.first() {
+			Some(c) if *c == pattern[1] => {
+				wildmatch(&pattern[2..], &text[1..])
+			}
+			_ => false,
+		},
+		Some(p) => match text.first() {
+			Some(c) if c == p => wildmatch(&pattern[1..], &text[1..]),
+			_ => false,
+		},
+	}
+}
+
+/// splits `[..]` class content (without the opening bracket) from
+/// the rest of the pattern
+fn match_class(pattern: &[u8]) -> Option<(&[u8], &[u8])> {
+	let mut idx = 0;
+
+	if matches!(pattern.first(), Some(b'!') | Some(b'^')) {
+		idx += 1;
+	}
+
+	// a leading `]` is part of the class
+	if pattern.get(idx) == Some(&b']') {
+		idx += 1;
+	}
+
+	while idx < pattern.len() {
+		if pattern[idx] == b']' {
+			return Some((&pattern[..idx], &pattern[idx + 1..]));
+		}
+		idx += 1;
+	}
+
+	None
+}
+
+fn class_contains(class: &[u8], c: u8) -> bool {
+	let (negated, class) = match class.first() {
+		Some(b'!') | Some(b'^') => (true, &class[1..]),
+		_ => (false, class),
+	};
+
+	let mut found = false;
+	let mut idx = 0;
+	while idx < class.len() {
+		if idx + 2 < class.len() && class[idx + 1] == b'-' {
+			if class[idx] <= c && c <= class[idx + 2] {
+				found = true;
+			}
+			idx += 3;
+		} else {
+			if class[idx] == c {
+				found = true;
+			}
+			idx += 1;
+		}
+	}
+
+	found != negated
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use std::fs;
+
+	fn matches(pattern: &str, path: &str, is_dir: bool) -> bool {
+		IgnorePattern::parse(pattern).unwrap().matches(path, is_dir)
+	}
+
+	#[test]
+	fn test_patterns() {
+		assert!(matches("*.log", "a/b/foo.log", false));
+		assert!(!matches("*.log", "foo.log.txt", false));
+		assert!(matches("target/", "target", true));
+		assert!(!matches("target/", "target", false));
+		assert!(matches("/build", "build", true));
+		assert!(!matches("/build", "sub/build", true));
+		assert!(matches("doc/*.md", "doc/a.md", false));
+		assert!(!matches("doc/*.md", "doc/sub/a.md", false));
+		assert!(matches("doc/**/*.md", "doc/a.md", false));
+		assert!(matches("doc/**/*.md", "doc/sub/x/a.md", false));
+		assert!(matches("**/tmp", "a/b/tmp", true));
+		assert!(matches("file?.[ch]", "file1.c", false));
+		assert!(!matches("file?.[!ch]", "file1.c", false));
+		assert!(matches("\\#hash", "#hash", false));
+		assert!(IgnorePattern::parse("# comment").is_none());
+		assert!(IgnorePattern::parse("!keep.log")
+			.unwrap()
+			.is_negated());
+	}
+
+	#[test]
+	fn test_ignored_files_with_rule() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(
+			&repo,
+			".gitignore",
+			"# logs\n*.log\n!keep.log\ntarget/\n",
+			"c1",
+		);
+		fs::create_dir_all(root.join("sub/target")).unwrap();
+		write_commit_file(&repo, "sub/.gitignore", "*.tmp\n", "c2");
+		repo_write_file(&repo, "sub/target/a", "a").unwrap();
+		repo_write_file(&repo, "sub/x.tmp", "x").unwrap();
+		repo_write_file(&repo, "foo.log", "log").unwrap();
+		repo_write_file(&repo, "keep.log", "keep").unwrap();
+
+		let res = get_ignored_files(repo_path).unwrap();
+
+		let rule = |pattern: &str, source: &str, line| {
+			Some(IgnoreRule {
+				pattern: pattern.to_string(),
+				source: source.to_string(),
+				line,
+			})
+		};
+
+		assert_eq!(
+			res,
+			vec![
+				IgnoredFile {
+					path: String::from("foo.log"),
+					rule: rule("*.log", ".gitignore", 2),
+				},
+				IgnoredFile {
+					path: String::from("sub/target"),
+					rule: rule("target/", ".gitignore", 4),
+				},
+				IgnoredFile {
+					path: String::from("sub/x.tmp"),
+					rule: rule("*.tmp", "sub/.gitignore", 1),
+				},
+			]
+		);
+
+		assert_eq!(
+			check_ignore(repo_path, "keep.log").unwrap(),
+			None
+		);
+		assert_eq!(
+			check_ignore(repo_path, "sub/target/a").unwrap(),
+			rule("target/", ".gitignore", 4)
+		);
+	}
+
+	#[test]
+	fn test_info_exclude() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir_all(repo.path().join("info")).unwrap();
+		fs::write(repo.path().join("info/exclude"), "secret\n")
+			.unwrap();
+		repo_write_file(&repo, "secret", "s").unwrap();
+
+		let res = get_ignored_files(repo_path).unwrap();
+
+		assert_eq!(res.len(), 1);
+		assert_eq!(
+			res[0].rule.as_ref().unwrap().source,
+			".git/info/exclude"
+		);
+	}
+
+	#[test]
+	fn test_ignore_case() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, ".gitignore", "*.LOG\n", "c1");
+
+		repo.config()
+			.unwrap()
+			.set_bool("core.ignorecase", false)
+			.unwrap();
+		assert_eq!(check_ignore(repo_path, "foo.log").unwrap(), None);
+
+		repo.config()
+			.unwrap()
+			.set_bool("core.ignorecase", true)
+			.unwrap();
+		assert_eq!(
+			check_ignore(repo_path, "foo.log").unwrap(),
+			Some(IgnoreRule {
+				pattern: String::from("*.LOG"),
+				source: String::from(".gitignore"),
+				line: 1,
+			})
+		);
+	}
+}
@@ -0,0 +1,275 @@
+//! sync git API for searching file contents
+
+use super::{utils, CommitId};
+use crate::error::Result;
+use git2::{
+	ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult,
+};
+use regex::Regex;
+use scopetime::scope_time;
+
+/// bytes checked for a nul byte to tell binary from text files,
+/// same as git uses
+const BINARY_CHECK_LEN: usize = 8000;
+
+/// matches `grep_files` stops at, a broad pattern on a large repo
+/// would otherwise keep most of its lines in memory
+pub const GREP_MAX_MATCHES: usize = 10_000;
+
+/// a line matching the pattern of `grep_files`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrepMatch {
+	/// repo relative path of the file
+	pub path: String,
+	/// 0-based line number
+	pub line: usize,
+	/// content of the line
+	pub content: String,
+}
+
+/// result of `grep_files`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GrepResult {
+	///
+	pub matches: Vec<GrepMatch>,
+	/// search stopped after `max_matches`
+	pub limit_reached: bool,
+}
+
+/// searches the files of `commit_id`, or the tracked files in the
+/// working tree if `None`, for lines matching the regex `pattern`
+/// and stops after `max_matches`.
+///
+/// `progress` gets the number of searched and total files and
+/// cancels by returning `false`, in which case `None` is returned
+pub fn grep_files<F>(
+	repo_path: &str,
+	commit_id: Option<CommitId>,
+	pattern: &str,
+	max_matches: usize,
+	mut progress: F,
+) -> Result<Option<GrepResult>>
+where
+	F: FnMut(usize, usize) -> bool,
+{
+	scope_time!("grep_files");
+
+	let regex = Regex::new(pattern)?;
+	let repo = utils::repo(repo_path)?;
+
+	let files = match commit_id {
+		Some(id) => commit_blobs(&repo, id)?,
+		None => index_files(&repo)?,
+	};
+
+	let work_dir = utils::work_dir(&repo)?;
+	let mut matches = Vec::new();
+
+	for (index, (path, id)) in files.iter().enumerate() {
+		// one more than allowed tells whether the limit was hit
+		let limit = (max_matches + 1).saturating_sub(matches.len());
+		if limit == 0 {
+			break;
+		}
+
+		let content = if commit_id.is_some() {
+			repo.find_blob(*id)
+				.ok()
+				.map(|blob| blob.content().to_vec())
+		} else {
+			std::fs::read(work_dir.join(path)).ok()
+		};
+
+		if let Some(content) = content {
+			search_content(
+				&regex,
+				path,
+				&content,
+				limit,
+				&mut matches,
+			);
+		}
+
+		if !progress(index + 1, files.len()) {
+			return Ok(None);
+		}
+	}
+
+	let limit_reached = matches.len() > max_matches;
+	matches.truncate(max_matches);
+
+	Ok(Some(GrepResult {
+		matches,
+		limit_reached,
+	}))
+}
+
+fn search_content(
+	regex: &Regex,
+	path: &str,
+	content: &[u8],
+	limit: usize,
+	matches: &mut Vec<GrepMatch>,
+) {
+	let binary =
+		content.iter().take(BINARY_CHECK_LEN).any(|byte| *byte == 0);
+	if binary {
+		return;
+	}
+
+	let content = String::from_utf8_lossy(content);
+	matches.extend(
+		content
+			.lines()
+			.enumerate()
+			.filter(|(_, text)| regex.is_match(text))
+			.take(limit)
+			.map(|(line, text)| GrepMatch {
+				path: path.to_string(),
+				line,
+				content: text.to_string(),
+			}),
+	);
+}
+
+fn commit_blobs(
+	repo: &Repository,
+	commit_id: CommitId,
+) -> Result<Vec<(String, Oid)>> {
+	let tree = repo.find_commit(commit_id.into())?.tree()?;
+
+	let mut files = Vec::new();
+	tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+		if entry.kind() == Some(ObjectType::Blob) {
+			files.push((
+				format!(
+					"{}{}",
+					root,
+					String::from_utf8_lossy(entry.name_bytes())
+				),
+				entry.id(),
+			));
+		}
+		TreeWalkResult::Ok
+	})?;
+
+	files.sort();
+
+	Ok(files)
+}
+
+fn index_files(repo: &Repository) -> Result<Vec<(String, Oid)>> {
+	let index = repo.index()?;
+
+	Ok(index
+		.iter()
+		.map(|entry| {
+			(
+				String::from_utf8_lossy(&entry.path).to_string(),
+				entry.id,
+			)
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, write_commit_file};
+
+	#[test]
+	fn test_grep_revision_and_workdir() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		std::fs::create_dir(root.join("src")).unwrap();
+		let c1 = write_commit_file(
+			&repo,
+			"src/foo.rs",
+			"fn foo() {}\nfn bar() {}\n",
+			"c1",
+		);
+		write_commit_file(&repo, "bin", "fn foo\0", "c2");
+
+		std::fs::write(
+			root.join("src/foo.rs"),
+			"fn bar() {}\nfn foo_changed() {}\n",
+		)
+		.unwrap();
+
+		let mut steps = Vec::new();
+		let res = grep_files(
+			repo_path,
+			Some(c1),
+			r"fn \w+\(",
+			GREP_MAX_MATCHES,
+			|done, total| {
+				steps.push((done, total));
+				true
+			},
+		)
+		.unwrap()
+		.unwrap();
+
+		assert_eq!(steps.last(), Some(&(1, 1)));
+		assert!(!res.limit_reached);
+		assert_eq!(
+			res.matches,
+			vec![
+				GrepMatch {
+					path: String::from("src/foo.rs"),
+					line: 0,
+					content: String::from("fn foo() {}"),
+				},
+				GrepMatch {
+					path: String::from("src/foo.rs"),
+					line: 1,
+					content: String::from("fn bar() {}"),
+				},
+			]
+		);
+
+		let res =
+			grep_files(repo_path, Some(c1), "fn", 1, |_, _| true)
+				.unwrap()
+				.unwrap();
+
+		assert!(res.limit_reached);
+		assert_eq!(res.matches.len(), 1);
+
+		assert_eq!(
+			grep_files(repo_path, Some(c1), "fn", 1, |_, _| false)
+				.unwrap(),
+			None
+		);
+
+		let res = grep_files(
+			repo_path,
+			None,
+			"foo",
+			GREP_MAX_MATCHES,
+			|_, _| true,
+		)
+		.unwrap()
+		.unwrap();
+
+		assert_eq!(
+			res.matches,
+			vec![GrepMatch {
+				path: String::from("src/foo.rs"),
+				line: 1,
+				content: String::from("fn foo_changed() {}"),
+			}]
+		);
+
+		assert!(grep_files(
+			repo_path,
+			None,
+			"(",
+			GREP_MAX_MATCHES,
+			|_, _| true
+		)
+		.is_err());
+	}
+}
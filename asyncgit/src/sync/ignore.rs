@@ -1,14 +1,240 @@
-use super::utils::{repo, work_dir};
+use super::{
+	gitignore::{global_excludes_file, ignore_case, IgnorePattern},
+	status::{get_status, StatusItemType, StatusType},
+	utils::{repo, work_dir},
+	ShowUntrackedFilesConfig,
+};
 use crate::error::{Error, Result};
 use scopetime::scope_time;
 use std::{
-	fs::{File, OpenOptions},
+	fs::{self, File, OpenOptions},
 	io::{Read, Seek, SeekFrom, Write},
-	path::Path,
+	path::{Path, PathBuf},
 };
 
 static GITIGNORE: &str = ".gitignore";
 
+/// ignore file a new rule is added to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IgnoreTarget {
+	/// `.gitignore` in the workdir root
+	Root,
+	/// `.gitignore` in the folder containing the ignored item
+	Nested,
+	/// `.git/info/exclude` (not shared with others)
+	InfoExclude,
+	/// `core.excludesFile` (applies to all repos)
+	Global,
+}
+
+/// what a new ignore rule matches
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IgnoreKind {
+	/// exactly the selected file or folder
+	Path,
+	/// all files with the extension of the selected file
+	Extension,
+	/// the selected folder or the folder containing the selected file
+	Directory,
+}
+
+/// rule about to be added to an ignore file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewIgnoreRule {
+	///
+	pub pattern: String,
+	/// ignore file to append the pattern to
+	pub file: PathBuf,
+	/// `file` relative to the workdir if inside of it
+	pub file_name: String,
+	/// folder (relative to workdir) the pattern applies to
+	base: String,
+}
+
+/// creates the rule ignoring `path` (file or folder relative to the
+/// workdir) by `kind`, to be added to `target`
+pub fn new_ignore_rule(
+	repo_path: &str,
+	path: &str,
+	kind: IgnoreKind,
+	target: IgnoreTarget,
+) -> Result<NewIgnoreRule> {
+	scope_time!("new_ignore_rule");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let path = Path::new(path.trim_end_matches('/'));
+
+	if path.file_name() == Path::new(GITIGNORE).file_name() {
+		return Err(Error::Generic(String::from(
+			"cannot ignore gitignore",
+		)));
+	}
+
+	// the item the pattern names
+	let subject = match kind {
+		IgnoreKind::Path | IgnoreKind::Extension => path,
+		IgnoreKind::Directory if work_dir.join(path).is_dir() => path,
+		IgnoreKind::Directory => path
+			.parent()
+			.filter(|p| !p.as_os_str().is_empty())
+			.ok_or_else(|| {
+				Error::Generic(String::from(
+					"file is not in a folder",
+				))
+			})?,
+	};
+
+	let base = match target {
+		IgnoreTarget::Nested => subject
+			.parent()
+			.map(|p| p.to_string_lossy().to_string())
+			.unwrap_or_default(),
+		_ => String::new(),
+	};
+
+	let subject_str = subject.to_string_lossy();
+	let rel_subject = if base.is_empty() {
+		escape_pattern(&subject_str)
+	} else {
+		format!("/{}", escape_pattern(&subject_str[base.len() + 1..]))
+	};
+
+	let pattern = match kind {
+		IgnoreKind::Path => rel_subject,
+		IgnoreKind::Extension => subject
+			.extension()
+			.map(|ext| {
+				format!(
+					"*.{}",
+					escape_pattern(&ext.to_string_lossy())
+				)
+			})
+			.ok_or_else(|| {
+				Error::Generic(String::from("file has no extension"))
+			})?,
+		IgnoreKind::Directory => format!("{}/", rel_subject),
+	};
+
+	let file = match target {
+		IgnoreTarget::Root | IgnoreTarget::Nested => {
+			work_dir.join(&base).join(GITIGNORE)
+		}
+		IgnoreTarget::InfoExclude => {
+			repo.path().join("info").join("exclude")
+		}
+		IgnoreTarget::Global => global_excludes_file(&repo)
+			.ok_or_else(|| {
+				Error::Generic(String::from(
+					"no global excludes file found",
+				))
+			})?,
+	};
+
+	let file_name = file.strip_prefix(work_dir).map_or_else(
+		|_| file.to_string_lossy().to_string(),
+		|p| p.to_string_lossy().to_string(),
+	);
+
+	Ok(NewIgnoreRule {
+		pattern,
+		file,
+		file_name,
+		base,
+	})
+}
+
+/// escapes glob characters and the characters gitignore treats
+/// specially at the start (`!`, `#`) or end (spaces) of a line so
+/// `path` is matched literally
+fn escape_pattern(path: &str) -> String {
+	let leading = path.len() - path.trim_start_matches(' ').len();
+	let trailing = path.trim_end_matches(' ').len();
+
+	let mut res = String::with_capacity(path.len());
+	for (idx, c) in path.char_indices() {
+		let escape = match c {
+			'\\' | '*' | '?' | '[' => true,
+			'!' | '#' => idx == 0,
+			' ' => idx < leading || idx >= trailing,
+			_ => false,
+		};
+
+		if escape {
+			res.push('\\');
+		}
+		res.push(c);
+	}
+
+	res
+}
+
+/// untracked files that `rule` would hide
+pub fn ignore_rule_preview(
+	repo_path: &str,
+	rule: &NewIgnoreRule,
+) -> Result<Vec<String>> {
+	scope_time!("ignore_rule_preview");
+
+	let ignore_case = ignore_case(&repo(repo_path)?);
+	let pattern = IgnorePattern::parse(&rule.pattern)
+		.ok_or_else(|| {
+			Error::Generic(format!(
+				"invalid pattern: '{}'",
+				rule.pattern
+			))
+		})?
+		.ignore_case(ignore_case);
+
+	let untracked = get_status(
+		repo_path,
+		StatusType::WorkingDir,
+		Some(ShowUntrackedFilesConfig::All),
+	)?;
+
+	Ok(untracked
+		.into_iter()
+		.filter(|item| item.status == StatusItemType::New)
+		.map(|item| item.path)
+		.filter(|path| {
+			let rel_path = if rule.base.is_empty() {
+				Some(path.as_str())
+			} else {
+				path.strip_prefix(&rule.base)
+					.and_then(|p| p.strip_prefix('/'))
+			};
+
+			rel_path.map_or(false, |rel_path| {
+				// the file itself or one of its folders
+				Path::new(rel_path)
+					.ancestors()
+					.filter(|p| !p.as_os_str().is_empty())
+					.enumerate()
+					.any(|(idx, p)| {
+						pattern.matches(&p.to_string_lossy(), idx > 0)
+					})
+			})
+		})
+		.collect())
+}
+
+/// appends `rule` to its ignore file (created if missing)
+pub fn add_ignore_rule(
+	repo_path: &str,
+	rule: &NewIgnoreRule,
+) -> Result<()> {
+	scope_time!("add_ignore_rule");
+
+	// make sure the rule belongs to a valid repo
+	repo(repo_path)?;
+
+	if let Some(parent) = rule.file.parent() {
+		fs::create_dir_all(parent)?;
+	}
+
+	append_line(&rule.file, &rule.pattern)
+}
+
 /// add file or path to root ignore file
 pub fn add_to_ignore(
 	repo_path: &str,
@@ -28,8 +254,12 @@ pub fn add_to_ignore(
 
 	let ignore_file = work_dir(&repo)?.join(GITIGNORE);
 
-	let optional_newline = ignore_file.exists()
-		&& !file_ends_with_newline(&ignore_file)?;
+	append_line(&ignore_file, path_to_ignore)
+}
+
+fn append_line(ignore_file: &Path, line: &str) -> Result<()> {
+	let optional_newline =
+		ignore_file.exists() && !file_ends_with_newline(ignore_file)?;
 
 	let mut file = OpenOptions::new()
 		.append(true)
@@ -40,7 +270,7 @@ pub fn add_to_ignore(
 		file,
 		"{}{}",
 		if optional_newline { "\n" } else { "" },
-		path_to_ignore
+		line
 	)?;
 
 	Ok(())
@@ -60,7 +290,10 @@ fn file_ends_with_newline(file: &Path) -> Result<bool> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::sync::{tests::repo_init, utils::repo_write_file};
+	use crate::sync::{
+		tests::{get_statuses, repo_init},
+		utils::repo_write_file,
+	};
 	use io::BufRead;
 	use pretty_assertions::assert_eq;
 	use std::{fs::File, io, path::Path};
@@ -149,4 +382,152 @@ mod tests {
 		let lines = read_lines(&root.join(ignore_file_path)).unwrap();
 		assert_eq!(lines.count(), 1);
 	}
+
+	#[test]
+	fn test_new_ignore_rule() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir_all(root.join("sub/dir")).unwrap();
+		repo_write_file(&repo, "sub/dir/a.log", "a").unwrap();
+
+		let rule = |kind, target| {
+			let rule = new_ignore_rule(
+				repo_path,
+				"sub/dir/a.log",
+				kind,
+				target,
+			)
+			.unwrap();
+			(rule.pattern, rule.file_name)
+		};
+
+		assert_eq!(
+			rule(IgnoreKind::Path, IgnoreTarget::Root),
+			(
+				String::from("sub/dir/a.log"),
+				String::from(".gitignore")
+			)
+		);
+		assert_eq!(
+			rule(IgnoreKind::Path, IgnoreTarget::Nested),
+			(
+				String::from("/a.log"),
+				String::from("sub/dir/.gitignore")
+			)
+		);
+		assert_eq!(
+			rule(IgnoreKind::Extension, IgnoreTarget::InfoExclude),
+			(
+				String::from("*.log"),
+				String::from(".git/info/exclude")
+			)
+		);
+		assert_eq!(
+			rule(IgnoreKind::Directory, IgnoreTarget::Nested),
+			(String::from("/dir/"), String::from("sub/.gitignore"))
+		);
+		assert_eq!(
+			rule(IgnoreKind::Directory, IgnoreTarget::Root),
+			(String::from("sub/dir/"), String::from(".gitignore"))
+		);
+	}
+
+	#[test]
+	fn test_ignore_rule_preview_and_add() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir_all(root.join("sub/dir")).unwrap();
+		repo_write_file(&repo, "sub/dir/a.log", "a").unwrap();
+		repo_write_file(&repo, "sub/b.log", "b").unwrap();
+		repo_write_file(&repo, "c.log", "c").unwrap();
+		repo_write_file(&repo, "sub/dir/d.txt", "d").unwrap();
+
+		let rule = new_ignore_rule(
+			repo_path,
+			"sub/dir/a.log",
+			IgnoreKind::Extension,
+			IgnoreTarget::Nested,
+		)
+		.unwrap();
+		assert_eq!(
+			ignore_rule_preview(repo_path, &rule).unwrap(),
+			vec![String::from("sub/dir/a.log")]
+		);
+
+		let rule = new_ignore_rule(
+			repo_path,
+			"sub/dir/a.log",
+			IgnoreKind::Directory,
+			IgnoreTarget::InfoExclude,
+		)
+		.unwrap();
+		assert_eq!(
+			ignore_rule_preview(repo_path, &rule).unwrap(),
+			vec![
+				String::from("sub/dir/a.log"),
+				String::from("sub/dir/d.txt")
+			]
+		);
+
+		add_ignore_rule(repo_path, &rule).unwrap();
+
+		assert_eq!(
+			fs::read_to_string(repo.path().join("info/exclude"))
+				.unwrap()
+				.lines()
+				.last(),
+			Some("sub/dir/")
+		);
+		assert_eq!(get_statuses(repo_path), (2, 0));
+	}
+
+	#[test]
+	fn test_new_ignore_rule_escaped() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		repo_write_file(&repo, "[a]*?.txt", "a").unwrap();
+		repo_write_file(&repo, "!b.txt ", "b").unwrap();
+		repo_write_file(&repo, "#c", "c").unwrap();
+		repo_write_file(&repo, "a12.txt", "decoy").unwrap();
+
+		let rule = |path| {
+			new_ignore_rule(
+				repo_path,
+				path,
+				IgnoreKind::Path,
+				IgnoreTarget::Root,
+			)
+			.unwrap()
+		};
+
+		assert_eq!(rule("[a]*?.txt").pattern, "\\[a]\\*\\?.txt");
+		assert_eq!(rule("!b.txt ").pattern, "\\!b.txt\\ ");
+		assert_eq!(rule("#c").pattern, "\\#c");
+
+		for path in &["[a]*?.txt", "!b.txt ", "#c"] {
+			let rule = rule(path);
+			assert_eq!(
+				ignore_rule_preview(repo_path, &rule).unwrap(),
+				vec![String::from(*path)]
+			);
+			add_ignore_rule(repo_path, &rule).unwrap();
+		}
+
+		assert!(repo.status_should_ignore(Path::new("#c")).unwrap());
+		assert!(repo
+			.status_should_ignore(Path::new("!b.txt "))
+			.unwrap());
+		assert!(repo
+			.status_should_ignore(Path::new("[a]*?.txt"))
+			.unwrap());
+		assert!(!repo
+			.status_should_ignore(Path::new("a12.txt"))
+			.unwrap());
+	}
 }
@@ -0,0 +1,201 @@
+//! assume-unchanged and skip-worktree index flags
+//! (like `git update-index --[no-]assume-unchanged/--[no-]skip-worktree`)
+
+use super::utils::repo;
+use crate::error::{Error, Result};
+use git2::{IndexEntryExtendedFlag, IndexEntryFlag, Repository};
+use scopetime::scope_time;
+use std::path::Path;
+
+/// index flags hiding local changes of a file from status
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IndexFlags {
+	/// git assumes the file is unchanged and does not check it
+	pub assume_unchanged: bool,
+	/// git does not touch the file in the workdir
+	pub skip_worktree: bool,
+}
+
+/// file with `IndexFlags` set
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IndexFlaggedFile {
+	///
+	pub path: String,
+	///
+	pub flags: IndexFlags,
+}
+
+/// sets or clears the assume-unchanged flag of `path`
+pub fn set_assume_unchanged(
+	repo_path: &str,
+	path: &str,
+	value: bool,
+) -> Result<()> {
+	scope_time!("set_assume_unchanged");
+
+	let repo = repo(repo_path)?;
+
+	update_entry(&repo, path, |flags, _| {
+		set_bit(flags, IndexEntryFlag::VALID.bits(), value);
+	})
+}
+
+/// sets or clears the skip-worktree flag of `path`
+pub fn set_skip_worktree(
+	repo_path: &str,
+	path: &str,
+	value: bool,
+) -> Result<()> {
+	scope_time!("set_skip_worktree");
+
+	let repo = repo(repo_path)?;
+
+	update_entry(&repo, path, |_, flags_extended| {
+		set_bit(
+			flags_extended,
+			IndexEntryExtendedFlag::SKIP_WORKTREE.bits(),
+			value,
+		);
+	})
+}
+
+/// all files in the index with assume-unchanged or skip-worktree set
+pub fn get_index_flagged_files(
+	repo_path: &str,
+) -> Result<Vec<IndexFlaggedFile>> {
+	scope_time!("get_index_flagged_files");
+
+	let repo = repo(repo_path)?;
+	let mut index = repo.index()?;
+	index.read(true)?;
+
+	Ok(index
+		.iter()
+		.filter_map(|entry| {
+			let flags = IndexFlags {
+				assume_unchanged: entry.flags
+					& IndexEntryFlag::VALID.bits()
+					!= 0,
+				skip_worktree: entry.flags_extended
+					& IndexEntryExtendedFlag::SKIP_WORKTREE.bits()
+					!= 0,
+			};
+
+			if flags == IndexFlags::default() {
+				None
+			} else {
+				Some(IndexFlaggedFile {
+					path: String::from_utf8_lossy(&entry.path)
+						.to_string(),
+					flags,
+				})
+			}
+		})
+		.collect())
+}
+
+fn set_bit(flags: &mut u16, bit: u16, value: bool) {
+	if value {
+		*flags |= bit;
+	} else {
+		*flags &= !bit;
+	}
+}
+
+fn update_entry<F>(repo: &Repository, path: &str, f: F) -> Result<()>
+where
+	F: FnOnce(&mut u16, &mut u16),
+{
+	let mut index = repo.index()?;
+	index.read(true)?;
+
+	let mut entry =
+		index.get_path(Path::new(path), 0).ok_or_else(|| {
+			Error::Generic(format!("'{}' is not in the index", path))
+		})?;
+
+	f(&mut entry.flags, &mut entry.flags_extended);
+
+	index.add(&entry)?;
+	index.write()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::{get_statuses, repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+
+	#[test]
+	fn test_assume_unchanged() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "config.txt", "a", "c1");
+		repo_write_file(&repo, "config.txt", "local").unwrap();
+
+		assert_eq!(get_statuses(repo_path), (1, 0));
+
+		set_assume_unchanged(repo_path, "config.txt", true).unwrap();
+
+		assert_eq!(get_statuses(repo_path), (0, 0));
+		assert_eq!(
+			get_index_flagged_files(repo_path).unwrap(),
+			vec![IndexFlaggedFile {
+				path: String::from("config.txt"),
+				flags: IndexFlags {
+					assume_unchanged: true,
+					skip_worktree: false,
+				},
+			}]
+		);
+
+		set_assume_unchanged(repo_path, "config.txt", false).unwrap();
+
+		assert_eq!(get_statuses(repo_path), (1, 0));
+		assert!(get_index_flagged_files(repo_path)
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn test_skip_worktree() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "config.txt", "a", "c1");
+		repo_write_file(&repo, "config.txt", "local").unwrap();
+
+		set_skip_worktree(repo_path, "config.txt", true).unwrap();
+
+		assert_eq!(get_statuses(repo_path), (0, 0));
+		assert!(
+			get_index_flagged_files(repo_path).unwrap()[0]
+				.flags
+				.skip_worktree
+		);
+
+		set_skip_worktree(repo_path, "config.txt", false).unwrap();
+
+		assert_eq!(get_statuses(repo_path), (1, 0));
+	}
+
+	#[test]
+	fn test_untracked_fails() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+
+		assert!(
+			set_skip_worktree(repo_path, "new.txt", true).is_err()
+		);
+	}
+}
@@ -201,7 +201,8 @@ mod tests {
 			let diff = get_commit_diff(
 				&repo,
 				*commit_id,
-				Some("baz".into()),
+				&["baz".into()],
+				None,
 			)?;
 
 			let contains_file = diff.deltas().len() > 0;
@@ -228,7 +229,8 @@ mod tests {
 			let diff = get_commit_diff(
 				&repo,
 				*commit_id,
-				Some("bar".into()),
+				&["bar".into()],
+				None,
 			)?;
 
 			let contains_file = diff.deltas().len() > 0;
@@ -0,0 +1,1057 @@
+//! apply mbox patches as commits (like `git am`)
+
+use super::{
+	commit::signature_allow_undefined_name,
+	utils::{get_head_repo, repo, work_dir},
+	CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{
+	build::CheckoutBuilder, ApplyLocation, Diff, Oid, Repository,
+	RepositoryState, ResetType, Signature, StatusOptions, StatusShow,
+	Time, Tree,
+};
+use scopetime::scope_time;
+use std::{
+	convert::TryFrom,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// same folder (and marker file) git uses so `RepositoryState::ApplyMailbox` is detected
+const STATE_DIR: &str = "rebase-apply";
+const STATE_APPLYING: &str = "applying";
+const STATE_NEXT: &str = "next";
+const STATE_LAST: &str = "last";
+const STATE_ORIG_HEAD: &str = "orig-head";
+/// marks that the patch `next` points to failed to apply
+const STATE_STOPPED: &str = "stopped";
+
+///
+#[derive(PartialEq, Eq, Debug)]
+pub enum ApplyMailboxState {
+	///
+	Finished,
+	/// a patch did not apply, needs to be resolved manually and staged
+	Stopped,
+}
+
+///
+#[derive(PartialEq, Eq, Debug)]
+pub struct ApplyMailboxProgress {
+	/// 1-based index of the current patch
+	pub current: usize,
+	///
+	pub total: usize,
+	/// subject of the current patch
+	pub subject: String,
+	/// path of the current patch file (to apply it manually)
+	pub patch_file: PathBuf,
+}
+
+/// patch (mail) of a mailbox
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MailPatch {
+	///
+	pub author: String,
+	///
+	pub email: String,
+	/// author time (seconds since epoch) and utc offset in minutes
+	pub time: Option<(i64, i32)>,
+	/// commit message subject without `[PATCH]` prefix
+	pub subject: String,
+	/// full commit message
+	pub message: String,
+	diff: String,
+}
+
+/// parses a mbox file (as written by `git format-patch`)
+pub fn mailbox_patches(
+	repo_path: &str,
+	mbox_file: &Path,
+) -> Result<Vec<MailPatch>> {
+	scope_time!("mailbox_patches");
+
+	let repo = repo(repo_path)?;
+
+	read_mailbox(&repo, mbox_file)?
+		.iter()
+		.map(|mail| parse_mail(mail))
+		.collect()
+}
+
+/// apply all patches of a mbox file as commits on HEAD keeping
+/// author and message.
+///
+/// stops with `ApplyMailboxState::Stopped` if a patch does not apply,
+/// see `continue_apply_mailbox` and `abort_apply_mailbox`
+pub fn apply_mailbox(
+	repo_path: &str,
+	mbox_file: &Path,
+) -> Result<ApplyMailboxState> {
+	scope_time!("apply_mailbox");
+
+	let repo = repo(repo_path)?;
+
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"repository has a pending operation",
+		)));
+	}
+
+	// like `git am`, so the commits contain nothing but the patches
+	if has_tracked_changes(&repo, StatusShow::IndexAndWorkdir)? {
+		return Err(Error::Generic(String::from(
+			"index or working tree has uncommitted changes",
+		)));
+	}
+
+	let mails = read_mailbox(&repo, mbox_file)?;
+	if mails.is_empty() {
+		return Err(Error::Generic(String::from("no patches found")));
+	}
+
+	// validate all upfront, so we do not stop in the middle for that
+	for mail in &mails {
+		parse_mail(mail)?;
+	}
+
+	let dir = state_dir(&repo);
+	fs::create_dir_all(&dir)?;
+
+	for (idx, mail) in mails.iter().enumerate() {
+		fs::write(dir.join(patch_name(idx + 1)), mail)?;
+	}
+
+	fs::write(dir.join(STATE_NEXT), "1\n")?;
+	fs::write(dir.join(STATE_LAST), format!("{}\n", mails.len()))?;
+
+	if let Ok(head) = get_head_repo(&repo) {
+		fs::write(
+			dir.join(STATE_ORIG_HEAD),
+			format!("{}\n", head.to_string()),
+		)?;
+	}
+
+	fs::write(dir.join(STATE_APPLYING), "")?;
+
+	apply_pending(&repo)
+}
+
+/// continue a stopped mailbox apply: commits the currently staged
+/// changes for the stopped patch (skips it if nothing is staged)
+/// and applies the remaining ones
+pub fn continue_apply_mailbox(
+	repo_path: &str,
+) -> Result<ApplyMailboxState> {
+	scope_time!("continue_apply_mailbox");
+
+	let repo = repo(repo_path)?;
+
+	if repo.index()?.has_conflicts() {
+		return Ok(ApplyMailboxState::Stopped);
+	}
+
+	let dir = state_dir(&repo);
+
+	if dir.join(STATE_STOPPED).exists() {
+		let next = read_number(&dir.join(STATE_NEXT))?;
+		let mail = parse_mail(&fs::read_to_string(
+			dir.join(patch_name(next)),
+		)?)?;
+
+		if index_differs_from_head(&repo)? {
+			commit_mail(&repo, &mail)?;
+		}
+
+		fs::remove_file(dir.join(STATE_STOPPED))?;
+		fs::write(dir.join(STATE_NEXT), format!("{}\n", next + 1))?;
+	}
+
+	apply_pending(&repo)
+}
+
+/// abort a pending mailbox apply, resets to where HEAD was before.
+///
+/// like `git am --abort` files changed by the already applied
+/// patches are reset in the working tree as well, other changes
+/// (made while resolving the stopped patch) are kept
+pub fn abort_apply_mailbox(repo_path: &str) -> Result<()> {
+	scope_time!("abort_apply_mailbox");
+
+	let repo = repo(repo_path)?;
+
+	let dir = state_dir(&repo);
+
+	if let Ok(orig_head) =
+		fs::read_to_string(dir.join(STATE_ORIG_HEAD))
+	{
+		let obj =
+			repo.find_object(Oid::from_str(orig_head.trim())?, None)?;
+
+		undo_applied_patches(&repo, &obj.peel_to_tree()?)?;
+
+		// this also cleans up the state folder
+		repo.reset(&obj, ResetType::Mixed, None)?;
+	}
+
+	if dir.exists() {
+		fs::remove_dir_all(dir)?;
+	}
+
+	Ok(())
+}
+
+/// two-way checkout from HEAD to `orig_tree` of the files the applied
+/// patches changed, files modified since HEAD are left untouched
+fn undo_applied_patches(
+	repo: &Repository,
+	orig_tree: &Tree,
+) -> Result<()> {
+	let head = repo.head()?.peel_to_commit()?;
+	let head_tree = head.tree()?;
+
+	let diff = repo.diff_tree_to_tree(
+		Some(orig_tree),
+		Some(&head_tree),
+		None,
+	)?;
+
+	if diff.deltas().len() == 0 {
+		return Ok(());
+	}
+
+	// unstage everything so the index does not get in the way of
+	// comparing the working tree with HEAD
+	repo.reset(head.as_object(), ResetType::Mixed, None)?;
+
+	let mut checkout = CheckoutBuilder::new();
+	checkout.safe().allow_conflicts(true);
+	for delta in diff.deltas() {
+		for file in &[delta.old_file(), delta.new_file()] {
+			if let Some(path) = file.path() {
+				checkout.path(path);
+			}
+		}
+	}
+
+	repo.checkout_tree(orig_tree.as_object(), Some(&mut checkout))?;
+
+	Ok(())
+}
+
+///
+pub fn apply_mailbox_progress(
+	repo_path: &str,
+) -> Result<ApplyMailboxProgress> {
+	scope_time!("apply_mailbox_progress");
+
+	let repo = repo(repo_path)?;
+
+	let dir = state_dir(&repo);
+	let current = read_number(&dir.join(STATE_NEXT))?;
+	let total = read_number(&dir.join(STATE_LAST))?;
+	let patch_file = dir.join(patch_name(current));
+
+	let subject = fs::read_to_string(&patch_file)
+		.ok()
+		.and_then(|mail| parse_mail(&mail).ok())
+		.map(|mail| mail.subject)
+		.unwrap_or_default();
+
+	Ok(ApplyMailboxProgress {
+		current,
+		total,
+		subject,
+		patch_file,
+	})
+}
+
+fn apply_pending(repo: &Repository) -> Result<ApplyMailboxState> {
+	let dir = state_dir(repo);
+
+	let last = read_number(&dir.join(STATE_LAST))?;
+	let mut next = read_number(&dir.join(STATE_NEXT))?;
+
+	while next <= last {
+		let mail = parse_mail(&fs::read_to_string(
+			dir.join(patch_name(next)),
+		)?)?;
+
+		let diff = Diff::from_buffer(mail.diff.as_bytes())?;
+
+		if repo.apply(&diff, ApplyLocation::Both, None).is_err() {
+			fs::write(dir.join(STATE_STOPPED), "")?;
+			return Ok(ApplyMailboxState::Stopped);
+		}
+
+		commit_mail(repo, &mail)?;
+
+		next += 1;
+		fs::write(dir.join(STATE_NEXT), format!("{}\n", next))?;
+	}
+
+	fs::remove_dir_all(dir)?;
+
+	Ok(ApplyMailboxState::Finished)
+}
+
+fn commit_mail(
+	repo: &Repository,
+	mail: &MailPatch,
+) -> Result<CommitId> {
+	let committer = signature_allow_undefined_name(repo)?;
+	let author = mail.time.map_or_else(
+		|| Signature::now(&mail.author, &mail.email),
+		|(time, offset)| {
+			Signature::new(
+				&mail.author,
+				&mail.email,
+				&Time::new(time, offset),
+			)
+		},
+	)?;
+
+	let mut index = repo.index()?;
+	let tree_id = index.write_tree()?;
+	let tree = repo.find_tree(tree_id)?;
+
+	let parents = if let Ok(id) = get_head_repo(repo) {
+		vec![repo.find_commit(id.into())?]
+	} else {
+		Vec::new()
+	};
+
+	let parents = parents.iter().collect::<Vec<_>>();
+
+	Ok(repo
+		.commit(
+			Some("HEAD"),
+			&author,
+			&committer,
+			&mail.message,
+			&tree,
+			parents.as_slice(),
+		)?
+		.into())
+}
+
+/// untracked files are not taken into account
+fn has_tracked_changes(
+	repo: &Repository,
+	show: StatusShow,
+) -> Result<bool> {
+	let mut opt = StatusOptions::new();
+	opt.show(show)
+		.include_untracked(false)
+		.include_ignored(false)
+		.exclude_submodules(true);
+
+	Ok(!repo.statuses(Some(&mut opt))?.is_empty())
+}
+
+fn index_differs_from_head(repo: &Repository) -> Result<bool> {
+	let tree = repo.head()?.peel_to_tree()?;
+	let diff = repo.diff_tree_to_index(Some(&tree), None, None)?;
+
+	Ok(diff.deltas().len() > 0)
+}
+
+fn state_dir(repo: &Repository) -> PathBuf {
+	repo.path().join(STATE_DIR)
+}
+
+fn patch_name(patch_no: usize) -> String {
+	format!("{:04}", patch_no)
+}
+
+fn read_number(path: &Path) -> Result<usize> {
+	fs::read_to_string(path)?.trim().parse().map_err(|_| {
+		Error::Generic(format!(
+			"invalid state file: {}",
+			path.display()
+		))
+	})
+}
+
+/// relative paths are interpreted relative to the repo workdir
+fn read_mailbox(
+	repo: &Repository,
+	mbox_file: &Path,
+) -> Result<Vec<String>> {
+	let mbox_file = if mbox_file.is_relative() {
+		work_dir(repo)?.join(mbox_file)
+	} else {
+		mbox_file.to_path_buf()
+	};
+
+	Ok(split_mailbox(&fs::read_to_string(mbox_file)?))
+}
+
+/// splits mbox content at the `From <sha> <date>` lines starting each mail
+fn split_mailbox(content: &str) -> Vec<String> {
+	let mut lines = content
+		.split('\n')
+		.map(|line| format!("{}\n", line))
+		.collect::<Vec<_>>();
+	if content.ends_with('\n') {
+		lines.pop();
+	}
+
+	let mut mails = Vec::new();
+	let mut current = String::new();
+
+	for (idx, line) in lines.iter().enumerate() {
+		let starts_mail = line.starts_with("From ")
+			&& lines
+				.get(idx + 1)
+				.map_or(false, |next| is_header(next));
+
+		if starts_mail && !current.trim().is_empty() {
+			mails.push(std::mem::take(&mut current));
+		}
+
+		current.push_str(line);
+	}
+
+	if !current.trim().is_empty() {
+		mails.push(current);
+	}
+
+	mails
+}
+
+fn is_header(line: &str) -> bool {
+	!line.starts_with(char::is_whitespace)
+		&& split_once(line, ":").map_or(false, |(name, _)| {
+			!name.is_empty() && !name.contains(' ')
+		})
+}
+
+fn parse_mail(mail: &str) -> Result<MailPatch> {
+	let mut lines = mail.lines().peekable();
+
+	if lines.peek().map_or(false, |l| l.starts_with("From ")) {
+		lines.next();
+	}
+
+	// unfold headers
+	let mut headers: Vec<(String, String)> = Vec::new();
+	for line in lines.by_ref() {
+		if line.trim().is_empty() {
+			break;
+		}
+
+		if line.starts_with(char::is_whitespace) {
+			if let Some((_, value)) = headers.last_mut() {
+				value.push(' ');
+				value.push_str(line.trim());
+			}
+		} else if let Some((name, value)) = split_once(line, ":") {
+			headers.push((
+				name.trim().to_lowercase(),
+				value.trim().to_string(),
+			));
+		}
+	}
+
+	let header = |name: &str| {
+		headers
+			.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, v)| decode_header(v))
+	};
+
+	let (author, email) = header("from")
+		.and_then(|from| parse_address(&from))
+		.ok_or_else(|| {
+			Error::Generic(String::from(
+				"invalid mail: no author found",
+			))
+		})?;
+
+	let subject =
+		strip_subject_prefix(&header("subject").unwrap_or_default())
+			.to_string();
+
+	let time = header("date").and_then(|date| parse_date(&date));
+
+	let mut body = Vec::new();
+	let mut diff = String::new();
+	let mut in_body = true;
+	// remaining old and new lines of the current hunk
+	let mut hunk_lines = (0, 0);
+	for line in lines {
+		if diff.is_empty() && line.starts_with("diff --git ") {
+			in_body = false;
+		} else if in_body && line == "---" {
+			// diffstat follows
+			in_body = false;
+			continue;
+		}
+
+		if in_body {
+			body.push(line);
+		} else if !diff.is_empty() || line.starts_with("diff --git ")
+		{
+			if hunk_lines == (0, 0) {
+				// the signature (`-- \n<version>`) follows the diff,
+				// inside a hunk this is a removed `- ` line
+				if line == "-- " {
+					break;
+				}
+				hunk_lines =
+					hunk_header_lines(line).unwrap_or_default();
+			} else {
+				hunk_lines = count_hunk_line(hunk_lines, line);
+			}
+
+			diff.push_str(line);
+			diff.push('\n');
+		}
+	}
+
+	if diff.trim().is_empty() {
+		return Err(Error::Generic(format!(
+			"patch '{}' contains no diff",
+			subject
+		)));
+	}
+
+	let body = body.join("\n");
+	let body = body.trim();
+
+	let message = if body.is_empty() {
+		format!("{}\n", subject)
+	} else {
+		format!("{}\n\n{}\n", subject, body)
+	};
+
+	Ok(MailPatch {
+		author,
+		email,
+		time,
+		subject,
+		message,
+		diff,
+	})
+}
+
+/// number of old and new lines of a `@@ -1,2 +1,3 @@` hunk header
+fn hunk_header_lines(line: &str) -> Option<(usize, usize)> {
+	let mut ranges = line.strip_prefix("@@ -")?.split(' ');
+
+	let count = |range: Option<&str>| {
+		range.map(|range| {
+			split_once(range, ",")
+				.map_or(Some(1), |(_, count)| count.parse().ok())
+		})
+	};
+
+	let old = count(ranges.next())??;
+	let new =
+		count(ranges.next().and_then(|r| r.strip_prefix('+')))??;
+
+	Some((old, new))
+}
+
+fn count_hunk_line(
+	(old, new): (usize, usize),
+	line: &str,
+) -> (usize, usize) {
+	match line.chars().next() {
+		Some('-') => (old.saturating_sub(1), new),
+		Some('+') => (old, new.saturating_sub(1)),
+		Some('\\') => (old, new),
+		// some mailers strip the space of empty context lines
+		_ => (old.saturating_sub(1), new.saturating_sub(1)),
+	}
+}
+
+fn split_once<'a>(
+	s: &'a str,
+	pat: &str,
+) -> Option<(&'a str, &'a str)> {
+	s.find(pat).map(|pos| (&s[..pos], &s[pos + pat.len()..]))
+}
+
+/// `[PATCH v2 1/3] subject` -> `subject`
+fn strip_subject_prefix(subject: &str) -> &str {
+	let mut subject = subject.trim();
+
+	while subject.starts_with('[') {
+		if let Some(end) = subject.find(']') {
+			subject = subject[end + 1..].trim_start();
+		} else {
+			break;
+		}
+	}
+
+	subject
+}
+
+/// `Name <mail>` / `"Name" <mail>` / `mail`
+fn parse_address(from: &str) -> Option<(String, String)> {
+	if let Some((name, rest)) = split_once(from, "<") {
+		let email = rest.split('>').next()?.trim();
+		let name = name.trim().trim_matches('"').trim();
+		let name = if name.is_empty() { email } else { name };
+
+		Some((name.to_string(), email.to_string()))
+	} else {
+		let email = from.trim();
+
+		(!email.is_empty())
+			.then(|| (email.to_string(), email.to_string()))
+	}
+}
+
+/// decodes RFC 2047 encoded words (`=?UTF-8?q?J=C3=B6rg?=`)
+fn decode_header(value: &str) -> String {
+	let mut res = String::new();
+	let mut rest = value;
+	let mut last_was_encoded = false;
+
+	while let Some(start) = rest.find("=?") {
+		let (before, word) = rest.split_at(start);
+
+		let decoded = split_once(&word[2..], "?").and_then(
+			|(_charset, word)| {
+				let (encoding, word) = split_once(word, "?")?;
+				let (text, rest) = split_once(word, "?=")?;
+
+				let bytes = match encoding {
+					"q" | "Q" => decode_q(text),
+					"b" | "B" => decode_base64(text)?,
+					_ => return None,
+				};
+
+				Some((
+					String::from_utf8_lossy(&bytes).into_owned(),
+					rest,
+				))
+			},
+		);
+
+		if let Some((decoded, after)) = decoded {
+			// whitespace between encoded words is dropped
+			if !(last_was_encoded && before.trim().is_empty()) {
+				res.push_str(before);
+			}
+			res.push_str(&decoded);
+			rest = after;
+			last_was_encoded = true;
+		} else {
+			res.push_str(before);
+			res.push_str("=?");
+			rest = &word[2..];
+			last_was_encoded = false;
+		}
+	}
+
+	res.push_str(rest);
+	res
+}
+
+fn decode_q(text: &str) -> Vec<u8> {
+	let bytes = text.as_bytes();
+	let mut res = Vec::with_capacity(bytes.len());
+	let mut idx = 0;
+
+	while idx < bytes.len() {
+		match bytes[idx] {
+			b'_' => res.push(b' '),
+			b'=' => {
+				let hex = bytes
+					.get(idx + 1..idx + 3)
+					.and_then(|hex| std::str::from_utf8(hex).ok())
+					.and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+				if let Some(b) = hex {
+					res.push(b);
+					idx += 2;
+				} else {
+					res.push(b'=');
+				}
+			}
+			b => res.push(b),
+		}
+		idx += 1;
+	}
+
+	res
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+	let mut res = Vec::with_capacity(text.len() * 3 / 4);
+	let mut buf = 0_u32;
+	let mut bits = 0;
+
+	for c in text.bytes().filter(|c| *c != b'=') {
+		let v = match c {
+			b'A'..=b'Z' => c - b'A',
+			b'a'..=b'z' => c - b'a' + 26,
+			b'0'..=b'9' => c - b'0' + 52,
+			b'+' => 62,
+			b'/' => 63,
+			_ => return None,
+		};
+
+		buf = (buf << 6) | u32::from(v);
+		bits += 6;
+
+		if bits >= 8 {
+			bits -= 8;
+			res.push(u8::try_from((buf >> bits) & 0xff).ok()?);
+		}
+	}
+
+	Some(res)
+}
+
+/// parses RFC 2822 dates (`Mon, 17 Sep 2001 12:00:00 +0200`) to
+/// seconds since epoch and utc offset in minutes
+fn parse_date(date: &str) -> Option<(i64, i32)> {
+	const MONTHS: [&str; 12] = [
+		"jan", "feb", "mar", "apr", "may", "jun", "jul", "aug",
+		"sep", "oct", "nov", "dec",
+	];
+
+	let mut parts =
+		date.split_whitespace().skip_while(|p| p.ends_with(','));
+
+	let day: i64 = parts.next()?.parse().ok()?;
+	let month = parts.next()?.to_lowercase();
+	let month = i64::try_from(
+		MONTHS.iter().position(|m| month.starts_with(m))? + 1,
+	)
+	.ok()?;
+	let year: i64 = parts.next()?.parse().ok()?;
+
+	let mut time = parts.next()?.split(':');
+	let hours: i64 = time.next()?.parse().ok()?;
+	let minutes: i64 = time.next()?.parse().ok()?;
+	let seconds: i64 =
+		time.next().map_or(Some(0), |s| s.parse().ok())?;
+
+	let offset = parts.next().unwrap_or("+0000");
+	let (sign, offset) = offset.split_at(1);
+	let offset: i32 = offset.parse().ok()?;
+	let offset = offset / 100 * 60 + offset % 100;
+	let offset = if sign == "-" { -offset } else { offset };
+
+	// see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+	let y = if month <= 2 { year - 1 } else { year };
+	let era = y.div_euclid(400);
+	let yoe = y.rem_euclid(400);
+	let mp = (month + 9) % 12;
+	let doy = (153 * mp + 2) / 5 + day - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	let days = era * 146_097 + doe - 719_468;
+
+	Some((
+		days * 86400 + hours * 3600 + minutes * 60 + seconds
+			- i64::from(offset) * 60,
+		offset,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		abort_apply_mailbox, apply_mailbox, apply_mailbox_progress,
+		continue_apply_mailbox, decode_header, mailbox_patches,
+		parse_date, parse_mail, ApplyMailboxState,
+	};
+	use crate::sync::{
+		commit, format_patches, get_commit_details, repo_state,
+		stage_add_file, tests::repo_init, utils::get_head_repo,
+		RepoState,
+	};
+	use std::{fs, path::Path};
+
+	#[test]
+	fn test_decode_header() {
+		assert_eq!(decode_header("plain"), "plain");
+		assert_eq!(
+			decode_header("=?UTF-8?q?J=C3=B6rg?= <j@x.org>"),
+			"Jörg <j@x.org>"
+		);
+		assert_eq!(
+			decode_header("=?UTF-8?q?a_b?= =?UTF-8?B?w7Y=?="),
+			"a bö"
+		);
+	}
+
+	#[test]
+	fn test_parse_date() {
+		assert_eq!(
+			parse_date("Thu, 1 Jan 1970 00:00:00 +0000"),
+			Some((0, 0))
+		);
+		assert_eq!(
+			parse_date("Sat, 17 Oct 2026 10:00:00 +0200"),
+			Some((1_792_224_000, 120))
+		);
+	}
+
+	#[test]
+	fn test_parse_mail_signature() {
+		let diff = "diff --git a/foo b/foo\n\
+			--- a/foo\n\
+			+++ b/foo\n\
+			@@ -1,3 +1,2 @@\n \
+			a\n\
+			-- \n \
+			b\n";
+		let mail = format!(
+			"From: name <name@x.org>\n\
+			Subject: [PATCH] remove dash\n\
+			\n\
+			---\n \
+			foo | 1 -\n\
+			\n\
+			{}-- \n\
+			2.30.0\n\n",
+			diff
+		);
+
+		let patch = parse_mail(&mail).unwrap();
+
+		assert_eq!(patch.subject, "remove dash");
+		assert_eq!(patch.diff, diff);
+
+		let without_signature =
+			mail.split("-- \n2.30.0").next().unwrap();
+
+		assert_eq!(parse_mail(without_signature).unwrap().diff, diff);
+	}
+
+	#[test]
+	fn test_apply_mailbox() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = get_head_repo(&repo).unwrap();
+
+		fs::write(root.join("foo.txt"), "a\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let c1 = commit(repo_path, "first\n\nwith body").unwrap();
+
+		fs::write(root.join("foo.txt"), "a\nb\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let c2 = commit(repo_path, "second").unwrap();
+
+		let files =
+			format_patches(repo_path, &[c2, c1], Path::new("out"))
+				.unwrap();
+
+		let mbox = files
+			.iter()
+			.map(|f| fs::read_to_string(f).unwrap())
+			.collect::<String>();
+		fs::write(root.join("all.mbox"), mbox).unwrap();
+		fs::remove_dir_all(root.join("out")).unwrap();
+
+		let patches =
+			mailbox_patches(repo_path, Path::new("all.mbox"))
+				.unwrap();
+		assert_eq!(patches.len(), 2);
+		assert_eq!(patches[0].subject, "first");
+		assert_eq!(patches[0].message, "first\n\nwith body\n");
+		assert_eq!(patches[1].author, "name");
+
+		repo.reset(
+			&repo.find_object(base.into(), None).unwrap(),
+			git2::ResetType::Hard,
+			None,
+		)
+		.unwrap();
+
+		// staged changes would end up in the first applied commit
+		fs::write(root.join("other.txt"), "staged\n").unwrap();
+		stage_add_file(repo_path, Path::new("other.txt")).unwrap();
+		assert!(
+			apply_mailbox(repo_path, Path::new("all.mbox")).is_err()
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		repo.reset(
+			&repo.find_object(base.into(), None).unwrap(),
+			git2::ResetType::Hard,
+			None,
+		)
+		.unwrap();
+
+		assert_eq!(
+			apply_mailbox(repo_path, Path::new("all.mbox")).unwrap(),
+			ApplyMailboxState::Finished
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		let head = get_head_repo(&repo).unwrap();
+		let details = get_commit_details(repo_path, head).unwrap();
+		assert_eq!(details.message.unwrap().subject, "second");
+		assert_eq!(details.author.name, "name");
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			"a\nb\n"
+		);
+	}
+
+	#[test]
+	fn test_apply_mailbox_stop_continue_abort() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = get_head_repo(&repo).unwrap();
+
+		fs::write(root.join("foo.txt"), "a\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let c1 = commit(repo_path, "first").unwrap();
+
+		let files =
+			format_patches(repo_path, &[c1], Path::new("out"))
+				.unwrap();
+		fs::copy(&files[0], root.join("p.mbox")).unwrap();
+		fs::remove_dir_all(root.join("out")).unwrap();
+
+		repo.reset(
+			&repo.find_object(base.into(), None).unwrap(),
+			git2::ResetType::Hard,
+			None,
+		)
+		.unwrap();
+
+		// conflicting file prevents the patch from applying
+		fs::write(root.join("foo.txt"), "x\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		commit(repo_path, "conflicting").unwrap();
+
+		assert_eq!(
+			apply_mailbox(repo_path, Path::new("p.mbox")).unwrap(),
+			ApplyMailboxState::Stopped
+		);
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::ApplyMailbox
+		);
+
+		let progress = apply_mailbox_progress(repo_path).unwrap();
+		assert_eq!(progress.current, 1);
+		assert_eq!(progress.total, 1);
+		assert_eq!(progress.subject, "first");
+
+		abort_apply_mailbox(repo_path).unwrap();
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		// changes made while stopped are kept on abort
+		assert_eq!(
+			apply_mailbox(repo_path, Path::new("p.mbox")).unwrap(),
+			ApplyMailboxState::Stopped
+		);
+		fs::write(root.join("foo.txt"), "y\n").unwrap();
+
+		abort_apply_mailbox(repo_path).unwrap();
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			"y\n"
+		);
+		fs::write(root.join("foo.txt"), "x\n").unwrap();
+
+		// now resolve manually and continue
+		assert_eq!(
+			apply_mailbox(repo_path, Path::new("p.mbox")).unwrap(),
+			ApplyMailboxState::Stopped
+		);
+
+		fs::write(root.join("foo.txt"), "a\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+
+		assert_eq!(
+			continue_apply_mailbox(repo_path).unwrap(),
+			ApplyMailboxState::Finished
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		let head = get_head_repo(&repo).unwrap();
+		let details = get_commit_details(repo_path, head).unwrap();
+		assert_eq!(details.message.unwrap().subject, "first");
+	}
+
+	#[test]
+	fn test_abort_apply_mailbox_after_applied_patches() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("c.txt"), "c\n").unwrap();
+		stage_add_file(repo_path, Path::new("c.txt")).unwrap();
+		let base = commit(repo_path, "base").unwrap();
+
+		fs::write(root.join("a.txt"), "a\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		let c1 = commit(repo_path, "first").unwrap();
+
+		fs::write(root.join("b.txt"), "b\n").unwrap();
+		stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+		let c2 = commit(repo_path, "second").unwrap();
+
+		fs::write(root.join("c.txt"), "c\nd\n").unwrap();
+		stage_add_file(repo_path, Path::new("c.txt")).unwrap();
+		let c3 = commit(repo_path, "third").unwrap();
+
+		let files = format_patches(
+			repo_path,
+			&[c1, c2, c3],
+			Path::new("out"),
+		)
+		.unwrap();
+		let mbox = files
+			.iter()
+			.map(|f| fs::read_to_string(f).unwrap())
+			.collect::<String>();
+		fs::write(root.join("all.mbox"), mbox).unwrap();
+		fs::remove_dir_all(root.join("out")).unwrap();
+
+		repo.reset(
+			&repo.find_object(base.into(), None).unwrap(),
+			git2::ResetType::Hard,
+			None,
+		)
+		.unwrap();
+
+		// third patch does not apply on top of this
+		fs::write(root.join("c.txt"), "x\n").unwrap();
+		stage_add_file(repo_path, Path::new("c.txt")).unwrap();
+		let orig_head = commit(repo_path, "conflicting").unwrap();
+
+		assert_eq!(
+			apply_mailbox(repo_path, Path::new("all.mbox")).unwrap(),
+			ApplyMailboxState::Stopped
+		);
+		assert_eq!(
+			apply_mailbox_progress(repo_path).unwrap().current,
+			3
+		);
+		assert!(root.join("b.txt").exists());
+
+		// resolving the stopped patch
+		fs::write(root.join("c.txt"), "y\n").unwrap();
+
+		abort_apply_mailbox(repo_path).unwrap();
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head_repo(&repo).unwrap(), orig_head);
+
+		// the applied patches are gone, own changes are kept
+		assert!(!root.join("a.txt").exists());
+		assert!(!root.join("b.txt").exists());
+		assert_eq!(
+			fs::read_to_string(root.join("c.txt")).unwrap(),
+			"y\n"
+		);
+	}
+}
@@ -3,8 +3,10 @@
 //TODO: remove once we have this activated on the toplevel
 #![deny(clippy::expect_used)]
 
+mod apply;
 pub mod blame;
 pub mod branch;
+mod clean;
 mod commit;
 mod commit_details;
 mod commit_files;
@@ -12,15 +14,22 @@ mod commits_info;
 mod config;
 pub mod cred;
 pub mod diff;
+mod filemode;
+mod gitignore;
+mod grep;
 mod hooks;
 mod hunks;
 mod ignore;
+mod index_flags;
 mod logwalker;
+mod mailbox;
 mod merge;
+mod mv;
 mod patches;
 mod rebase;
 pub mod remotes;
 mod reset;
+mod restore;
 mod staging;
 mod stash;
 mod state;
@@ -29,7 +38,14 @@ mod tags;
 mod tree;
 pub mod utils;
 
-pub use blame::{blame_file, BlameHunk, FileBlame};
+pub use apply::{
+	apply_patch, patch_preview, ApplyLocation, PatchFile, PatchHunk,
+};
+pub use blame::{
+	blame_file, blame_file_incremental, blame_origin_in_parent,
+	BlameDetection, BlameHunk, BlameOptions, BlameOrigin,
+	BlameProgress, FileBlame,
+};
 pub use branch::{
 	branch_compare_upstream, checkout_branch, config_is_pull_rebase,
 	create_branch, delete_branch, get_branch_remote,
@@ -38,6 +54,7 @@ pub use branch::{
 	merge_rebase::merge_upstream_rebase, rename::rename_branch,
 	validate_branch_name, BranchCompare, BranchInfo,
 };
+pub use clean::{clean_paths, get_clean_candidates};
 pub use commit::{amend, commit, tag};
 pub use commit_details::{
 	get_commit_details, CommitDetails, CommitMessage, CommitSignature,
@@ -47,31 +64,57 @@ pub use commits_info::{
 	get_commit_info, get_commits_info, CommitId, CommitInfo,
 };
 pub use config::{
-	get_config_string, untracked_files_config,
+	diff_algorithm_config, get_config_string, untracked_files_config,
 	ShowUntrackedFilesConfig,
 };
 pub use diff::get_diff_commit;
+pub use filemode::toggle_executable;
 pub use git2::BranchType;
+pub use gitignore::{
+	check_ignore, get_ignored_files, IgnoreRule, IgnoredFile,
+};
+pub use grep::{grep_files, GrepMatch, GrepResult, GREP_MAX_MATCHES};
 pub use hooks::{
 	hooks_commit_msg, hooks_post_commit, hooks_pre_commit, HookResult,
 };
 pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
-pub use ignore::add_to_ignore;
+pub use ignore::{
+	add_ignore_rule, add_to_ignore, ignore_rule_preview,
+	new_ignore_rule, IgnoreKind, IgnoreTarget, NewIgnoreRule,
+};
+pub use index_flags::{
+	get_index_flagged_files, set_assume_unchanged, set_skip_worktree,
+	IndexFlaggedFile, IndexFlags,
+};
 pub use logwalker::{LogWalker, LogWalkerFilter};
+pub use mailbox::{
+	abort_apply_mailbox, apply_mailbox, apply_mailbox_progress,
+	continue_apply_mailbox, mailbox_patches, ApplyMailboxProgress,
+	ApplyMailboxState, MailPatch,
+};
 pub use merge::{
 	abort_merge, abort_pending_rebase, continue_pending_rebase,
 	merge_branch, merge_commit, merge_msg, mergehead_ids,
 	rebase_progress,
 };
+pub use mv::{move_path, remove_from_index};
+pub use patches::{
+	format_patches, get_diff_commit_patch, get_diff_commits_patch,
+	get_diff_patch,
+};
 pub use rebase::rebase_branch;
 pub use remotes::{
 	get_default_remote, get_remotes, push::AsyncProgress,
 	tags::PushTagsProgress,
 };
 pub use reset::{reset_stage, reset_workdir};
-pub use staging::{discard_lines, stage_lines};
+pub use restore::{restore_file, restore_hunk, save_revision_file};
+pub use staging::{
+	discard_lines, hunk_edit_text, stage_edited_hunk, stage_lines,
+};
 pub use stash::{
-	get_stashes, stash_apply, stash_drop, stash_pop, stash_save,
+	get_stashes, stash_apply, stash_branch, stash_drop, stash_pop,
+	stash_reword, stash_save, stash_save_paths, StashPath,
 };
 pub use state::{repo_state, RepoState};
 pub use tags::{
@@ -80,8 +123,9 @@ pub use tags::{
 };
 pub use tree::{tree_file_content, tree_files, TreeFile};
 pub use utils::{
-	get_head, get_head_tuple, is_bare_repo, is_repo, repo_dir,
-	stage_add_all, stage_add_file, stage_addremoved, Head,
+	filter_by_pathspec, get_head, get_head_tuple, is_bare_repo,
+	is_repo, repo_dir, stage_add_all, stage_add_file,
+	stage_add_force, stage_addremoved, Head,
 };
 
 #[cfg(test)]
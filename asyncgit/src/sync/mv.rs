@@ -0,0 +1,229 @@
+//! move tracked files (like `git mv`) and untrack them (like `git rm --cached`)
+
+use super::utils::{repo, work_dir};
+use crate::error::{Error, Result};
+use git2::{Index, IndexEntry};
+use scopetime::scope_time;
+use std::{
+	convert::TryFrom,
+	fs,
+	path::{Component, Path},
+};
+
+// lower 12 bits of the index entry flags hold the path length
+const INDEX_ENTRY_NAMEMASK: u16 = 0x0fff;
+
+/// moves the tracked file or folder `from` to `to` in the workdir and
+/// in the index, so that the move shows up as a rename when staged
+pub fn move_path(
+	repo_path: &str,
+	from: &str,
+	to: &str,
+) -> Result<()> {
+	scope_time!("move_path");
+
+	let from = validate_path(from)?;
+	let to = validate_path(to)?;
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let mut index = repo.index()?;
+
+	let entries = tracked_entries(&index, from)?;
+
+	let to_full = work_dir.join(to);
+	if to_full.symlink_metadata().is_ok() {
+		return Err(Error::Generic(format!(
+			"destination already exists: '{}'",
+			to
+		)));
+	}
+
+	if let Some(parent) = to_full.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	fs::rename(work_dir.join(from), &to_full)?;
+
+	for mut entry in entries {
+		let old_path =
+			String::from_utf8_lossy(&entry.path).to_string();
+		let new_path = format!("{}{}", to, &old_path[from.len()..]);
+
+		index.remove_path(Path::new(&old_path))?;
+
+		let len = new_path.len().min(INDEX_ENTRY_NAMEMASK.into());
+		entry.flags = (entry.flags & !INDEX_ENTRY_NAMEMASK)
+			| u16::try_from(len)?;
+		entry.path = new_path.into_bytes();
+		index.add(&entry)?;
+	}
+
+	index.write()?;
+
+	Ok(())
+}
+
+/// removes the tracked file or folder `path` from the index but keeps
+/// it in the workdir
+pub fn remove_from_index(repo_path: &str, path: &str) -> Result<()> {
+	scope_time!("remove_from_index");
+
+	let path = validate_path(path)?;
+
+	let repo = repo(repo_path)?;
+	let mut index = repo.index()?;
+
+	for entry in tracked_entries(&index, path)? {
+		index.remove_path(Path::new(
+			String::from_utf8_lossy(&entry.path).as_ref(),
+		))?;
+	}
+
+	index.write()?;
+
+	Ok(())
+}
+
+fn validate_path(path: &str) -> Result<&str> {
+	let path = path.trim_end_matches('/');
+
+	if path.is_empty()
+		|| !Path::new(path)
+			.components()
+			.all(|c| matches!(c, Component::Normal(_)))
+	{
+		return Err(Error::Generic(format!(
+			"invalid path: '{}'",
+			path
+		)));
+	}
+
+	Ok(path)
+}
+
+/// index entries of the file `path` or of all files in the folder `path`
+fn tracked_entries(
+	index: &Index,
+	path: &str,
+) -> Result<Vec<IndexEntry>> {
+	let folder = format!("{}/", path);
+
+	let entries = index
+		.iter()
+		.filter(|entry| {
+			entry.path == path.as_bytes()
+				|| entry.path.starts_with(folder.as_bytes())
+		})
+		.collect::<Vec<_>>();
+
+	if entries.is_empty() {
+		return Err(Error::Generic(format!(
+			"path is not tracked: '{}'",
+			path
+		)));
+	}
+
+	Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		status::{get_status, StatusItemType, StatusType},
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+
+	fn stage_status(
+		repo_path: &str,
+	) -> Vec<(String, StatusItemType)> {
+		get_status(repo_path, StatusType::Stage, None)
+			.unwrap()
+			.into_iter()
+			.map(|i| (i.path, i.status))
+			.collect()
+	}
+
+	#[test]
+	fn test_move_file() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		move_path(repo_path, "a.txt", "sub/b.txt").unwrap();
+
+		assert!(!root.join("a.txt").exists());
+		assert!(root.join("sub/b.txt").exists());
+		assert_eq!(
+			stage_status(repo_path),
+			vec![(
+				String::from("sub/b.txt"),
+				StatusItemType::Renamed
+			)]
+		);
+		assert!(get_status(repo_path, StatusType::WorkingDir, None)
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn test_move_folder() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::create_dir(root.join("src")).unwrap();
+		write_commit_file(&repo, "src/a.rs", "a", "c1");
+		write_commit_file(&repo, "src/b.rs", "b", "c2");
+		write_commit_file(&repo, "src_other.rs", "c", "c3");
+
+		assert!(move_path(repo_path, "src", "src_other.rs").is_err());
+		assert!(move_path(repo_path, "missing", "x").is_err());
+		assert!(move_path(repo_path, "src", "../x").is_err());
+
+		move_path(repo_path, "src/", "lib").unwrap();
+
+		assert_eq!(
+			stage_status(repo_path),
+			vec![
+				(String::from("lib/a.rs"), StatusItemType::Renamed),
+				(String::from("lib/b.rs"), StatusItemType::Renamed),
+			]
+		);
+		assert!(root.join("src_other.rs").exists());
+	}
+
+	#[test]
+	fn test_remove_from_index() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+
+		assert!(remove_from_index(repo_path, "new.txt").is_err());
+
+		remove_from_index(repo_path, "a.txt").unwrap();
+
+		assert!(root.join("a.txt").exists());
+		assert_eq!(
+			stage_status(repo_path),
+			vec![(String::from("a.txt"), StatusItemType::Deleted)]
+		);
+		assert_eq!(
+			get_status(repo_path, StatusType::WorkingDir, None)
+				.unwrap()
+				.into_iter()
+				.map(|i| (i.path, i.status))
+				.collect::<Vec<_>>(),
+			vec![
+				(String::from("a.txt"), StatusItemType::New),
+				(String::from("new.txt"), StatusItemType::New),
+			]
+		);
+	}
+}
@@ -1,6 +1,28 @@
-use super::diff::{get_diff_raw, DiffOptions, HunkHeader};
-use crate::error::{Error, Result};
-use git2::{Diff, DiffLine, Patch, Repository};
+use super::{
+	commit_files::{get_commit_diff, get_compare_commits_diff},
+	diff::{get_diff_raw, DiffOptions, HunkHeader},
+	utils::{repo, work_dir},
+	CommitId,
+};
+use crate::{
+	error::{Error, Result},
+	hash,
+};
+use git2::{
+	Commit, Delta, Diff, DiffFormat, DiffLine, DiffStatsFormat, Oid,
+	Patch, Repository, Sort,
+};
+use scopetime::scope_time;
+use std::{
+	collections::HashSet,
+	convert::TryFrom,
+	fmt::Write,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// max length of the subject part of a patch file name (like git)
+const PATCH_FILENAME_MAX_SUBJECT: usize = 52;
 
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) struct HunkLines<'a> {
@@ -40,7 +62,8 @@ pub(crate) fn get_file_diff_patch_and_hunklines<'a>(
 }
 
 //
-fn patch_get_hunklines<'a>(
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn patch_get_hunklines<'a>(
 	patch: &Patch<'a>,
 ) -> Result<Vec<HunkLines<'a>>> {
 	let count_hunks = patch.num_hunks();
@@ -80,3 +103,456 @@ fn get_patches<'a>(diff: &Diff<'a>) -> Result<Vec<Patch<'a>>> {
 
 	Ok(res)
 }
+
+/// unified patch (as in `git diff`) of `file` in stage or workdir,
+/// if `hunk` is set only this hunk (by header hash) is part of it
+pub fn get_diff_patch(
+	repo_path: &str,
+	file: &str,
+	stage: bool,
+	hunk: Option<u64>,
+	options: Option<DiffOptions>,
+) -> Result<String> {
+	scope_time!("get_diff_patch");
+
+	let repo = repo(repo_path)?;
+	let diff = get_diff_raw(&repo, file, stage, false, options)?;
+
+	let is_untracked = diff
+		.deltas()
+		.next()
+		.map_or(false, |delta| delta.status() == Delta::Untracked);
+
+	// untracked files have no content in a regular workdir diff
+	let diff = if is_untracked {
+		let mut opt = git2::DiffOptions::new();
+		opt.pathspec(file);
+		opt.include_untracked(true);
+		opt.recurse_untracked_dirs(true);
+		opt.show_untracked_content(true);
+
+		repo.diff_index_to_workdir(None, Some(&mut opt))?
+	} else {
+		diff
+	};
+
+	diff_to_patch(&diff, hunk)
+}
+
+/// unified patch of `file` in commit `id`
+/// (optionally limited to a single `hunk`)
+pub fn get_diff_commit_patch(
+	repo_path: &str,
+	id: CommitId,
+	file: String,
+	hunk: Option<u64>,
+	options: Option<DiffOptions>,
+) -> Result<String> {
+	scope_time!("get_diff_commit_patch");
+
+	let repo = repo(repo_path)?;
+	let diff = get_commit_diff(&repo, id, &[file], options)?;
+
+	diff_to_patch(&diff, hunk)
+}
+
+/// unified patch of `file` between two commits
+/// (optionally limited to a single `hunk`)
+pub fn get_diff_commits_patch(
+	repo_path: &str,
+	ids: (CommitId, CommitId),
+	file: String,
+	hunk: Option<u64>,
+	options: Option<DiffOptions>,
+) -> Result<String> {
+	scope_time!("get_diff_commits_patch");
+
+	let repo = repo(repo_path)?;
+	let diff =
+		get_compare_commits_diff(&repo, ids, &[file], options)?;
+
+	diff_to_patch(&diff, hunk)
+}
+
+/// write each commit as a mbox style patch file into `dir`
+/// (like `git format-patch`), parent commits come first.
+///
+/// relative `dir` is interpreted relative to the repo workdir.
+/// returns the paths of the written files
+pub fn format_patches(
+	repo_path: &str,
+	ids: &[CommitId],
+	dir: &Path,
+) -> Result<Vec<PathBuf>> {
+	scope_time!("format_patches");
+
+	let repo = repo(repo_path)?;
+
+	let dir = if dir.is_relative() {
+		work_dir(&repo)?.join(dir)
+	} else {
+		dir.to_path_buf()
+	};
+
+	fs::create_dir_all(&dir)?;
+
+	let commits = sort_parents_first(&repo, ids)?;
+
+	let total = commits.len();
+
+	commits
+		.iter()
+		.enumerate()
+		.map(|(idx, commit)| {
+			let content =
+				format_patch(&repo, commit, idx + 1, total)?;
+
+			let file = dir.join(patch_file_name(
+				idx + 1,
+				commit.summary().unwrap_or_default(),
+			));
+
+			fs::write(&file, content)?;
+
+			Ok(file)
+		})
+		.collect()
+}
+
+/// orders `ids` parents first, like a reverse topological revwalk.
+/// the walk stops at the parents of the oldest selected commits
+/// instead of walking the whole history
+fn sort_parents_first<'a>(
+	repo: &'a Repository,
+	ids: &[CommitId],
+) -> Result<Vec<Commit<'a>>> {
+	let selected =
+		ids.iter().map(|id| Oid::from(*id)).collect::<HashSet<_>>();
+
+	let mut walk = repo.revwalk()?;
+	walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+	for id in &selected {
+		walk.push(*id)?;
+	}
+
+	for id in &selected {
+		let has_selected_ancestor = selected.iter().any(|other| {
+			other != id
+				&& repo
+					.graph_descendant_of(*id, *other)
+					.unwrap_or(false)
+		});
+
+		if !has_selected_ancestor {
+			for parent in repo.find_commit(*id)?.parent_ids() {
+				walk.hide(parent)?;
+			}
+		}
+	}
+
+	walk.filter(|id| {
+		id.as_ref().map_or(true, |id| selected.contains(id))
+	})
+	.map(|id| Ok(repo.find_commit(id?)?))
+	.collect()
+}
+
+fn format_patch(
+	repo: &Repository,
+	commit: &Commit,
+	patch_no: usize,
+	total: usize,
+) -> Result<String> {
+	let diff =
+		get_commit_diff(repo, CommitId::new(commit.id()), &[], None)?;
+
+	let author = commit.author();
+	let message = commit.message().unwrap_or_default();
+	let mut message_lines = message.lines();
+	let subject = message_lines.next().unwrap_or_default();
+	let body = message_lines.collect::<Vec<_>>().join("\n");
+	let body = body.trim();
+
+	let subject_prefix = if total > 1 {
+		format!("[PATCH {}/{}]", patch_no, total)
+	} else {
+		String::from("[PATCH]")
+	};
+
+	let stats = diff.stats()?.to_buf(DiffStatsFormat::FULL, 72)?;
+
+	let mut res = format!(
+		"From {} Mon Sep 17 00:00:00 2001\nFrom: {} <{}>\nDate: {}\nSubject: {} {}\n\n",
+		commit.id(),
+		encode_header(author.name().unwrap_or_default()),
+		author.email().unwrap_or_default(),
+		rfc2822_date(
+			author.when().seconds(),
+			author.when().offset_minutes()
+		),
+		subject_prefix,
+		encode_header(subject),
+	);
+
+	if !body.is_empty() {
+		res.push_str(body);
+		res.push('\n');
+	}
+
+	res.push_str("---\n");
+	res.push_str(stats.as_str().unwrap_or_default());
+	res.push('\n');
+	res.push_str(&diff_to_patch(&diff, None)?);
+	res.push_str("-- \ngitui\n\n");
+
+	Ok(res)
+}
+
+/// RFC 2047 encodes `value` as a single `q` encoded word
+/// (`=?UTF-8?q?J=C3=B6rg?=`) if it is not plain ascii
+fn encode_header(value: &str) -> String {
+	if value.is_ascii() {
+		return value.to_string();
+	}
+
+	let mut res = String::from("=?UTF-8?q?");
+	for b in value.bytes() {
+		match b {
+			b' ' => res.push('_'),
+			b'!' | b'*' | b'+' | b'-' | b'/' => {
+				res.push(char::from(b));
+			}
+			b if b.is_ascii_alphanumeric() => {
+				res.push(char::from(b));
+			}
+			b => {
+				write!(res, "={:02X}", b).ok();
+			}
+		}
+	}
+	res.push_str("?=");
+
+	res
+}
+
+/// file name like `git format-patch` generates: `0001-fix-the-thing.patch`
+fn patch_file_name(patch_no: usize, subject: &str) -> String {
+	let mut name = String::with_capacity(PATCH_FILENAME_MAX_SUBJECT);
+
+	for c in subject.chars() {
+		if name.len() >= PATCH_FILENAME_MAX_SUBJECT {
+			break;
+		}
+
+		if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+			name.push(c);
+		} else if !name.is_empty() && !name.ends_with('-') {
+			name.push('-');
+		}
+	}
+
+	let name = name.trim_end_matches(|c| c == '-' || c == '.');
+
+	format!("{:04}-{}.patch", patch_no, name)
+}
+
+/// formats time as RFC 2822 date (as used in mails)
+fn rfc2822_date(seconds: i64, offset_minutes: i32) -> String {
+	const DAYS: [&str; 7] =
+		["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+	const MONTHS: [&str; 12] = [
+		"Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug",
+		"Sep", "Oct", "Nov", "Dec",
+	];
+
+	let local = seconds + i64::from(offset_minutes) * 60;
+	let days = local.div_euclid(86400);
+	let secs_of_day = local.rem_euclid(86400);
+
+	// see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+	let z = days + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z.rem_euclid(146_097);
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + i64::from(month <= 2);
+
+	let sign = if offset_minutes < 0 { '-' } else { '+' };
+	let offset = offset_minutes.abs();
+
+	format!(
+		"{}, {} {} {} {:02}:{:02}:{:02} {}{:02}{:02}",
+		DAYS[usize::try_from(days.rem_euclid(7)).unwrap_or_default()],
+		day,
+		MONTHS[usize::try_from(month - 1).unwrap_or_default()],
+		year,
+		secs_of_day / 3600,
+		(secs_of_day % 3600) / 60,
+		secs_of_day % 60,
+		sign,
+		offset / 60,
+		offset % 60,
+	)
+}
+
+/// unified patch text of `diff` (optionally only a single hunk)
+fn diff_to_patch(diff: &Diff, hunk: Option<u64>) -> Result<String> {
+	let mut res = Vec::new();
+	let mut hunk_found = false;
+
+	diff.print(DiffFormat::Patch, |_delta, diff_hunk, line| {
+		let is_hunk_line = diff_hunk.is_some();
+		let include = match (hunk, diff_hunk) {
+			(Some(hunk), Some(diff_hunk)) => {
+				hash(&HunkHeader::from(diff_hunk)) == hunk
+			}
+			_ => true,
+		};
+
+		if include {
+			if is_hunk_line {
+				hunk_found = true;
+			}
+
+			let origin = line.origin();
+			if matches!(origin, '+' | '-' | ' ') {
+				res.extend_from_slice(origin.to_string().as_bytes());
+			}
+			res.extend_from_slice(line.content());
+		}
+
+		true
+	})?;
+
+	if hunk.is_some() && !hunk_found {
+		return Err(Error::Generic(String::from("hunk not found")));
+	}
+
+	Ok(String::from_utf8_lossy(&res).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{encode_header, format_patches, get_diff_patch};
+	use crate::sync::{
+		commit, stage_add_file, tests::repo_init_empty,
+	};
+	use std::{fs, path::Path};
+
+	#[test]
+	fn test_format_patches() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("foo.txt"), "a\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let id1 = commit(repo_path, "first: commit\n\nbody").unwrap();
+
+		fs::write(root.join("foo.txt"), "a\nb\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let id2 = commit(repo_path, "second").unwrap();
+
+		let files =
+			format_patches(repo_path, &[id2, id1], Path::new("out"))
+				.unwrap();
+
+		assert_eq!(files.len(), 2);
+		assert_eq!(
+			files[0].file_name().unwrap().to_str().unwrap(),
+			"0001-first-commit.patch"
+		);
+		assert_eq!(
+			files[1].file_name().unwrap().to_str().unwrap(),
+			"0002-second.patch"
+		);
+
+		let patch = fs::read_to_string(&files[1]).unwrap();
+		assert!(patch.contains("Subject: [PATCH 2/2] second\n"));
+		assert!(patch.contains("+b\n"));
+
+		let patch = fs::read_to_string(&files[0]).unwrap();
+		assert!(patch.contains("\n\nbody\n---\n"));
+
+		fs::write(root.join("foo.txt"), "a\nb\nc\n").unwrap();
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let id3 = commit(repo_path, "third").unwrap();
+
+		let files = format_patches(
+			repo_path,
+			&[id3, id1, id2],
+			Path::new("out2"),
+		)
+		.unwrap();
+
+		assert_eq!(
+			files
+				.iter()
+				.map(|f| f.file_name().unwrap().to_str().unwrap())
+				.collect::<Vec<_>>(),
+			vec![
+				"0001-first-commit.patch",
+				"0002-second.patch",
+				"0003-third.patch"
+			]
+		);
+
+		let files =
+			format_patches(repo_path, &[id3, id1], Path::new("out3"))
+				.unwrap();
+
+		assert_eq!(
+			files
+				.iter()
+				.map(|f| f.file_name().unwrap().to_str().unwrap())
+				.collect::<Vec<_>>(),
+			vec!["0001-first-commit.patch", "0002-third.patch"]
+		);
+	}
+
+	#[test]
+	fn test_encode_header() {
+		assert_eq!(encode_header("plain name"), "plain name");
+		assert_eq!(
+			encode_header("J\u{f6}rg M"),
+			"=?UTF-8?q?J=C3=B6rg_M?="
+		);
+	}
+
+	#[test]
+	fn test_diff_patch() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		fs::write(root.join("foo.txt"), "a\n").unwrap();
+
+		let patch =
+			get_diff_patch(repo_path, "foo.txt", false, None, None)
+				.unwrap();
+		assert!(patch.starts_with("diff --git a/foo.txt b/foo.txt"));
+		assert!(patch.ends_with("@@ -0,0 +1 @@\n+a\n"));
+
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		commit(repo_path, "first").unwrap();
+
+		fs::write(root.join("foo.txt"), "b\n").unwrap();
+
+		let patch =
+			get_diff_patch(repo_path, "foo.txt", false, None, None)
+				.unwrap();
+		assert!(patch.ends_with("-a\n+b\n"));
+
+		assert!(get_diff_patch(
+			repo_path,
+			"foo.txt",
+			false,
+			Some(0),
+			None
+		)
+		.is_err());
+	}
+}
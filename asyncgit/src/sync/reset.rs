@@ -44,9 +44,14 @@ mod tests {
 	use crate::error::Result;
 	use crate::sync::{
 		commit,
-		status::{get_status, StatusType},
+		diff::DiffOptions,
+		status::{
+			get_status, get_status_with_renames, StatusItemType,
+			StatusType,
+		},
 		tests::{
-			debug_cmd_print, get_statuses, repo_init, repo_init_empty,
+			debug_cmd_print, get_statuses, repo_init,
+			repo_init_empty, write_commit_file,
 		},
 		utils::{stage_add_all, stage_add_file},
 	};
@@ -311,4 +316,40 @@ mod tests {
 
 		assert_eq!(get_statuses(repo_path), (0, 0));
 	}
+
+	#[test]
+	fn test_reset_workdir_rename() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "1\n2\n3\n4\n", "c1");
+		fs::rename(root.join("a.txt"), root.join("b.txt")).unwrap();
+
+		let items = get_status_with_renames(
+			repo_path,
+			StatusType::WorkingDir,
+			None,
+			DiffOptions {
+				rename_threshold: Some(50),
+				..DiffOptions::default()
+			},
+		)
+		.unwrap();
+		assert_eq!(items.len(), 1);
+		assert_eq!(items[0].status, StatusItemType::Renamed);
+
+		// discarding a rename restores its source and removes
+		// the untracked target
+		reset_workdir(repo_path, items[0].old_path.as_ref().unwrap())
+			.unwrap();
+		reset_workdir(repo_path, &items[0].path).unwrap();
+
+		assert_eq!(
+			fs::read_to_string(root.join("a.txt")).unwrap(),
+			"1\n2\n3\n4\n"
+		);
+		assert!(!root.join("b.txt").exists());
+		assert_eq!(get_statuses(repo_path), (0, 0));
+	}
 }
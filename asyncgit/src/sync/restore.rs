@@ -0,0 +1,420 @@
+//! restore single files or hunks of a commit or stash
+//! (like `git restore --source=<commit> [--staged] [-p] <path>`)
+//! or save files of a commit to any path
+
+use super::{
+	apply::ApplyLocation,
+	commit_files::get_commit_diff,
+	diff::HunkHeader,
+	utils::{repo, work_dir},
+	CommitId,
+};
+use crate::{
+	error::{Error, Result},
+	hash,
+};
+use git2::{
+	ApplyOptions, Delta, DiffFile, FileMode, IndexEntry, IndexTime,
+	Oid, Repository,
+};
+use scopetime::scope_time;
+use std::{cell::Cell, fs, path::Path};
+
+/// writes `path` as it is in commit (or stash) `id` to `location`,
+/// the file gets removed if the commit deleted it
+pub fn restore_file(
+	repo_path: &str,
+	id: CommitId,
+	path: &str,
+	location: ApplyLocation,
+) -> Result<()> {
+	scope_time!("restore_file");
+
+	let repo = repo(repo_path)?;
+	let diff = get_commit_diff(&repo, id, &[path.to_string()], None)?;
+
+	let delta = diff
+		.deltas()
+		.find(|delta| {
+			delta.new_file().path() == Some(Path::new(path))
+		})
+		.ok_or_else(|| {
+			Error::Generic(format!(
+				"'{}' not changed in commit",
+				path
+			))
+		})?;
+
+	let file = if delta.status() == Delta::Deleted {
+		None
+	} else {
+		Some(delta.new_file())
+	};
+
+	if location != ApplyLocation::Index {
+		restore_workdir(&repo, path, file.as_ref())?;
+	}
+	if location != ApplyLocation::WorkDir {
+		restore_index(&repo, path, file.as_ref())?;
+	}
+
+	Ok(())
+}
+
+/// applies the hunk `hunk_hash` of `path` in commit (or stash) `id`
+/// to `location`
+pub fn restore_hunk(
+	repo_path: &str,
+	id: CommitId,
+	path: &str,
+	hunk_hash: u64,
+	location: ApplyLocation,
+) -> Result<()> {
+	scope_time!("restore_hunk");
+
+	let repo = repo(repo_path)?;
+	let diff = get_commit_diff(&repo, id, &[path.to_string()], None)?;
+
+	let found = Cell::new(0_usize);
+	let mut opt = ApplyOptions::new();
+	opt.hunk_callback(|hunk| {
+		let matches = hunk.map_or(false, |hunk| {
+			hash(&HunkHeader::from(hunk)) == hunk_hash
+		});
+		if matches {
+			found.set(found.get() + 1);
+		}
+		matches
+	});
+
+	repo.apply(&diff, location.into(), Some(&mut opt))?;
+
+	if found.get() == 0 {
+		return Err(Error::Generic("hunk not found".to_string()));
+	}
+
+	Ok(())
+}
+
+/// writes the content of `path` in commit `id` to `target` relative
+/// to the working directory, `target == path` restores the file in
+/// the working tree (like `git restore --source=<commit> <path>`)
+pub fn save_revision_file(
+	repo_path: &str,
+	id: CommitId,
+	path: &str,
+	target: &str,
+) -> Result<()> {
+	scope_time!("save_revision_file");
+
+	let repo = repo(repo_path)?;
+	let entry = repo
+		.find_commit(id.into())?
+		.tree()?
+		.get_path(Path::new(path))?;
+
+	write_workdir_file(
+		&repo,
+		target,
+		entry.id(),
+		entry.filemode() == i32::from(FileMode::BlobExecutable),
+	)
+}
+
+fn restore_workdir(
+	repo: &Repository,
+	path: &str,
+	file: Option<&DiffFile>,
+) -> Result<()> {
+	if let Some(file) = file {
+		write_workdir_file(
+			repo,
+			path,
+			file.id(),
+			file.mode() == FileMode::BlobExecutable,
+		)?;
+	} else {
+		let full_path = work_dir(repo)?.join(path);
+		if full_path.exists() {
+			fs::remove_file(full_path)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// writes blob `id` to `path` in the working tree, the executable
+/// bit of the file is set or cleared according to `executable`
+fn write_workdir_file(
+	repo: &Repository,
+	path: &str,
+	id: Oid,
+	executable: bool,
+) -> Result<()> {
+	let full_path = work_dir(repo)?.join(path);
+
+	if let Some(parent) = full_path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	fs::write(&full_path, repo.find_blob(id)?.content())?;
+
+	set_executable(&full_path, executable)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> Result<()> {
+	use std::os::unix::fs::PermissionsExt;
+
+	let mut permissions = fs::metadata(path)?.permissions();
+	let mode = permissions.mode();
+	permissions.set_mode(if executable {
+		mode | 0o111
+	} else {
+		mode & !0o111
+	});
+	fs::set_permissions(path, permissions)?;
+
+	Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> Result<()> {
+	Ok(())
+}
+
+fn restore_index(
+	repo: &Repository,
+	path: &str,
+	file: Option<&DiffFile>,
+) -> Result<()> {
+	let mut index = repo.index()?;
+
+	if let Some(file) = file {
+		index.add(&IndexEntry {
+			ctime: IndexTime::new(0, 0),
+			mtime: IndexTime::new(0, 0),
+			dev: 0,
+			ino: 0,
+			mode: file.mode().into(),
+			uid: 0,
+			gid: 0,
+			file_size: 0,
+			id: file.id(),
+			flags: 0,
+			flags_extended: 0,
+			path: path.as_bytes().to_vec(),
+		})?;
+	} else if index.get_path(Path::new(path), 0).is_some() {
+		index.remove_path(Path::new(path))?;
+	}
+
+	index.write()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		commit,
+		diff::get_diff_commit,
+		stage_add_file, stash_save,
+		status::{get_status, StatusItemType, StatusType},
+		tests::{repo_init, write_commit_file},
+		utils::{repo_read_file, repo_write_file},
+	};
+
+	fn statuses(
+		repo_path: &str,
+		status_type: StatusType,
+	) -> Vec<(String, StatusItemType)> {
+		get_status(repo_path, status_type, None)
+			.unwrap()
+			.into_iter()
+			.map(|i| (i.path, i.status))
+			.collect()
+	}
+
+	#[test]
+	fn test_restore_file() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		write_commit_file(&repo, "a.txt", "b", "c2");
+
+		restore_file(repo_path, c1, "a.txt", ApplyLocation::WorkDir)
+			.unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a");
+		assert_eq!(
+			statuses(repo_path, StatusType::WorkingDir),
+			vec![(String::from("a.txt"), StatusItemType::Modified)]
+		);
+		assert!(statuses(repo_path, StatusType::Stage).is_empty());
+
+		restore_file(repo_path, c1, "a.txt", ApplyLocation::Index)
+			.unwrap();
+
+		assert!(
+			statuses(repo_path, StatusType::WorkingDir).is_empty()
+		);
+		assert_eq!(
+			statuses(repo_path, StatusType::Stage),
+			vec![(String::from("a.txt"), StatusItemType::Modified)]
+		);
+
+		assert!(restore_file(
+			repo_path,
+			c1,
+			"other.txt",
+			ApplyLocation::WorkDir
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn test_restore_file_from_stash() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		repo_write_file(&repo, "a.txt", "a2").unwrap();
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+
+		let stash = stash_save(repo_path, None, true, false).unwrap();
+
+		restore_file(
+			repo_path,
+			stash,
+			"new.txt",
+			ApplyLocation::Both,
+		)
+		.unwrap();
+
+		assert_eq!(repo_read_file(&repo, "new.txt").unwrap(), "new");
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a");
+		assert_eq!(
+			statuses(repo_path, StatusType::Stage),
+			vec![(String::from("new.txt"), StatusItemType::New)]
+		);
+	}
+
+	#[test]
+	fn test_restore_hunk() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let lines =
+			(0..20).map(|i| i.to_string() + "\n").collect::<String>();
+		let changed = lines
+			.replace("\n1\n", "\nx\n")
+			.replace("\n18\n", "\ny\n");
+
+		write_commit_file(&repo, "a.txt", &lines, "c1");
+		let c2 = write_commit_file(&repo, "a.txt", &changed, "c2");
+		write_commit_file(&repo, "a.txt", &lines, "c3");
+
+		let diff =
+			get_diff_commit(repo_path, c2, "a.txt", None, None)
+				.unwrap();
+		assert_eq!(diff.hunks.len(), 2);
+
+		restore_hunk(
+			repo_path,
+			c2,
+			"a.txt",
+			diff.hunks[1].header_hash,
+			ApplyLocation::WorkDir,
+		)
+		.unwrap();
+
+		assert_eq!(
+			repo_read_file(&repo, "a.txt").unwrap(),
+			lines.replace("\n18\n", "\ny\n")
+		);
+		assert!(statuses(repo_path, StatusType::Stage).is_empty());
+
+		assert!(restore_hunk(
+			repo_path,
+			c2,
+			"a.txt",
+			diff.hunks[1].header_hash.wrapping_add(1),
+			ApplyLocation::WorkDir,
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn test_save_revision_file() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		write_commit_file(&repo, "a.txt", "b", "c2");
+
+		save_revision_file(repo_path, c1, "a.txt", "old/a.txt")
+			.unwrap();
+
+		assert_eq!(repo_read_file(&repo, "old/a.txt").unwrap(), "a");
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "b");
+
+		save_revision_file(repo_path, c1, "a.txt", "a.txt").unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a");
+		assert!(statuses(repo_path, StatusType::Stage).is_empty());
+
+		assert!(save_revision_file(
+			repo_path,
+			c1,
+			"other.txt",
+			"other.txt"
+		)
+		.is_err());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_save_revision_file_mode() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let is_executable = |path: &str| {
+			fs::metadata(root.join(path))
+				.unwrap()
+				.permissions()
+				.mode() & 0o111
+				!= 0
+		};
+		let set_executable = |path: &str| {
+			fs::set_permissions(
+				root.join(path),
+				fs::Permissions::from_mode(0o755),
+			)
+			.unwrap();
+		};
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+
+		repo_write_file(&repo, "run.sh", "run").unwrap();
+		set_executable("run.sh");
+		stage_add_file(repo_path, Path::new("run.sh")).unwrap();
+		let c2 = commit(repo_path, "c2").unwrap();
+
+		set_executable("a.txt");
+		save_revision_file(repo_path, c1, "a.txt", "a.txt").unwrap();
+		assert!(!is_executable("a.txt"));
+
+		save_revision_file(repo_path, c2, "run.sh", "copy.sh")
+			.unwrap();
+		assert!(is_executable("copy.sh"));
+	}
+}
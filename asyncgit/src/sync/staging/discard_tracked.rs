@@ -1,4 +1,7 @@
-use super::{apply_selection, load_file};
+use super::{
+	apply_selection, discard_untracked::discard_untracked_lines,
+	load_file,
+};
 use crate::error::Result;
 use crate::sync::{
 	diff::DiffLinePosition,
@@ -6,6 +9,7 @@ use crate::sync::{
 	utils::{repo, repo_write_file},
 };
 use scopetime::scope_time;
+use std::path::Path;
 
 /// discards specific lines in an unstaged hunk of a diff
 pub fn discard_lines(
@@ -20,9 +24,12 @@ pub fn discard_lines(
 	}
 
 	let repo = repo(repo_path)?;
-	repo.index()?.read(true)?;
+	let mut index = repo.index()?;
+	index.read(true)?;
 
-	//TODO: check that file is not new (status modified)
+	if index.get_path(Path::new(file_path), 0).is_none() {
+		return discard_untracked_lines(&repo, file_path, lines);
+	}
 
 	let new_content = {
 		let (_patch, hunks) = get_file_diff_patch_and_hunklines(
@@ -0,0 +1,72 @@
+use super::{apply_selection, load_file, untracked_file_patch};
+use crate::{
+	error::Result,
+	sync::{
+		diff::DiffLinePosition, patches::patch_get_hunklines,
+		utils::repo_write_file,
+	},
+};
+use git2::Repository;
+
+/// removes `lines` from the untracked file `file_path`
+pub(super) fn discard_untracked_lines(
+	repo: &Repository,
+	file_path: &str,
+	lines: &[DiffLinePosition],
+) -> Result<()> {
+	let working_content = load_file(repo, file_path)?;
+
+	let new_content = {
+		let patch =
+			untracked_file_patch(&working_content, file_path)?;
+		let hunks = patch_get_hunklines(&patch)?;
+		let old_lines = working_content.lines().collect::<Vec<_>>();
+
+		apply_selection(lines, &hunks, &old_lines, false, true)?
+	};
+
+	repo_write_file(repo, file_path, new_content.as_str())?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sync::{
+		diff::DiffLinePosition, discard_lines, tests::repo_init,
+		utils::repo_write_file,
+	};
+	use std::fs;
+
+	#[test]
+	fn test_discard_untracked_lines() {
+		static FILE: &str = "0\n1\n2\n3\n";
+
+		let (path, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		repo_write_file(&repo, "new.txt", FILE).unwrap();
+
+		discard_lines(
+			path,
+			"new.txt",
+			&[
+				DiffLinePosition {
+					old_lineno: None,
+					new_lineno: Some(1),
+				},
+				DiffLinePosition {
+					old_lineno: None,
+					new_lineno: Some(3),
+				},
+			],
+		)
+		.unwrap();
+
+		assert_eq!(
+			fs::read_to_string(root.join("new.txt")).unwrap(),
+			"1\n3\n"
+		);
+	}
+}
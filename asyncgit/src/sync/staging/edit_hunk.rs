@@ -0,0 +1,365 @@
+use super::apply_selection;
+use crate::{
+	error::{Error, Result},
+	hash,
+	sync::{
+		diff::{
+			get_diff_raw, DiffLinePosition, DiffOptions, HunkHeader,
+		},
+		patches::patch_get_hunklines,
+		utils::repo,
+	},
+};
+use easy_cast::Conv;
+use git2::{Diff, DiffLineType, Patch, Repository};
+use scopetime::scope_time;
+use std::path::Path;
+
+const COMMENT: char = '#';
+const NO_NEWLINE_MARKER: &str = "\\ No newline at end of file";
+
+const EDIT_HINT: &str = "# ---
+# To remove '-' lines, make them ' ' lines (context).
+# To remove '+' lines, delete them.
+# Lines starting with # will be removed.
+# If the patch applies cleanly, the edited hunk will be staged.
+# To abort, delete all lines.
+";
+
+/// text of the unstaged hunk `hunk_hash` of `file_path` to be edited
+/// before staging it (like `e` in `git add -p`)
+pub fn hunk_edit_text(
+	repo_path: &str,
+	file_path: &str,
+	hunk_hash: u64,
+	options: Option<DiffOptions>,
+) -> Result<String> {
+	scope_time!("hunk_edit_text");
+
+	let repo = repo(repo_path)?;
+
+	let (header, lines) =
+		find_hunk(&repo, file_path, hunk_hash, options)?;
+
+	let mut res = format!(
+		"@@ -{},{} +{},{} @@\n",
+		header.old_start,
+		header.old_lines,
+		header.new_start,
+		header.new_lines
+	);
+	for line in lines {
+		res.push_str(&line);
+	}
+
+	res.push_str(EDIT_HINT);
+
+	Ok(res)
+}
+
+/// stages an edited version (see `hunk_edit_text`) of the unstaged
+/// hunk `hunk_hash` of `file_path`.
+///
+/// fails if the edited hunk does not match the index anymore
+pub fn stage_edited_hunk(
+	repo_path: &str,
+	file_path: &str,
+	hunk_hash: u64,
+	options: Option<DiffOptions>,
+	edited: &str,
+) -> Result<()> {
+	scope_time!("stage_edited_hunk");
+
+	let repo = repo(repo_path)?;
+
+	let (header, _) =
+		find_hunk(&repo, file_path, hunk_hash, options)?;
+
+	let (lines, no_newline_at_end) = parse_edited_hunk(edited)?;
+
+	let mut index = repo.index()?;
+	index.read(true)?;
+	let mut idx =
+		index.get_path(Path::new(file_path), 0).ok_or_else(|| {
+			Error::Generic(String::from(
+				"only non new files supported",
+			))
+		})?;
+	let blob = repo.find_blob(idx.id)?;
+	let indexed_content = String::from_utf8(blob.content().into())?;
+	let old_lines = indexed_content.lines().collect::<Vec<_>>();
+
+	// pure additions start after `old_start`
+	let old_start = if header.old_lines == 0 {
+		header.old_start + 1
+	} else {
+		header.old_start
+	};
+
+	validate_context(&lines, &old_lines, usize::conv(old_start))?;
+
+	let old_count =
+		lines.iter().filter(|l| !l.starts_with('+')).count();
+	let new_count =
+		lines.iter().filter(|l| !l.starts_with('-')).count();
+
+	let patch = format!(
+		"diff --git a/{f} b/{f}\n--- a/{f}\n+++ b/{f}\n@@ -{} +{} @@\n{}\n",
+		range(old_start, old_count),
+		range(old_start, new_count),
+		lines.join("\n"),
+		f = file_path,
+	);
+
+	let diff = Diff::from_buffer(patch.as_bytes())?;
+	let patch = Patch::from_diff(&diff, 0)?.ok_or_else(|| {
+		Error::Generic(String::from("edited hunk is invalid"))
+	})?;
+	let hunks = patch_get_hunklines(&patch)?;
+
+	let selection = hunks
+		.iter()
+		.flat_map(|h| h.lines.iter().map(DiffLinePosition::from))
+		.collect::<Vec<_>>();
+
+	let mut new_content = apply_selection(
+		&selection, &hunks, &old_lines, false, false,
+	)?;
+	if no_newline_at_end {
+		new_content.pop();
+	}
+
+	let blob_id = repo.blob(new_content.as_bytes())?;
+
+	idx.id = blob_id;
+	idx.file_size = u32::try_conv(new_content.len())?;
+	index.add(&idx)?;
+
+	index.write()?;
+	index.read(true)?;
+
+	Ok(())
+}
+
+fn range(start: u32, count: usize) -> String {
+	format!("{},{}", start, count)
+}
+
+fn find_hunk(
+	repo: &Repository,
+	file_path: &str,
+	hunk_hash: u64,
+	options: Option<DiffOptions>,
+) -> Result<(HunkHeader, Vec<String>)> {
+	let diff = get_diff_raw(repo, file_path, false, false, options)?;
+
+	if let Some(patch) = Patch::from_diff(&diff, 0)? {
+		for hunk in patch_get_hunklines(&patch)? {
+			if hash(&hunk.hunk) == hunk_hash {
+				let lines = hunk
+					.lines
+					.iter()
+					.map(|line| match line.origin_value() {
+						DiffLineType::ContextEOFNL
+						| DiffLineType::AddEOFNL
+						| DiffLineType::DeleteEOFNL => {
+							format!("{}\n", NO_NEWLINE_MARKER)
+						}
+						_ => {
+							let content = String::from_utf8_lossy(
+								line.content(),
+							);
+							format!(
+								"{}{}\n",
+								line.origin(),
+								content
+									.strip_suffix('\n')
+									.unwrap_or(&content)
+							)
+						}
+					})
+					.collect();
+
+				return Ok((hunk.hunk, lines));
+			}
+		}
+	}
+
+	Err(Error::Generic(String::from("hunk not found")))
+}
+
+/// strips comments, the header and `\ No newline at end of file`
+/// markers, validates line prefixes.
+///
+/// also returns whether the new side of the hunk ends without newline
+fn parse_edited_hunk(edited: &str) -> Result<(Vec<&str>, bool)> {
+	let lines = edited
+		.lines()
+		.filter(|line| !line.starts_with(COMMENT))
+		.skip_while(|line| line.starts_with("@@"))
+		.collect::<Vec<_>>();
+
+	if !lines
+		.iter()
+		.any(|l| l.starts_with('+') || l.starts_with('-'))
+	{
+		return Err(Error::Generic(String::from(
+			"edited hunk contains no changes",
+		)));
+	}
+
+	let mut res = Vec::with_capacity(lines.len());
+	let mut no_newline_at_end = false;
+
+	for line in lines {
+		if line.starts_with('\\') {
+			// the marker refers to the line before it
+			no_newline_at_end = res
+				.last()
+				.map_or(false, |l: &&str| !l.starts_with('-'));
+		} else if line.is_empty() {
+			// an empty line is an empty context line
+			res.push(" ");
+		} else if line
+			.starts_with(|c| c == ' ' || c == '+' || c == '-')
+		{
+			res.push(line);
+		} else {
+			return Err(Error::Generic(format!(
+				"invalid line in edited hunk: '{}'",
+				line
+			)));
+		}
+	}
+
+	Ok((res, no_newline_at_end))
+}
+
+/// context and removed lines have to match the index
+fn validate_context(
+	lines: &[&str],
+	old_lines: &[&str],
+	old_start: usize,
+) -> Result<()> {
+	let lines = lines.iter().filter(|l| !l.starts_with('+'));
+
+	for (old_idx, line) in (old_start.saturating_sub(1)..).zip(lines)
+	{
+		if old_lines.get(old_idx) != Some(&&line[1..]) {
+			return Err(Error::Generic(format!(
+				"edited hunk does not apply: line {} differs",
+				old_idx + 1
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		sync::{
+			diff::get_diff,
+			tests::{repo_init, write_commit_file},
+			utils::repo_write_file,
+		},
+		DiffLineType,
+	};
+
+	static FILE_1: &str = "a\nb\nc\nd\n";
+	static FILE_2: &str = "a\nB\nc\nx\nd\n";
+
+	#[test]
+	fn test_stage_edited_hunk() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		write_commit_file(&repo, "test.txt", FILE_1, "c1");
+		repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+		let diff = get_diff(path, "test.txt", false, None).unwrap();
+		let hunk = diff.hunks[0].header_hash;
+
+		let text =
+			hunk_edit_text(path, "test.txt", hunk, None).unwrap();
+		assert!(text.starts_with("@@ -1,4 +1,5 @@\n a\n-b\n+B\n"));
+
+		// only stage the added `x` line, keep `b`
+		let edited = text
+			.replace("-b\n+B\n", " b\n")
+			.replace("+x\n", "+x\n+y\n");
+
+		stage_edited_hunk(path, "test.txt", hunk, None, &edited)
+			.unwrap();
+
+		let diff = get_diff(path, "test.txt", true, None).unwrap();
+		let added = diff.hunks[0]
+			.lines
+			.iter()
+			.filter(|l| l.line_type == DiffLineType::Add)
+			.map(|l| l.content.trim().to_string())
+			.collect::<Vec<_>>();
+
+		assert_eq!(added, vec!["x", "y"]);
+		assert_eq!(diff.lines, 7);
+	}
+
+	#[test]
+	fn test_stage_edited_hunk_no_newline_at_end() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		write_commit_file(&repo, "test.txt", "a\nb\nc", "c1");
+		repo_write_file(&repo, "test.txt", "a\nB\nc\nd").unwrap();
+
+		let diff = get_diff(path, "test.txt", false, None).unwrap();
+		let hunk = diff.hunks[0].header_hash;
+
+		let text =
+			hunk_edit_text(path, "test.txt", hunk, None).unwrap();
+		assert!(text.starts_with(
+			"@@ -1,3 +1,4 @@\n a\n-b\n-c\n\\ No newline at end of file\n+B\n+c\n+d\n\\ No newline at end of file\n"
+		));
+
+		// keep `b`
+		let edited = text.replace("-b\n", " b\n").replace("+B\n", "");
+
+		stage_edited_hunk(path, "test.txt", hunk, None, &edited)
+			.unwrap();
+
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let entry = index.get_path(Path::new("test.txt"), 0).unwrap();
+		let blob = repo.find_blob(entry.id).unwrap();
+
+		assert_eq!(blob.content(), b"a\nb\nc\nd");
+	}
+
+	#[test]
+	fn test_stage_edited_hunk_invalid() {
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		write_commit_file(&repo, "test.txt", FILE_1, "c1");
+		repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+		let diff = get_diff(path, "test.txt", false, None).unwrap();
+		let hunk = diff.hunks[0].header_hash;
+
+		let text =
+			hunk_edit_text(path, "test.txt", hunk, None).unwrap();
+
+		// context does not match the index
+		let edited = text.replace(" c\n", " z\n");
+		assert!(stage_edited_hunk(
+			path, "test.txt", hunk, None, &edited
+		)
+		.is_err());
+
+		// no changes left
+		assert!(stage_edited_hunk(path, "test.txt", hunk, None, "")
+			.is_err());
+	}
+}
@@ -1,16 +1,21 @@
 mod discard_tracked;
+mod discard_untracked;
+mod edit_hunk;
 mod stage_tracked;
+mod stage_untracked;
 
 pub use discard_tracked::discard_lines;
+pub use edit_hunk::{hunk_edit_text, stage_edited_hunk};
 pub use stage_tracked::stage_lines;
 
 use super::{
 	diff::DiffLinePosition, patches::HunkLines, utils::work_dir,
 };
 use crate::error::Result;
-use git2::{DiffLine, DiffLineType, Repository};
+use git2::{DiffLine, DiffLineType, Patch, Repository};
 use std::{
 	collections::HashSet, convert::TryFrom, fs::File, io::Read,
+	path::Path,
 };
 
 const NEWLINE: char = '\n';
@@ -184,3 +189,17 @@ pub fn load_file(
 
 	Ok(res)
 }
+
+/// patch of an untracked file with `content` against an empty file
+fn untracked_file_patch<'a>(
+	content: &'a str,
+	file_path: &str,
+) -> Result<Patch<'a>> {
+	Ok(Patch::from_buffers(
+		&[],
+		None,
+		content.as_bytes(),
+		Some(Path::new(file_path)),
+		None,
+	)?)
+}
@@ -1,4 +1,6 @@
-use super::apply_selection;
+use super::{
+	apply_selection, stage_untracked::stage_untracked_lines,
+};
 use crate::{
 	error::{Error, Result},
 	sync::{
@@ -28,12 +30,17 @@ pub fn stage_lines(
 
 	let mut index = repo.index()?;
 	index.read(true)?;
-	let mut idx =
-		index.get_path(Path::new(file_path), 0).ok_or_else(|| {
-			Error::Generic(String::from(
+	let mut idx = match index.get_path(Path::new(file_path), 0) {
+		Some(idx) => idx,
+		None if !is_stage => {
+			return stage_untracked_lines(&repo, file_path, lines);
+		}
+		None => {
+			return Err(Error::Generic(String::from(
 				"only non new files supported",
-			))
-		})?;
+			)))
+		}
+	};
 	let blob = repo.find_blob(idx.id)?;
 	let indexed_content = String::from_utf8(blob.content().into())?;
 
@@ -0,0 +1,138 @@
+use super::{apply_selection, load_file, untracked_file_patch};
+use crate::{
+	error::Result,
+	sync::{
+		diff::DiffLinePosition, patches::patch_get_hunklines,
+		utils::work_dir,
+	},
+};
+use easy_cast::Conv;
+use git2::{IndexEntry, IndexTime, Oid, Repository};
+use std::path::Path;
+
+const FILEMODE_BLOB: u32 = 0o100_644;
+const FILEMODE_BLOB_EXECUTABLE: u32 = 0o100_755;
+
+/// stages only `lines` of the untracked file `file_path` by adding
+/// an index entry containing just those lines (intent-to-add)
+pub(super) fn stage_untracked_lines(
+	repo: &Repository,
+	file_path: &str,
+	lines: &[DiffLinePosition],
+) -> Result<()> {
+	let working_content = load_file(repo, file_path)?;
+
+	let new_content = {
+		let patch =
+			untracked_file_patch(&working_content, file_path)?;
+		let hunks = patch_get_hunklines(&patch)?;
+
+		apply_selection(lines, &hunks, &[], false, false)?
+	};
+
+	let mode = if is_executable(&work_dir(repo)?.join(file_path)) {
+		FILEMODE_BLOB_EXECUTABLE
+	} else {
+		FILEMODE_BLOB
+	};
+
+	let entry = IndexEntry {
+		ctime: IndexTime::new(0, 0),
+		mtime: IndexTime::new(0, 0),
+		dev: 0,
+		ino: 0,
+		mode,
+		uid: 0,
+		gid: 0,
+		file_size: u32::try_conv(new_content.len())?,
+		id: Oid::zero(),
+		flags: 0,
+		flags_extended: 0,
+		path: file_path.as_bytes().to_vec(),
+	};
+
+	let mut index = repo.index()?;
+	index.read(true)?;
+	index.add_frombuffer(&entry, new_content.as_bytes())?;
+	index.write()?;
+
+	Ok(())
+}
+
+#[cfg(not(windows))]
+fn is_executable(path: &Path) -> bool {
+	use std::os::unix::fs::PermissionsExt;
+
+	path.metadata()
+		.map(|meta| meta.permissions().mode() & 0o111 != 0)
+		.unwrap_or_default()
+}
+
+#[cfg(windows)]
+const fn is_executable(_: &Path) -> bool {
+	false
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		sync::{
+			diff::{get_diff, DiffLinePosition},
+			stage_lines,
+			tests::{get_statuses, repo_init},
+			utils::repo_write_file,
+		},
+		DiffLineType,
+	};
+
+	#[test]
+	fn test_stage_untracked_lines() {
+		static FILE: &str = "0\n1\n2\n3\n";
+
+		let (path, repo) = repo_init().unwrap();
+		let path = path.path().to_str().unwrap();
+
+		repo_write_file(&repo, "new.txt", FILE).unwrap();
+		assert_eq!(get_statuses(path), (1, 0));
+
+		stage_lines(
+			path,
+			"new.txt",
+			false,
+			&[
+				DiffLinePosition {
+					old_lineno: None,
+					new_lineno: Some(2),
+				},
+				DiffLinePosition {
+					old_lineno: None,
+					new_lineno: Some(4),
+				},
+			],
+		)
+		.unwrap();
+
+		assert_eq!(get_statuses(path), (1, 1));
+
+		let diff = get_diff(path, "new.txt", true, None).unwrap();
+		let staged = diff.hunks[0]
+			.lines
+			.iter()
+			.skip(1)
+			.map(|l| l.content.to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(staged, vec!["1", "3"]);
+
+		// remaining lines show up as unstaged modification
+		let diff = get_diff(path, "new.txt", false, None).unwrap();
+		assert!(!diff.untracked);
+		assert_eq!(
+			diff.hunks[0]
+				.lines
+				.iter()
+				.filter(|l| l.line_type == DiffLineType::Add)
+				.count(),
+			2
+		);
+	}
+}
@@ -1,10 +1,31 @@
-use super::{utils::repo, CommitId};
-use crate::error::{Error, Result};
+use super::{
+	diff::HunkHeader,
+	utils::{repo, work_dir},
+	CommitId,
+};
+use crate::{
+	error::{Error, Result},
+	hash,
+};
 use git2::{
-	build::CheckoutBuilder, Oid, Repository, StashApplyOptions,
-	StashFlags,
+	build::CheckoutBuilder, ApplyOptions, Commit, Diff, FileMode,
+	Index, IndexEntry, IndexTime, Oid, Repository, StashApplyOptions,
+	StashFlags, Tree,
 };
 use scopetime::scope_time;
+use std::{cell::RefCell, fs, path::Path};
+
+/// file to stash with `stash_save_paths`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StashPath {
+	/// repo relative path
+	pub path: String,
+	/// header hashes of the workdir hunks to stash,
+	/// `None` stashes all changes of the file incl. the staged ones
+	pub hunks: Option<Vec<u64>>,
+}
+
+const STASH_REF: &str = "refs/stash";
 
 ///
 pub fn get_stashes(repo_path: &str) -> Result<Vec<CommitId>> {
@@ -79,6 +100,122 @@ pub fn stash_apply(
 	Ok(())
 }
 
+/// creates the branch `name` at the commit the stash was created on,
+/// checks it out and applies the stash incl. its index there, the
+/// stash gets dropped if it applied cleanly (like `git stash branch`)
+pub fn stash_branch(
+	repo_path: &str,
+	stash_id: CommitId,
+	name: &str,
+) -> Result<String> {
+	scope_time!("stash_branch");
+
+	let mut repo = repo(repo_path)?;
+
+	let index = get_stash_index(&mut repo, stash_id.get_oid())?;
+	let branch_ref = checkout_new_branch(
+		&repo,
+		&repo.find_commit(stash_id.into())?.parent(0)?,
+		name,
+	)?;
+
+	let mut opt = StashApplyOptions::default();
+	opt.reinstantiate_index();
+	repo.stash_apply(index, Some(&mut opt))?;
+	repo.stash_drop(index)?;
+
+	Ok(branch_ref)
+}
+
+/// changes the message of the stash `stash_id`, it keeps its position
+/// in the stash list. returns the id of the reworded stash
+pub fn stash_reword(
+	repo_path: &str,
+	stash_id: CommitId,
+	message: &str,
+) -> Result<CommitId> {
+	scope_time!("stash_reword");
+
+	let repo = repo(repo_path)?;
+
+	let stash = repo.find_commit(stash_id.into())?;
+	let parents = stash.parents().collect::<Vec<_>>();
+	let new_id = repo.commit(
+		None,
+		&stash.author(),
+		&stash.committer(),
+		message,
+		&stash.tree()?,
+		&parents.iter().collect::<Vec<_>>(),
+	)?;
+
+	let reflog = repo.reflog(STASH_REF)?;
+	let entries = reflog
+		.iter()
+		.map(|entry| {
+			let id = entry.id_new();
+			if id == stash_id.get_oid() {
+				(
+					new_id,
+					entry.committer().to_owned(),
+					message.to_string(),
+				)
+			} else {
+				(
+					id,
+					entry.committer().to_owned(),
+					entry.message().unwrap_or_default().to_string(),
+				)
+			}
+		})
+		.collect::<Vec<_>>();
+
+	if !entries.iter().any(|(id, ..)| *id == new_id) {
+		return Err(Error::Generic(
+			"stash commit not found".to_string(),
+		));
+	}
+
+	if entries.first().map_or(false, |(id, ..)| *id == new_id) {
+		repo.reference(STASH_REF, new_id, true, message)?;
+	}
+
+	// rebuild the reflog (newest entry first) to keep the order
+	let mut reflog = repo.reflog(STASH_REF)?;
+	while !reflog.is_empty() {
+		reflog.remove(0, false)?;
+	}
+	for (id, committer, message) in entries.iter().rev() {
+		reflog.append(*id, committer, Some(message))?;
+	}
+	reflog.write()?;
+
+	Ok(CommitId::new(new_id))
+}
+
+fn checkout_new_branch(
+	repo: &Repository,
+	base: &Commit,
+	name: &str,
+) -> Result<String> {
+	let branch = repo.branch(name, base, false)?;
+	let branch_ref = branch
+		.get()
+		.name()
+		.ok_or_else(|| {
+			Error::Generic("invalid branch name".to_string())
+		})?
+		.to_string();
+
+	if let Err(e) = repo.checkout_tree(base.as_object(), None) {
+		branch.into_reference().delete()?;
+		return Err(e.into());
+	}
+	repo.set_head(&branch_ref)?;
+
+	Ok(branch_ref)
+}
+
 fn get_stash_index(
 	repo: &mut Repository,
 	stash_id: Oid,
@@ -126,11 +263,341 @@ pub fn stash_save(
 	Ok(CommitId::new(id))
 }
 
+/// stashes only the changes of `paths` and leaves all other changes in
+/// place (like `git stash push -- <paths>` and `git stash -p`).
+///
+/// untracked `paths` are skipped unless `include_untracked` is set.
+/// files stashed with hunks keep their staged changes in the index
+/// (like `git stash -p`), other files only if `keep_index` is set
+pub fn stash_save_paths(
+	repo_path: &str,
+	message: Option<&str>,
+	paths: &[StashPath],
+	include_untracked: bool,
+	keep_index: bool,
+) -> Result<CommitId> {
+	scope_time!("stash_save_paths");
+
+	let repo = repo(repo_path)?;
+	let work_dir = work_dir(&repo)?;
+	let head = repo.head()?.peel_to_commit()?;
+	let mut index = repo.index()?;
+
+	let (untracked, tracked): (Vec<_>, Vec<_>) =
+		paths.iter().cloned().partition(|p| {
+			index.get_path(Path::new(&p.path), 0).is_none()
+				&& work_dir.join(&p.path).is_file()
+		});
+	let untracked = if include_untracked {
+		untracked
+	} else {
+		Vec::new()
+	};
+
+	if tracked.is_empty() && untracked.is_empty() {
+		return Err(Error::Generic("nothing to stash".to_string()));
+	}
+
+	// index commit: HEAD with the staged version of `paths`
+	let mut stash_index = Index::new()?;
+	stash_index.read_tree(&head.tree()?)?;
+	for p in &tracked {
+		let path = Path::new(&p.path);
+		if let Some(entry) = index.get_path(path, 0) {
+			stash_index.add(&entry)?;
+		} else if stash_index.get_path(path, 0).is_some() {
+			stash_index.remove_path(path)?;
+		}
+	}
+	let index_tree =
+		repo.find_tree(stash_index.write_tree_to(&repo)?)?;
+
+	// workdir commit: index commit with the selected workdir changes
+	let diff = workdir_diff(&repo, &index, &tracked)?;
+	let workdir_tree = repo.find_tree(
+		apply_paths(&repo, &index_tree, &diff, &tracked, true)?
+			.write_tree_to(&repo)?,
+	)?;
+
+	// untracked files go into a 3rd parent like `git stash -u` does
+	let untracked_tree = untracked_tree(&repo, work_dir, &untracked)?;
+
+	let id = commit_stash(
+		&repo,
+		&head,
+		message,
+		&index_tree,
+		&workdir_tree,
+		untracked_tree.as_ref(),
+	)?;
+
+	let paths = tracked
+		.into_iter()
+		.chain(
+			untracked
+				.into_iter()
+				.map(|p| StashPath { hunks: None, ..p }),
+		)
+		.collect::<Vec<_>>();
+	remove_stashed(&repo, &mut index, &head, &paths, keep_index)?;
+
+	Ok(id)
+}
+
+fn workdir_diff<'a>(
+	repo: &'a Repository,
+	index: &Index,
+	paths: &[StashPath],
+) -> Result<Diff<'a>> {
+	let mut opt = git2::DiffOptions::new();
+	opt.show_binary(true).disable_pathspec_match(true);
+	for p in paths {
+		opt.pathspec(&p.path);
+	}
+
+	Ok(repo.diff_index_to_workdir(Some(index), Some(&mut opt))?)
+}
+
+/// applies the hunks of `diff` selected by `paths` to `tree`
+/// (or all other hunks if `selected` is false)
+fn apply_paths(
+	repo: &Repository,
+	tree: &Tree,
+	diff: &Diff,
+	paths: &[StashPath],
+	selected: bool,
+) -> Result<Index> {
+	let current: RefCell<Option<&StashPath>> = RefCell::new(None);
+
+	let mut opt = ApplyOptions::new();
+	opt.delta_callback(|delta| {
+		*current.borrow_mut() = delta
+			.and_then(|delta| {
+				delta
+					.new_file()
+					.path()
+					.or_else(|| delta.old_file().path())
+			})
+			.and_then(|path| {
+				paths.iter().find(|p| Path::new(&p.path) == path)
+			});
+		true
+	});
+	opt.hunk_callback(|hunk| {
+		hunk.map_or(false, |hunk| {
+			let hunk_hash = hash(&HunkHeader::from(hunk));
+			let is_selected = current.borrow().map_or(false, |p| {
+				p.hunks
+					.as_ref()
+					.map_or(true, |hunks| hunks.contains(&hunk_hash))
+			});
+			is_selected == selected
+		})
+	});
+
+	Ok(repo.apply_to_tree(tree, diff, Some(&mut opt))?)
+}
+
+fn untracked_tree<'a>(
+	repo: &'a Repository,
+	work_dir: &Path,
+	paths: &[StashPath],
+) -> Result<Option<Tree<'a>>> {
+	if paths.is_empty() {
+		return Ok(None);
+	}
+
+	let mut index = Index::new()?;
+	for p in paths {
+		index.add(&IndexEntry {
+			ctime: IndexTime::new(0, 0),
+			mtime: IndexTime::new(0, 0),
+			dev: 0,
+			ino: 0,
+			mode: FileMode::Blob.into(),
+			uid: 0,
+			gid: 0,
+			file_size: 0,
+			id: repo.blob_path(&work_dir.join(&p.path))?,
+			flags: 0,
+			flags_extended: 0,
+			path: p.path.as_bytes().to_vec(),
+		})?;
+	}
+
+	Ok(Some(repo.find_tree(index.write_tree_to(repo)?)?))
+}
+
+fn commit_stash(
+	repo: &Repository,
+	head: &Commit,
+	message: Option<&str>,
+	index_tree: &Tree,
+	workdir_tree: &Tree,
+	untracked_tree: Option<&Tree>,
+) -> Result<CommitId> {
+	let sig = repo.signature()?;
+
+	let branch = repo
+		.head()?
+		.shorthand()
+		.filter(|_| !repo.head_detached().unwrap_or_default())
+		.unwrap_or("(no branch)")
+		.to_string();
+	let head_info = format!(
+		"{}: {} {}",
+		branch,
+		CommitId::new(head.id()).get_short_string(),
+		head.summary().unwrap_or_default()
+	);
+
+	let index_commit = repo.find_commit(repo.commit(
+		None,
+		&sig,
+		&sig,
+		&format!("index on {}", head_info),
+		index_tree,
+		&[head],
+	)?)?;
+
+	let untracked_commit = untracked_tree
+		.map(|tree| {
+			repo.commit(
+				None,
+				&sig,
+				&sig,
+				&format!("untracked files on {}", head_info),
+				tree,
+				&[],
+			)
+			.and_then(|id| repo.find_commit(id))
+		})
+		.transpose()?;
+
+	let mut parents = vec![head, &index_commit];
+	if let Some(commit) = &untracked_commit {
+		parents.push(commit);
+	}
+
+	let message = message.map_or_else(
+		|| format!("WIP on {}", head_info),
+		|message| format!("On {}: {}", branch, message),
+	);
+
+	let id = repo.commit(
+		None,
+		&sig,
+		&sig,
+		&message,
+		workdir_tree,
+		&parents,
+	)?;
+
+	repo.reference_ensure_log(STASH_REF)?;
+	repo.reference(STASH_REF, id, true, &message)?;
+
+	Ok(CommitId::new(id))
+}
+
+/// resets stashed files to HEAD (or to the index with `keep_index`)
+/// and removes stashed hunks from the workdir
+fn remove_stashed(
+	repo: &Repository,
+	index: &mut Index,
+	head: &Commit,
+	paths: &[StashPath],
+	keep_index: bool,
+) -> Result<()> {
+	let work_dir = work_dir(repo)?;
+
+	let (hunk_paths, file_paths): (Vec<_>, Vec<_>) =
+		paths.iter().cloned().partition(|p| p.hunks.is_some());
+
+	if !hunk_paths.is_empty() {
+		let index_tree = repo.find_tree(index.write_tree()?)?;
+		let diff = workdir_diff(repo, index, &hunk_paths)?;
+		let remaining = apply_paths(
+			repo,
+			&index_tree,
+			&diff,
+			&hunk_paths,
+			false,
+		)?;
+
+		for p in &hunk_paths {
+			let file = work_dir.join(&p.path);
+			match remaining.get_path(Path::new(&p.path), 0) {
+				Some(entry) => {
+					fs::write(
+						file,
+						repo.find_blob(entry.id)?.content(),
+					)?;
+				}
+				None => fs::remove_file(file)?,
+			}
+		}
+	}
+
+	if !file_paths.is_empty() && keep_index {
+		let (staged, untracked): (Vec<_>, Vec<_>) = file_paths
+			.iter()
+			.map(|p| p.path.as_str())
+			.partition(|p| index.get_path(Path::new(p), 0).is_some());
+
+		for path in &untracked {
+			let file = work_dir.join(path);
+			if file.exists() {
+				fs::remove_file(file)?;
+			}
+		}
+
+		if !staged.is_empty() {
+			let mut checkout = CheckoutBuilder::new();
+			checkout.force();
+			for path in &staged {
+				checkout.path(path);
+			}
+			repo.checkout_index(Some(index), Some(&mut checkout))?;
+		}
+	} else if !file_paths.is_empty() {
+		let head_tree = head.tree()?;
+		let (tracked, untracked): (Vec<_>, Vec<_>) = file_paths
+			.iter()
+			.map(|p| p.path.as_str())
+			.partition(|p| head_tree.get_path(Path::new(p)).is_ok());
+
+		for path in &untracked {
+			if index.get_path(Path::new(path), 0).is_some() {
+				index.remove_path(Path::new(path))?;
+			}
+			let file = work_dir.join(path);
+			if file.exists() {
+				fs::remove_file(file)?;
+			}
+		}
+		index.write()?;
+
+		if !tracked.is_empty() {
+			repo.reset_default(Some(head.as_object()), &tracked)?;
+
+			let mut checkout = CheckoutBuilder::new();
+			checkout.force();
+			for path in &tracked {
+				checkout.path(path);
+			}
+			repo.checkout_index(None, Some(&mut checkout))?;
+		}
+	}
+
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::sync::{
-		commit, get_commit_files, get_commits_info, stage_add_file,
+		commit, get_commit_files, get_commits_info, reset_stage,
+		reset_workdir, stage_add_file,
 		tests::{
 			debug_cmd_print, get_statuses, repo_init,
 			write_commit_file,
@@ -367,4 +834,278 @@ mod tests {
 			"test3"
 		);
 	}
+
+	#[test]
+	fn test_stash_branch() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let base = write_commit_file(&repo, "test.txt", "test", "c1");
+
+		repo_write_file(&repo, "test.txt", "test2").unwrap();
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+		stage_add_file(repo_path, Path::new("new.txt")).unwrap();
+
+		let id =
+			stash_save(repo_path, Some("foo"), true, false).unwrap();
+
+		write_commit_file(&repo, "test.txt", "test3", "c2");
+
+		assert!(stash_branch(repo_path, id, "master").is_err());
+
+		assert_eq!(
+			stash_branch(repo_path, id, "from-stash").unwrap(),
+			"refs/heads/from-stash"
+		);
+
+		assert_eq!(
+			repo.head().unwrap().name().unwrap(),
+			"refs/heads/from-stash"
+		);
+		assert_eq!(
+			repo.head().unwrap().peel_to_commit().unwrap().id(),
+			base.get_oid()
+		);
+		assert_eq!(
+			repo_read_file(&repo, "test.txt").unwrap(),
+			"test2"
+		);
+		assert_eq!(get_statuses(repo_path), (1, 1));
+		assert!(get_stashes(repo_path).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_stash_reword() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "test.txt", "test", "c1");
+
+		for msg in &["s1", "s2", "s3"] {
+			repo_write_file(&repo, "test.txt", msg).unwrap();
+			stash_save(repo_path, Some(msg), true, false).unwrap();
+		}
+
+		let stash_messages = || {
+			let stashes = get_stashes(repo_path).unwrap();
+			get_commits_info(repo_path, &stashes, 100)
+				.unwrap()
+				.into_iter()
+				.map(|c| c.message)
+				.collect::<Vec<_>>()
+		};
+
+		let stashes = get_stashes(repo_path).unwrap();
+
+		stash_reword(repo_path, stashes[1], "renamed").unwrap();
+		assert_eq!(
+			stash_messages(),
+			vec!["On master: s3", "renamed", "On master: s1"]
+		);
+
+		let id = stash_reword(repo_path, stashes[0], "top").unwrap();
+		assert_eq!(
+			stash_messages(),
+			vec!["top", "renamed", "On master: s1"]
+		);
+		assert_eq!(get_stashes(repo_path).unwrap()[0], id);
+
+		assert!(stash_reword(repo_path, stashes[0], "x").is_err());
+
+		stash_pop(repo_path, get_stashes(repo_path).unwrap()[1])
+			.unwrap();
+		assert_eq!(repo_read_file(&repo, "test.txt").unwrap(), "s2");
+		assert_eq!(stash_messages(), vec!["top", "On master: s1"]);
+	}
+
+	#[test]
+	fn test_stash_save_paths() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		write_commit_file(&repo, "b.txt", "b", "c2");
+
+		repo_write_file(&repo, "a.txt", "a2").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		repo_write_file(&repo, "b.txt", "b2").unwrap();
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+
+		let id = stash_save_paths(
+			repo_path,
+			Some("foo"),
+			&[
+				StashPath {
+					path: String::from("a.txt"),
+					hunks: None,
+				},
+				StashPath {
+					path: String::from("new.txt"),
+					hunks: None,
+				},
+			],
+			true,
+			false,
+		)
+		.unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a");
+		assert_eq!(repo_read_file(&repo, "b.txt").unwrap(), "b2");
+		assert!(!root.join("new.txt").exists());
+		assert_eq!(get_statuses(repo_path), (1, 0));
+
+		let stashes = get_stashes(repo_path).unwrap();
+		assert_eq!(stashes, vec![id]);
+		let info =
+			get_commits_info(repo_path, &stashes, 100).unwrap();
+		assert_eq!(info[0].message, "On master: foo");
+
+		stash_apply(repo_path, id, false).unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a2");
+		assert_eq!(repo_read_file(&repo, "new.txt").unwrap(), "new");
+		assert_eq!(get_statuses(repo_path), (3, 0));
+	}
+
+	#[test]
+	fn test_stash_save_paths_options() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		repo_write_file(&repo, "a.txt", "a2").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		repo_write_file(&repo, "a.txt", "a3").unwrap();
+		repo_write_file(&repo, "new.txt", "new").unwrap();
+
+		let paths = [
+			StashPath {
+				path: String::from("a.txt"),
+				hunks: None,
+			},
+			StashPath {
+				path: String::from("new.txt"),
+				hunks: None,
+			},
+		];
+
+		assert!(stash_save_paths(
+			repo_path,
+			None,
+			&paths[1..],
+			false,
+			false
+		)
+		.is_err());
+
+		let id =
+			stash_save_paths(repo_path, None, &paths, false, true)
+				.unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a2");
+		assert_eq!(repo_read_file(&repo, "new.txt").unwrap(), "new");
+		assert_eq!(get_statuses(repo_path), (1, 1));
+
+		fs::remove_file(root.join("new.txt")).unwrap();
+		reset_stage(repo_path, "a.txt").unwrap();
+		reset_workdir(repo_path, "a.txt").unwrap();
+		stash_apply(repo_path, id, false).unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a3");
+		assert!(!root.join("new.txt").exists());
+	}
+
+	#[test]
+	fn test_stash_save_paths_renamed() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		fs::rename(root.join("a.txt"), root.join("b.txt")).unwrap();
+
+		let id = stash_save_paths(
+			repo_path,
+			None,
+			&[
+				StashPath {
+					path: String::from("b.txt"),
+					hunks: None,
+				},
+				StashPath {
+					path: String::from("a.txt"),
+					hunks: None,
+				},
+			],
+			true,
+			false,
+		)
+		.unwrap();
+
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "a");
+		assert!(!root.join("b.txt").exists());
+		assert_eq!(get_statuses(repo_path), (0, 0));
+
+		stash_apply(repo_path, id, false).unwrap();
+
+		assert!(!root.join("a.txt").exists());
+		assert_eq!(repo_read_file(&repo, "b.txt").unwrap(), "a");
+	}
+
+	#[test]
+	fn test_stash_save_hunks() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		let lines =
+			(0..20).map(|i| i.to_string() + "\n").collect::<String>();
+		write_commit_file(&repo, "a.txt", &lines, "c1");
+
+		let changed = lines
+			.replace("\n1\n", "\nx\n")
+			.replace("\n18\n", "\ny\n");
+		repo_write_file(&repo, "a.txt", &changed).unwrap();
+
+		let diff = crate::sync::diff::get_diff(
+			repo_path, "a.txt", false, None,
+		)
+		.unwrap();
+		assert_eq!(diff.hunks.len(), 2);
+
+		stash_save_paths(
+			repo_path,
+			None,
+			&[StashPath {
+				path: String::from("a.txt"),
+				hunks: Some(vec![diff.hunks[1].header_hash]),
+			}],
+			true,
+			false,
+		)
+		.unwrap();
+
+		assert_eq!(
+			repo_read_file(&repo, "a.txt").unwrap(),
+			lines.replace("\n1\n", "\nx\n")
+		);
+
+		let id = get_stashes(repo_path).unwrap()[0];
+		let info = get_commits_info(repo_path, &[id], 100).unwrap();
+		assert!(info[0].message.starts_with("WIP on master: "));
+
+		repo_write_file(&repo, "a.txt", &lines).unwrap();
+		stash_pop(repo_path, id).unwrap();
+
+		assert_eq!(
+			repo_read_file(&repo, "a.txt").unwrap(),
+			lines.replace("\n18\n", "\ny\n")
+		);
+	}
 }
@@ -11,6 +11,8 @@ pub enum RepoState {
 	Merge,
 	///
 	Rebase,
+	/// `git am` like patch series apply
+	ApplyMailbox,
 	///
 	Other,
 }
@@ -21,6 +23,7 @@ impl From<RepositoryState> for RepoState {
 			RepositoryState::Clean => Self::Clean,
 			RepositoryState::Merge => Self::Merge,
 			RepositoryState::RebaseMerge => Self::Rebase,
+			RepositoryState::ApplyMailbox => Self::ApplyMailbox,
 			_ => Self::Other,
 		}
 	}
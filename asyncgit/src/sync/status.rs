@@ -3,9 +3,13 @@
 use crate::{
 	error::Error,
 	error::Result,
-	sync::{config::untracked_files_config_repo, utils},
+	sync::{
+		config::untracked_files_config_repo, diff::DiffOptions, utils,
+	},
+};
+use git2::{
+	Delta, Diff, Repository, Status, StatusOptions, StatusShow,
 };
-use git2::{Delta, Status, StatusOptions, StatusShow};
 use scopetime::scope_time;
 use std::path::Path;
 
@@ -23,14 +27,20 @@ pub enum StatusItemType {
 	///
 	Renamed,
 	///
+	Copied,
+	///
 	Typechange,
 	///
 	Conflicted,
+	///
+	Ignored,
 }
 
 impl From<Status> for StatusItemType {
 	fn from(s: Status) -> Self {
-		if s.is_index_new() || s.is_wt_new() {
+		if s.is_ignored() {
+			Self::Ignored
+		} else if s.is_index_new() || s.is_wt_new() {
 			Self::New
 		} else if s.is_index_deleted() || s.is_wt_deleted() {
 			Self::Deleted
@@ -52,6 +62,7 @@ impl From<Delta> for StatusItemType {
 			Delta::Added => Self::New,
 			Delta::Deleted => Self::Deleted,
 			Delta::Renamed => Self::Renamed,
+			Delta::Copied => Self::Copied,
 			Delta::Typechange => Self::Typechange,
 			_ => Self::Modified,
 		}
@@ -65,6 +76,8 @@ pub struct StatusItem {
 	pub path: String,
 	///
 	pub status: StatusItemType,
+	/// path before the rename or the copy source
+	pub old_path: Option<String>,
 }
 
 ///
@@ -99,6 +112,23 @@ pub fn get_status(
 	repo_path: &str,
 	status_type: StatusType,
 	show_untracked: Option<ShowUntrackedFilesConfig>,
+) -> Result<Vec<StatusItem>> {
+	get_status_with_renames(
+		repo_path,
+		status_type,
+		show_untracked,
+		DiffOptions::default(),
+	)
+}
+
+/// like `get_status` but detects renames/copies using the thresholds
+/// in `options` (between head and index as well as between index and
+/// workdir), without thresholds only staged renames are detected
+pub fn get_status_with_renames(
+	repo_path: &str,
+	status_type: StatusType,
+	show_untracked: Option<ShowUntrackedFilesConfig>,
+	options: DiffOptions,
 ) -> Result<Vec<StatusItem>> {
 	scope_time!("get_status");
 
@@ -110,52 +140,289 @@ pub fn get_status(
 		untracked_files_config_repo(&repo)?
 	};
 
-	let mut options = StatusOptions::default();
-	options
+	let detect_similar = options.rename_threshold.is_some()
+		|| options.copy_threshold.is_some();
+
+	let mut status_options = StatusOptions::default();
+	status_options
 		.show(status_type.into())
 		.update_index(true)
 		.include_untracked(show_untracked.include_untracked())
-		.renames_head_to_index(true)
+		.renames_head_to_index(!detect_similar)
 		.recurse_untracked_dirs(
 			show_untracked.recurse_untracked_dirs(),
 		);
 
-	let statuses = repo.statuses(Some(&mut options))?;
+	let statuses = repo.statuses(Some(&mut status_options))?;
 
 	let mut res = Vec::with_capacity(statuses.len());
 
 	for e in statuses.iter() {
 		let status: Status = e.status();
 
-		let path = match e.head_to_index() {
-			Some(diff) => diff
-				.new_file()
-				.path()
-				.and_then(Path::to_str)
-				.map(String::from)
-				.ok_or_else(|| {
+		let (path, old_path) = match e.head_to_index() {
+			Some(diff) => {
+				let path = diff
+					.new_file()
+					.path()
+					.and_then(Path::to_str)
+					.map(String::from)
+					.ok_or_else(|| {
+						Error::Generic(
+							"failed to get path to diff's new file."
+								.to_string(),
+						)
+					})?;
+				let old_path = diff
+					.old_file()
+					.path()
+					.and_then(Path::to_str)
+					.filter(|old_path| *old_path != path)
+					.map(String::from);
+
+				(path, old_path)
+			}
+			None => (
+				e.path().map(String::from).ok_or_else(|| {
 					Error::Generic(
-						"failed to get path to diff's new file."
+						"failed to get the path to indexed file."
 							.to_string(),
 					)
 				})?,
-			None => e.path().map(String::from).ok_or_else(|| {
-				Error::Generic(
-					"failed to get the path to indexed file."
-						.to_string(),
-				)
-			})?,
+				None,
+			),
 		};
 
 		res.push(StatusItem {
 			path,
 			status: StatusItemType::from(status),
+			old_path,
 		});
 	}
 
+	if detect_similar {
+		if !matches!(status_type, StatusType::WorkingDir) {
+			let diff = diff_head_to_index(&repo)?;
+			apply_similar(diff, &options, &mut res)?;
+		}
+		if !matches!(status_type, StatusType::Stage) {
+			let diff = diff_index_to_workdir(&repo, show_untracked)?;
+			apply_similar(diff, &options, &mut res)?;
+		}
+	}
+
 	res.sort_by(|a, b| {
 		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
 	});
 
 	Ok(res)
 }
+
+fn diff_head_to_index(repo: &Repository) -> Result<Diff<'_>> {
+	let tree = match utils::get_head_repo(repo) {
+		Ok(id) => Some(repo.find_commit(id.into())?.tree()?),
+		Err(_) => None,
+	};
+
+	Ok(repo.diff_tree_to_index(tree.as_ref(), None, None)?)
+}
+
+fn diff_index_to_workdir(
+	repo: &Repository,
+	show_untracked: ShowUntrackedFilesConfig,
+) -> Result<Diff<'_>> {
+	let mut opt = git2::DiffOptions::new();
+	opt.include_untracked(show_untracked.include_untracked())
+		.recurse_untracked_dirs(true);
+
+	Ok(repo.diff_index_to_workdir(None, Some(&mut opt))?)
+}
+
+/// merges the deleted/new entries in `res` that `diff` pairs up as
+/// renames into one `Renamed` entry and marks copies as `Copied`
+fn apply_similar(
+	mut diff: Diff,
+	options: &DiffOptions,
+	res: &mut Vec<StatusItem>,
+) -> Result<()> {
+	options.find_similar(&mut diff, false)?;
+
+	for delta in diff.deltas() {
+		let status = delta.status();
+		if status != Delta::Renamed && status != Delta::Copied {
+			continue;
+		}
+
+		let (old_path, new_path) = match (
+			delta.old_file().path().and_then(Path::to_str),
+			delta.new_file().path().and_then(Path::to_str),
+		) {
+			(Some(old_path), Some(new_path)) => (old_path, new_path),
+			_ => continue,
+		};
+
+		let new_idx = res.iter().position(|item| {
+			item.path == new_path
+				&& item.status == StatusItemType::New
+		});
+		let old_idx = res.iter().position(|item| {
+			item.path == old_path
+				&& item.status == StatusItemType::Deleted
+		});
+
+		match (status, new_idx, old_idx) {
+			(Delta::Renamed, Some(new_idx), Some(old_idx)) => {
+				res[new_idx].status = StatusItemType::Renamed;
+				res[new_idx].old_path = Some(old_path.to_string());
+				res.remove(old_idx);
+			}
+			(Delta::Copied, Some(new_idx), _) => {
+				res[new_idx].status = StatusItemType::Copied;
+				res[new_idx].old_path = Some(old_path.to_string());
+			}
+			_ => (),
+		}
+	}
+
+	Ok(())
+}
+
+/// ignored files and folders of the workdir, ignored folders are
+/// listed once (without trailing slash) instead of their content
+pub fn get_ignored(repo_path: &str) -> Result<Vec<StatusItem>> {
+	scope_time!("get_ignored");
+
+	let repo = utils::repo(repo_path)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.show(StatusShow::Workdir)
+		.include_untracked(true)
+		.recurse_untracked_dirs(true)
+		.include_ignored(true)
+		.recurse_ignored_dirs(false);
+
+	let statuses = repo.statuses(Some(&mut options))?;
+
+	let mut res = statuses
+		.iter()
+		.filter(|e| e.status().is_ignored())
+		.filter_map(|e| {
+			e.path().map(|path| StatusItem {
+				path: path.trim_end_matches('/').to_string(),
+				status: StatusItemType::Ignored,
+				old_path: None,
+			})
+		})
+		.collect::<Vec<_>>();
+
+	res.sort_by(|a, b| {
+		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+	});
+
+	Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use std::fs;
+
+	const CONTENT: &str = "1\n2\n3\n4\n5\n6\n7\n8\n";
+
+	fn statuses(
+		repo_path: &str,
+		rename_threshold: Option<u16>,
+		copy_threshold: Option<u16>,
+	) -> Vec<(String, StatusItemType, Option<String>)> {
+		get_status_with_renames(
+			repo_path,
+			StatusType::WorkingDir,
+			None,
+			DiffOptions {
+				rename_threshold,
+				copy_threshold,
+				..DiffOptions::default()
+			},
+		)
+		.unwrap()
+		.into_iter()
+		.map(|i| (i.path, i.status, i.old_path))
+		.collect()
+	}
+
+	#[test]
+	fn test_workdir_rename() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", CONTENT, "c1");
+		fs::remove_file(root.join("a.txt")).unwrap();
+		repo_write_file(&repo, "b.txt", &format!("{}9\n", CONTENT))
+			.unwrap();
+
+		assert_eq!(
+			statuses(repo_path, None, None),
+			vec![
+				(
+					String::from("a.txt"),
+					StatusItemType::Deleted,
+					None
+				),
+				(String::from("b.txt"), StatusItemType::New, None),
+			]
+		);
+		assert_eq!(
+			statuses(repo_path, Some(50), None),
+			vec![(
+				String::from("b.txt"),
+				StatusItemType::Renamed,
+				Some(String::from("a.txt"))
+			)]
+		);
+		assert_eq!(statuses(repo_path, Some(99), None).len(), 2);
+
+		// plain `get_status` does not pair up renames in the workdir
+		assert_eq!(
+			get_status(repo_path, StatusType::WorkingDir, None)
+				.unwrap()
+				.into_iter()
+				.map(|i| i.status)
+				.collect::<Vec<_>>(),
+			vec![StatusItemType::Deleted, StatusItemType::New]
+		);
+	}
+
+	#[test]
+	fn test_workdir_copy() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, "a.txt", CONTENT, "c1");
+		repo_write_file(&repo, "a.txt", &format!("0\n{}", CONTENT))
+			.unwrap();
+		repo_write_file(&repo, "c.txt", CONTENT).unwrap();
+
+		assert_eq!(
+			statuses(repo_path, Some(50), Some(50)),
+			vec![
+				(
+					String::from("a.txt"),
+					StatusItemType::Modified,
+					None
+				),
+				(
+					String::from("c.txt"),
+					StatusItemType::Copied,
+					Some(String::from("a.txt"))
+				),
+			]
+		);
+	}
+}
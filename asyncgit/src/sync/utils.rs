@@ -5,7 +5,10 @@ use crate::{
 	error::{Error, Result},
 	sync::config::untracked_files_config_repo,
 };
-use git2::{IndexAddOption, Repository, RepositoryOpenFlags};
+use git2::{
+	IndexAddOption, Pathspec, PathspecFlags, Repository,
+	RepositoryOpenFlags,
+};
 use scopetime::scope_time;
 use std::{
 	fs::File,
@@ -124,6 +127,49 @@ pub fn stage_add_file(repo_path: &str, path: &Path) -> Result<()> {
 	Ok(())
 }
 
+/// add a file or folder to stage even if it is ignored (like `git add -f`)
+pub fn stage_add_force(repo_path: &str, path: &Path) -> Result<()> {
+	scope_time!("stage_add_force");
+
+	let repo = repo(repo_path)?;
+
+	let mut index = repo.index()?;
+
+	// `add_path` does not check ignore rules but wants single files
+	let mut files = Vec::new();
+	collect_files(work_dir(&repo)?, path, &mut files)?;
+	for file in files {
+		index.add_path(&file)?;
+	}
+
+	index.write()?;
+
+	Ok(())
+}
+
+/// all files in `path` (relative to `work_dir`) recursively
+fn collect_files(
+	work_dir: &Path,
+	path: &Path,
+	files: &mut Vec<PathBuf>,
+) -> Result<()> {
+	let full_path = work_dir.join(path);
+
+	if full_path.is_dir() {
+		for entry in std::fs::read_dir(full_path)? {
+			collect_files(
+				work_dir,
+				&path.join(entry?.file_name()),
+				files,
+			)?;
+		}
+	} else {
+		files.push(path.to_path_buf());
+	}
+
+	Ok(())
+}
+
 /// like `stage_add_file` but uses a pattern to match/glob multiple files/folders
 pub fn stage_add_all(
 	repo_path: &str,
@@ -186,6 +232,36 @@ pub fn stage_addremoved(repo_path: &str, path: &Path) -> Result<()> {
 	Ok(())
 }
 
+/// filters `items` by a pathspec / glob `pattern` (like `src/**/*.rs`),
+/// `path` returns the repo relative path of an item
+pub fn filter_by_pathspec<T, F>(
+	pattern: &str,
+	items: Vec<T>,
+	path: F,
+) -> Result<Vec<T>>
+where
+	F: Fn(&T) -> &str,
+{
+	// `**/` also matches no directory at all
+	let patterns = if pattern.contains("**/") {
+		vec![pattern.to_string(), pattern.replace("**/", "")]
+	} else {
+		vec![pattern.to_string()]
+	};
+
+	let pathspec = Pathspec::new(patterns)?;
+
+	Ok(items
+		.into_iter()
+		.filter(|item| {
+			pathspec.matches_path(
+				Path::new(path(item)),
+				PathspecFlags::DEFAULT,
+			)
+		})
+		.collect())
+}
+
 pub(crate) fn bytes2string(bytes: &[u8]) -> Result<String> {
 	Ok(String::from_utf8(bytes.to_vec())?)
 }
@@ -279,6 +355,25 @@ mod tests {
 		assert_eq!(get_statuses(repo_path), (1, 1));
 	}
 
+	#[test]
+	fn test_stage_add_force() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path = root.as_os_str().to_str().unwrap();
+
+		write_commit_file(&repo, ".gitignore", "*.log\nout/", "c1");
+		repo_write_file(&repo, "foo.log", "log").unwrap();
+		fs::create_dir(root.join("out")).unwrap();
+		repo_write_file(&repo, "out/a", "a").unwrap();
+
+		assert_eq!(get_statuses(repo_path), (0, 0));
+
+		stage_add_force(repo_path, Path::new("foo.log")).unwrap();
+		stage_add_force(repo_path, Path::new("out")).unwrap();
+
+		assert_eq!(get_statuses(repo_path), (0, 2));
+	}
+
 	#[test]
 	fn test_staging_folder() -> Result<()> {
 		let (_td, repo) = repo_init().unwrap();
@@ -454,4 +549,23 @@ mod tests {
 
 		Ok(())
 	}
+	#[test]
+	fn test_filter_by_pathspec() {
+		let paths =
+			vec!["src/a.rs", "src/x/b.rs", "c.rs", "src/d.txt"];
+
+		let matches =
+			filter_by_pathspec("src/**/*.rs", paths.clone(), |p| p)
+				.unwrap();
+		assert_eq!(matches, vec!["src/a.rs", "src/x/b.rs"]);
+
+		let matches =
+			filter_by_pathspec("*.txt", paths.clone(), |p| p)
+				.unwrap();
+		assert_eq!(matches, vec!["src/d.txt"]);
+
+		let matches =
+			filter_by_pathspec("src", paths, |p| p).unwrap();
+		assert_eq!(matches.len(), 3);
+	}
 }
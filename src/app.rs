@@ -2,20 +2,24 @@ use crate::{
 	accessors,
 	cmdbar::CommandBar,
 	components::{
-		event_pump, AppOption, BlameFileComponent,
-		BranchListComponent, CommandBlocking, CommandInfo,
-		CommitComponent, CompareCommitsComponent, Component,
-		ConfirmComponent, CreateBranchComponent, DrawableComponent,
-		ExternalEditorComponent, FetchComponent, FileFindPopup,
-		HelpComponent, InspectCommitComponent, MsgComponent,
-		OptionsPopupComponent, PullComponent, PushComponent,
+		event_pump, AppOption, ApplyPatchComponent,
+		BlameFileComponent, BranchListComponent, CleanComponent,
+		CommandBlocking, CommandInfo, CommitComponent,
+		CompareCommitsComponent, Component, ConfirmComponent,
+		CreateBranchComponent, DrawableComponent,
+		ExportPatchesComponent, ExternalEditorComponent,
+		FetchComponent, FileFindPopup, GrepPopup, HelpComponent,
+		IgnoreRuleComponent, IndexFlagsComponent,
+		InspectCommitComponent, MoveFileComponent, MsgComponent,
+		Options, OptionsPopupComponent, PullComponent, PushComponent,
 		PushTagsComponent, RenameBranchComponent, RevisionFilesPopup,
-		SharedOptions, StashMsgComponent, TagCommitComponent,
-		TagListComponent,
+		RewordStashComponent, SaveFileComponent, SharedOptions,
+		StageMatchingComponent, StashBranchComponent,
+		StashMsgComponent, TagCommitComponent, TagListComponent,
 	},
 	input::{Input, InputEvent, InputState},
 	keys::{KeyConfig, SharedKeyConfig},
-	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
 	setup_popups,
 	strings::{self, order},
 	tabs::{FilesTab, Revlog, StashList, Stashing, Status},
@@ -23,7 +27,10 @@ use crate::{
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::{bail, Result};
-use asyncgit::{sync, AsyncGitNotification, CWD};
+use asyncgit::{
+	sync::{self, diff::DiffOptions, ApplyLocation, CommitId},
+	AsyncGitNotification, CWD,
+};
 use crossbeam_channel::Sender;
 use crossterm::event::{Event, KeyEvent};
 use std::{
@@ -59,6 +66,17 @@ pub struct App {
 	fetch_popup: FetchComponent,
 	tag_commit_popup: TagCommitComponent,
 	create_branch_popup: CreateBranchComponent,
+	apply_patch_popup: ApplyPatchComponent,
+	stage_matching_popup: StageMatchingComponent,
+	index_flags_popup: IndexFlagsComponent,
+	clean_popup: CleanComponent,
+	ignore_rule_popup: IgnoreRuleComponent,
+	move_file_popup: MoveFileComponent,
+	save_file_popup: SaveFileComponent,
+	grep_popup: GrepPopup,
+	reword_stash_popup: RewordStashComponent,
+	stash_branch_popup: StashBranchComponent,
+	export_patches_popup: ExportPatchesComponent,
 	rename_branch_popup: RenameBranchComponent,
 	select_branch_popup: BranchListComponent,
 	options_popup: OptionsPopupComponent,
@@ -78,6 +96,7 @@ pub struct App {
 	// "Flags"
 	requires_redraw: Cell<bool>,
 	file_to_open: Option<String>,
+	hunk_to_edit: Option<(String, u64, DiffOptions)>,
 }
 
 // public interface
@@ -94,7 +113,8 @@ impl App {
 		let queue = Queue::new();
 		let theme = Rc::new(theme);
 		let key_config = Rc::new(key_config);
-		let options = SharedOptions::default();
+		let options: SharedOptions =
+			Rc::new(RefCell::new(Options::from_git_config()));
 
 		Self {
 			input,
@@ -131,12 +151,14 @@ impl App {
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			compare_commits_popup: CompareCommitsComponent::new(
 				&queue,
 				sender,
 				theme.clone(),
 				key_config.clone(),
+				options.clone(),
 			),
 			external_editor_popup: ExternalEditorComponent::new(
 				theme.clone(),
@@ -176,6 +198,62 @@ impl App {
 				theme.clone(),
 				key_config.clone(),
 			),
+			apply_patch_popup: ApplyPatchComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			stage_matching_popup: StageMatchingComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			index_flags_popup: IndexFlagsComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			clean_popup: CleanComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			ignore_rule_popup: IgnoreRuleComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			move_file_popup: MoveFileComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			save_file_popup: SaveFileComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			grep_popup: GrepPopup::new(
+				&queue,
+				sender,
+				theme.clone(),
+				key_config.clone(),
+			),
+			reword_stash_popup: RewordStashComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			stash_branch_popup: StashBranchComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
+			export_patches_popup: ExportPatchesComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
 			rename_branch_popup: RenameBranchComponent::new(
 				queue.clone(),
 				theme.clone(),
@@ -249,6 +327,7 @@ impl App {
 			key_config,
 			requires_redraw: Cell::new(false),
 			file_to_open: None,
+			hunk_to_edit: None,
 		}
 	}
 
@@ -339,6 +418,26 @@ impl App {
 		} else if let InputEvent::State(polling_state) = ev {
 			self.external_editor_popup.hide();
 			if let InputState::Paused = polling_state {
+				if let Some((path, hunk, options)) =
+					self.hunk_to_edit.take()
+				{
+					match ExternalEditorComponent::edit_hunk(
+						&path, hunk, options,
+					) {
+						Ok(()) => self.update()?,
+						Err(e) => {
+							let msg =
+								format!("edit hunk error:\n{}", e);
+							log::error!("{}", msg.as_str());
+							self.msg.show_error(msg.as_str())?;
+						}
+					}
+
+					self.requires_redraw.set(true);
+					self.input.set_polling(true);
+					return Ok(());
+				}
+
 				let result = match self.file_to_open.take() {
 					Some(path) => {
 						ExternalEditorComponent::open_file_in_editor(
@@ -399,6 +498,7 @@ impl App {
 			self.pull_popup.update_git(ev);
 			self.fetch_popup.update_git(ev);
 			self.select_branch_popup.update_git(ev)?;
+			self.grep_popup.update_git(ev);
 		}
 
 		self.files_tab.update_async(ev);
@@ -433,6 +533,7 @@ impl App {
 			|| self.fetch_popup.any_work_pending()
 			|| self.revision_files_popup.any_work_pending()
 			|| self.tags_popup.any_work_pending()
+			|| self.grep_popup.any_work_pending()
 	}
 
 	///
@@ -466,6 +567,17 @@ impl App {
 			fetch_popup,
 			tag_commit_popup,
 			create_branch_popup,
+			export_patches_popup,
+			apply_patch_popup,
+			stage_matching_popup,
+			index_flags_popup,
+			clean_popup,
+			ignore_rule_popup,
+			move_file_popup,
+			save_file_popup,
+			grep_popup,
+			reword_stash_popup,
+			stash_branch_popup,
 			rename_branch_popup,
 			select_branch_popup,
 			revision_files_popup,
@@ -494,6 +606,17 @@ impl App {
 			select_branch_popup,
 			tags_popup,
 			create_branch_popup,
+			export_patches_popup,
+			apply_patch_popup,
+			stage_matching_popup,
+			index_flags_popup,
+			clean_popup,
+			ignore_rule_popup,
+			move_file_popup,
+			save_file_popup,
+			grep_popup,
+			reword_stash_popup,
+			stash_branch_popup,
 			rename_branch_popup,
 			revision_files_popup,
 			find_file_popup,
@@ -508,7 +631,9 @@ impl App {
 	);
 
 	fn check_quit(&mut self, ev: Event) -> bool {
-		if self.any_popup_visible() {
+		if self.any_popup_visible()
+			|| self.status_tab.is_diff_search_active()
+		{
 			return false;
 		}
 		if let Event::Key(e) = ev {
@@ -647,23 +772,72 @@ impl App {
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::ShowInfoMsg(msg) => {
+				self.msg.show_info(msg.as_str())?;
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::Update(u) => flags.insert(u),
 			InternalEvent::OpenCommit => self.commit.show()?,
-			InternalEvent::PopupStashing(opts) => {
-				self.stashmsg_popup.options(opts);
+			InternalEvent::PopupStashing(opts, paths) => {
+				self.stashmsg_popup.options(opts, paths);
 				self.stashmsg_popup.show()?;
 			}
 			InternalEvent::TagCommit(id) => {
 				self.tag_commit_popup.open(id)?;
 			}
-			InternalEvent::BlameFile(path) => {
-				self.blame_file_popup.open(&path)?;
+			InternalEvent::BlameFile(path, commit) => {
+				self.blame_file_popup.open(&path, commit)?;
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
 			InternalEvent::CreateBranch => {
 				self.create_branch_popup.open()?;
 			}
+			InternalEvent::OpenStageMatching(action, items) => {
+				self.stage_matching_popup.open(action, items)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenIgnoreRule(path) => {
+				self.ignore_rule_popup.open(path)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenStashBranch(id) => {
+				self.stash_branch_popup.open(id)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenRewordStash(id) => {
+				self.reword_stash_popup.open(id)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenGrep(revision) => {
+				self.grep_popup.open(revision)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenSaveFile(id, path) => {
+				self.save_file_popup.open(id, path)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenMoveFile(path) => {
+				self.move_file_popup.open(path)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenClean => {
+				self.clean_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenIndexFlags => {
+				self.index_flags_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenApplyPatch => {
+				self.apply_patch_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ExportPatches(ids) => {
+				self.export_patches_popup.open(ids)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::RenameBranch(branch_ref, cur_name) => {
 				self.rename_branch_popup
 					.open(branch_ref, cur_name)?;
@@ -696,6 +870,12 @@ impl App {
 				self.file_to_open = path;
 				flags.insert(NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::EditHunk(path, hunk, options) => {
+				self.input.set_polling(false);
+				self.external_editor_popup.show()?;
+				self.hunk_to_edit = Some((path, hunk, options));
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::Push(branch, force, delete) => {
 				self.push_popup.push(branch, force, delete)?;
 				flags.insert(NeedsUpdate::ALL);
@@ -735,13 +915,22 @@ impl App {
 			}
 			InternalEvent::OptionSwitched(o) => {
 				match o {
-					AppOption::StatusShowUntracked => {
+					AppOption::StatusShowUntracked
+					| AppOption::StatusShowIgnored
+					| AppOption::DiffRenameThreshold
+					| AppOption::DiffCopyThreshold => {
 						self.status_tab.update()?;
 					}
 					AppOption::DiffContextLines
 					| AppOption::DiffIgnoreWhitespaces
-					| AppOption::DiffInterhunkLines => {
+					| AppOption::DiffIgnoreWhitespaceChange
+					| AppOption::DiffIgnoreWhitespaceEol
+					| AppOption::DiffIgnoreBlankLines
+					| AppOption::DiffInterhunkLines
+					| AppOption::DiffAlgorithm => {
 						self.status_tab.update_diff()?;
+						self.inspect_commit_popup.update_diff()?;
+						self.compare_commits_popup.update_diff()?;
 					}
 				}
 
@@ -752,6 +941,13 @@ impl App {
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::OpenGrepMatch(grep_match, workdir) => {
+				self.files_tab.grep_match(&grep_match, workdir);
+				self.revision_files_popup
+					.grep_match(&grep_match, workdir);
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::FileFinderChanged(file) => {
 				self.files_tab.file_finder_update(&file);
 				self.revision_files_popup.file_finder_update(&file);
@@ -763,6 +959,36 @@ impl App {
 		Ok(flags)
 	}
 
+	fn clean(&self, paths: &[String]) {
+		if let Err(e) = sync::clean_paths(CWD, paths) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"clean error:\n{}",
+				e
+			)));
+		}
+	}
+
+	fn restore_file(
+		&self,
+		id: CommitId,
+		path: &str,
+		hunk: Option<u64>,
+		location: ApplyLocation,
+	) {
+		let res = hunk.map_or_else(
+			|| sync::restore_file(CWD, id, path, location),
+			|hunk| sync::restore_hunk(CWD, id, path, hunk, location),
+		);
+
+		if let Err(e) = res {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"restore error:\n{}",
+				e
+			)));
+		}
+	}
+
+	#[allow(clippy::too_many_lines)]
 	fn process_confirmed_action(
 		&mut self,
 		action: Action,
@@ -774,6 +1000,22 @@ impl App {
 					flags.insert(NeedsUpdate::ALL);
 				}
 			}
+			Action::ResetMatching(paths) => {
+				for path in paths {
+					if !self.status_tab.reset(&ResetItem {
+						path,
+						is_folder: false,
+						old_path: None,
+					}) {
+						break;
+					}
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::Clean(paths) => {
+				self.clean(&paths);
+				flags.insert(NeedsUpdate::ALL);
+			}
 			Action::StashDrop(_) | Action::StashPop(_) => {
 				if let Err(e) = StashList::action_confirmed(&action) {
 					self.queue.push(InternalEvent::ShowErrorMsg(
@@ -849,6 +1091,24 @@ impl App {
 				self.status_tab.abort_rebase();
 				flags.insert(NeedsUpdate::ALL);
 			}
+			Action::AbortApplyMailbox => {
+				self.status_tab.abort_apply_mailbox();
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::RestoreFile(id, path, hunk, location) => {
+				self.restore_file(id, &path, hunk, location);
+				flags.insert(NeedsUpdate::ALL);
+			}
+			Action::SaveRevisionFile(id, path, target) => {
+				if let Err(e) =
+					sync::save_revision_file(CWD, id, &path, &target)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("save file error:\n{}", e),
+					));
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
 		};
 
 		Ok(())
@@ -0,0 +1,452 @@
+use super::{
+	textinput::TextInputComponent,
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, InputType, ScrollType,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{
+		self, ApplyLocation, ApplyMailboxState, MailPatch, PatchFile,
+	},
+	CWD,
+};
+use crossterm::event::Event;
+use std::path::Path;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+#[derive(Copy, Clone, PartialEq)]
+enum ApplyMode {
+	Location(ApplyLocation),
+	Mailbox,
+}
+
+impl ApplyMode {
+	const fn next(self) -> Self {
+		match self {
+			Self::Location(ApplyLocation::WorkDir) => {
+				Self::Location(ApplyLocation::Index)
+			}
+			Self::Location(ApplyLocation::Index) => {
+				Self::Location(ApplyLocation::Both)
+			}
+			Self::Location(ApplyLocation::Both) => Self::Mailbox,
+			Self::Mailbox => Self::Location(ApplyLocation::WorkDir),
+		}
+	}
+
+	const fn name(self) -> &'static str {
+		match self {
+			Self::Location(ApplyLocation::WorkDir) => "workdir",
+			Self::Location(ApplyLocation::Index) => "index",
+			Self::Location(ApplyLocation::Both) => "workdir + index",
+			Self::Mailbox => "mailbox (commits)",
+		}
+	}
+}
+
+enum Preview {
+	Hunks(Vec<PatchFile>),
+	Mails(Vec<MailPatch>),
+	Error(String),
+}
+
+pub struct ApplyPatchComponent {
+	input: TextInputComponent,
+	mode: ApplyMode,
+	preview: Option<Preview>,
+	scroll: VerticalScroll,
+	visible: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ApplyPatchComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(70, 60, f.size());
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(Span::styled(
+						strings::apply_patch_popup_title(
+							&self.key_config,
+						),
+						self.theme.title(true),
+					))
+					.border_style(self.theme.block(true)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Length(3),
+						Constraint::Length(1),
+						Constraint::Min(1),
+					]
+					.as_ref(),
+				)
+				.margin(1)
+				.split(area);
+
+			self.input.draw(f, chunks[0])?;
+
+			f.render_widget(
+				Paragraph::new(Spans::from(vec![
+					Span::styled(
+						strings::apply_patch_mode(&self.key_config),
+						self.theme.text(true, false),
+					),
+					Span::styled(
+						self.mode.name(),
+						self.theme.text(true, true),
+					),
+				])),
+				chunks[1],
+			);
+
+			self.draw_preview(f, chunks[2]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for ApplyPatchComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::apply_patch_preview(
+					&self.key_config,
+				),
+				!self.input.get_text().is_empty(),
+				self.preview.is_none(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::apply_patch_confirm(
+					&self.key_config,
+				),
+				self.can_apply(),
+				self.preview.is_some(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::apply_patch_mode(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.exit_popup {
+					self.hide();
+				} else if e == self.key_config.keys.enter {
+					if self.preview.is_some() {
+						if self.can_apply() {
+							self.apply();
+						}
+					} else {
+						self.update_preview();
+					}
+				} else if e == self.key_config.keys.tab_toggle {
+					self.mode = self.mode.next();
+					if self.preview.is_some() {
+						self.update_preview();
+					}
+				} else if e == self.key_config.keys.popup_down {
+					self.scroll.move_top(ScrollType::Down);
+				} else if e == self.key_config.keys.popup_up {
+					self.scroll.move_top(ScrollType::Up);
+				} else if self.input.event(ev)?.is_consumed() {
+					// path changed
+					self.preview = None;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl ApplyPatchComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut input = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			&strings::apply_patch_file_title(&key_config),
+			&strings::apply_patch_file_msg(&key_config),
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		input.embed();
+
+		Self {
+			input,
+			mode: ApplyMode::Location(ApplyLocation::WorkDir),
+			preview: None,
+			scroll: VerticalScroll::new(),
+			visible: false,
+			queue,
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.preview = None;
+		self.scroll.reset();
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn can_apply(&self) -> bool {
+		match &self.preview {
+			Some(Preview::Hunks(files)) => files.iter().any(|f| {
+				f.applies
+					&& (f.hunks.is_empty()
+						|| f.hunks.iter().any(|h| h.applies))
+			}),
+			Some(Preview::Mails(mails)) => !mails.is_empty(),
+			_ => false,
+		}
+	}
+
+	fn update_preview(&mut self) {
+		let path = Path::new(self.input.get_text());
+
+		let preview = match self.mode {
+			ApplyMode::Location(location) => {
+				sync::patch_preview(CWD, path, location)
+					.map(Preview::Hunks)
+			}
+			ApplyMode::Mailbox => {
+				sync::mailbox_patches(CWD, path).map(Preview::Mails)
+			}
+		};
+
+		self.preview = Some(
+			preview.unwrap_or_else(|e| Preview::Error(e.to_string())),
+		);
+		self.scroll.reset();
+	}
+
+	fn apply(&mut self) {
+		let path = Path::new(self.input.get_text());
+
+		let msg = match self.mode {
+			ApplyMode::Location(location) => sync::apply_patch(
+				CWD, path, location,
+			)
+			.map(|rejected| {
+				strings::apply_patch_done_msg(
+					&rejected
+						.iter()
+						.flat_map(|f| {
+							if f.hunks.is_empty() {
+								vec![f.path.clone()]
+							} else {
+								f.hunks
+									.iter()
+									.map(|h| {
+										format!(
+											"{} {}",
+											f.path, h.header
+										)
+									})
+									.collect()
+							}
+						})
+						.collect::<Vec<_>>(),
+				)
+			}),
+			ApplyMode::Mailbox => sync::apply_mailbox(CWD, path)
+				.and_then(|state| {
+					Ok(match state {
+						ApplyMailboxState::Finished => {
+							strings::apply_mailbox_done_msg()
+						}
+						ApplyMailboxState::Stopped => {
+							let progress =
+								sync::apply_mailbox_progress(CWD)?;
+							strings::apply_mailbox_stopped_msg(
+								&self.key_config,
+								progress.current,
+								progress.total,
+								&progress.subject,
+								&progress
+									.patch_file
+									.to_string_lossy(),
+							)
+						}
+					})
+				}),
+		};
+
+		self.hide();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		match msg {
+			Ok(msg) => {
+				self.input.clear();
+				self.preview = None;
+				self.queue.push(InternalEvent::ShowInfoMsg(msg));
+			}
+			Err(e) => {
+				log::error!("apply patch: {}", e);
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("apply patch error:\n{}", e),
+				));
+			}
+		}
+	}
+
+	fn preview_text(&self) -> Vec<Spans<'_>> {
+		match &self.preview {
+			None => vec![Spans::from(Span::styled(
+				strings::apply_patch_preview_hint(&self.key_config),
+				self.theme.text(false, false),
+			))],
+			Some(Preview::Error(e)) => vec![Spans::from(
+				Span::styled(e.clone(), self.theme.text_danger()),
+			)],
+			Some(Preview::Hunks(files)) => {
+				let mut txt = Vec::new();
+				for file in files {
+					txt.push(Spans::from(Span::styled(
+						file.path.clone(),
+						self.theme.text(file.applies, true),
+					)));
+
+					for hunk in &file.hunks {
+						let style = if hunk.applies {
+							self.theme.text(true, false)
+						} else {
+							self.theme.text_danger()
+						};
+
+						txt.push(Spans::from(Span::styled(
+							format!(
+								"  {} (+{} -{}){}",
+								hunk.header,
+								hunk.added,
+								hunk.removed,
+								if hunk.applies {
+									""
+								} else {
+									" [rejected]"
+								}
+							),
+							style,
+						)));
+					}
+				}
+				txt
+			}
+			Some(Preview::Mails(mails)) => mails
+				.iter()
+				.enumerate()
+				.map(|(idx, mail)| {
+					Spans::from(vec![
+						Span::styled(
+							format!("{:>3} ", idx + 1),
+							self.theme.text(true, false),
+						),
+						Span::styled(
+							mail.subject.clone(),
+							self.theme.text(true, true),
+						),
+						Span::styled(
+							format!(" <{}>", mail.author),
+							self.theme.commit_author(false),
+						),
+					])
+				})
+				.collect(),
+		}
+	}
+
+	fn draw_preview<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+		let txt = self.preview_text();
+
+		let height = usize::from(r.height);
+		let top = self.scroll.update_no_selection(txt.len(), height);
+
+		f.render_widget(
+			Paragraph::new(
+				txt.into_iter()
+					.skip(top)
+					.take(height)
+					.collect::<Vec<_>>(),
+			),
+			r,
+		);
+
+		self.scroll.draw(f, r, &self.theme);
+	}
+}
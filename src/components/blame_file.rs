@@ -6,13 +6,16 @@ use crate::{
 	components::{utils::string_width_align, ScrollType},
 	keys::SharedKeyConfig,
 	queue::{InternalEvent, Queue},
-	strings,
+	strings, try_or_popup,
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{BlameHunk, CommitId, FileBlame},
-	AsyncBlame, AsyncGitNotification, BlameParams,
+	sync::{
+		self, BlameDetection, BlameHunk, BlameOptions, BlameProgress,
+		CommitDetails, CommitId, FileBlame,
+	},
+	AsyncBlame, AsyncGitNotification, BlameParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -21,8 +24,11 @@ use tui::{
 	backend::Backend,
 	layout::{Constraint, Rect},
 	symbols::line::VERTICAL,
-	text::Span,
-	widgets::{Block, Borders, Cell, Clear, Row, Table, TableState},
+	text::{Span, Spans},
+	widgets::{
+		Block, Borders, Cell, Clear, Paragraph, Row, Table,
+		TableState, Wrap,
+	},
 	Frame,
 };
 
@@ -32,8 +38,18 @@ pub struct BlameFileComponent {
 	queue: Queue,
 	async_blame: AsyncBlame,
 	visible: bool,
-	file_path: Option<String>,
+	params: Option<BlameParams>,
+	/// kept across files so the chosen mode sticks
+	options: BlameOptions,
+	/// previously blamed revisions and their selected line
+	stack: Vec<(BlameParams, usize)>,
 	file_blame: Option<FileBlame>,
+	/// set while `file_blame` is still partial
+	progress: Option<BlameProgress>,
+	heatmap: bool,
+	show_message: bool,
+	/// details of the selected line's commit while `show_message`
+	message: Option<(CommitId, CommitDetails)>,
 	table_state: std::cell::Cell<TableState>,
 	key_config: SharedKeyConfig,
 	current_height: std::cell::Cell<usize>,
@@ -43,12 +59,45 @@ static NO_COMMIT_ID: &str = "0000000";
 static NO_AUTHOR: &str = "<no author>";
 static MIN_AUTHOR_WIDTH: usize = 3;
 static MAX_AUTHOR_WIDTH: usize = 20;
+static MIN_WIDTH_FOR_SUMMARY: usize = 100;
+static MAX_SUMMARY_WIDTH: usize = 40;
 
 fn get_author_width(width: usize) -> usize {
 	(width.saturating_sub(19) / 3)
 		.clamp(MIN_AUTHOR_WIDTH, MAX_AUTHOR_WIDTH)
 }
 
+fn get_summary_width(width: usize) -> usize {
+	if width < MIN_WIDTH_FOR_SUMMARY {
+		0
+	} else {
+		(width / 4).min(MAX_SUMMARY_WIDTH)
+	}
+}
+
+/// oldest and newest commit time of all blamed lines
+fn get_age_range(file_blame: &FileBlame) -> (i64, i64) {
+	file_blame
+		.lines
+		.iter()
+		.filter_map(|(hunk, _)| hunk.as_ref().map(|hunk| hunk.time))
+		.fold((i64::MAX, i64::MIN), |(oldest, newest), time| {
+			(oldest.min(time), newest.max(time))
+		})
+}
+
+/// 0 for the oldest up to 100 for the newest change
+fn get_heat(time: i64, oldest: i64, newest: i64) -> usize {
+	let span = newest.saturating_sub(oldest);
+	if span <= 0 {
+		return 100;
+	}
+
+	(time.saturating_sub(oldest).saturating_mul(100) / span)
+		.try_into()
+		.unwrap_or_default()
+}
+
 const fn number_of_digits(number: usize) -> usize {
 	let mut rest = number;
 	let mut result = 0;
@@ -79,6 +128,11 @@ impl DrawableComponent for BlameFileComponent {
 				Constraint::Length(10),
 				// commit author
 				Constraint::Length(author_width.try_into()?),
+				// commit summary
+				Constraint::Length(
+					get_summary_width(area.width.into())
+						.try_into()?,
+				),
 				// line number and vertical bar
 				Constraint::Length(
 					(self.get_line_number_width().saturating_add(1))
@@ -135,6 +189,8 @@ impl DrawableComponent for BlameFileComponent {
 
 			self.table_state.set(table_state);
 			self.current_height.set(area.height.into());
+
+			self.draw_message(f, area);
 		}
 
 		Ok(())
@@ -174,6 +230,38 @@ impl Component for BlameFileComponent {
 				)
 				.order(1),
 			);
+			out.push(CommandInfo::new(
+				strings::commands::blame_parent(&self.key_config),
+				self.selected_commit().is_some(),
+				self.file_blame.is_some(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::blame_back(&self.key_config),
+				!self.stack.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::blame_heatmap(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::blame_message(&self.key_config),
+				true,
+				self.file_blame.is_some(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::blame_detection(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::blame_ignore_revs(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
 		}
 
 		visibility_blocking(self)
@@ -203,6 +291,31 @@ impl Component for BlameFileComponent {
 					self.move_selection(ScrollType::PageDown);
 				} else if key == self.key_config.keys.page_up {
 					self.move_selection(ScrollType::PageUp);
+				} else if key == self.key_config.keys.blame_parent {
+					try_or_popup!(
+						self,
+						"blame parent error:",
+						self.blame_parent()
+					);
+				} else if key == self.key_config.keys.blame_back {
+					self.blame_back()?;
+				} else if key == self.key_config.keys.blame_heatmap {
+					self.heatmap = !self.heatmap;
+				} else if key == self.key_config.keys.blame_message {
+					self.show_message = !self.show_message;
+				} else if key == self.key_config.keys.blame_detection
+				{
+					self.set_options(BlameOptions {
+						detection: self.options.detection.next(),
+						..self.options
+					})?;
+				} else if key
+					== self.key_config.keys.blame_ignore_revs
+				{
+					self.set_options(BlameOptions {
+						ignore_revs: !self.options.ignore_revs,
+						..self.options
+					})?;
 				} else if key == self.key_config.keys.focus_right {
 					self.hide();
 
@@ -219,6 +332,8 @@ impl Component for BlameFileComponent {
 					);
 				}
 
+				self.update_message()?;
+
 				return Ok(EventState::Consumed);
 			}
 		}
@@ -232,6 +347,10 @@ impl Component for BlameFileComponent {
 
 	fn hide(&mut self) {
 		self.visible = false;
+
+		if let Err(e) = self.async_blame.cancel() {
+			log::error!("cancel blame: {}", e);
+		}
 	}
 
 	fn show(&mut self) -> Result<()> {
@@ -256,22 +375,100 @@ impl BlameFileComponent {
 			async_blame: AsyncBlame::new(sender),
 			queue: queue.clone(),
 			visible: false,
-			file_path: None,
+			params: None,
+			options: BlameOptions::default(),
+			stack: Vec::new(),
 			file_blame: None,
+			progress: None,
+			heatmap: false,
+			show_message: false,
+			message: None,
 			table_state: std::cell::Cell::new(TableState::default()),
 			key_config,
 			current_height: std::cell::Cell::new(0),
 		}
 	}
 
-	///
-	pub fn open(&mut self, file_path: &str) -> Result<()> {
-		self.file_path = Some(file_path.into());
-		self.file_blame = None;
-		self.table_state.get_mut().select(Some(0));
+	/// blames `file_path` at `commit_id` (HEAD if `None`)
+	pub fn open(
+		&mut self,
+		file_path: &str,
+		commit_id: Option<CommitId>,
+	) -> Result<()> {
+		self.stack.clear();
 		self.show()?;
 
-		self.update()?;
+		self.load(
+			BlameParams {
+				file_path: file_path.into(),
+				commit_id,
+				options: self.options,
+			},
+			0,
+		)
+	}
+
+	fn load(
+		&mut self,
+		params: BlameParams,
+		line: usize,
+	) -> Result<()> {
+		self.params = Some(params);
+		self.file_blame = None;
+		self.progress = None;
+		self.table_state.get_mut().select(Some(line));
+
+		self.update()
+	}
+
+	/// blames the parent of the commit that changed the selected line
+	fn blame_parent(&mut self) -> Result<()> {
+		let line = self.table_state.get_mut().selected().unwrap_or(0);
+
+		if let (Some(params), Some(file_blame)) =
+			(self.params.clone(), self.file_blame.as_ref())
+		{
+			match sync::blame_origin_in_parent(CWD, file_blame, line)?
+			{
+				Some(origin) => {
+					self.stack.push((params, line));
+					self.load(
+						BlameParams {
+							file_path: origin.path,
+							commit_id: Some(origin.commit_id),
+							options: self.options,
+						},
+						origin.line,
+					)?;
+				}
+				None => {
+					self.queue.push(InternalEvent::ShowInfoMsg(
+						strings::blame_no_parent(),
+					));
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// re-blames the current revision with `options`
+	fn set_options(&mut self, options: BlameOptions) -> Result<()> {
+		self.options = options;
+
+		if let Some(params) = self.params.clone() {
+			let line =
+				self.table_state.get_mut().selected().unwrap_or(0);
+			self.load(BlameParams { options, ..params }, line)?;
+		}
+
+		Ok(())
+	}
+
+	fn blame_back(&mut self) -> Result<()> {
+		if let Some((params, line)) = self.stack.pop() {
+			self.load(params, line)?;
+		}
 
 		Ok(())
 	}
@@ -295,11 +492,7 @@ impl BlameFileComponent {
 
 	fn update(&mut self) -> Result<()> {
 		if self.is_visible() {
-			if let Some(file_path) = &self.file_path {
-				let blame_params = BlameParams {
-					file_path: file_path.into(),
-				};
-
+			if let Some(blame_params) = self.params.clone() {
 				if let Some((
 					previous_blame_params,
 					last_file_blame,
@@ -307,35 +500,184 @@ impl BlameFileComponent {
 				{
 					if previous_blame_params == blame_params {
 						self.file_blame = Some(last_file_blame);
+						self.progress = None;
+						self.clamp_selection();
 
-						return Ok(());
+						return self.update_message();
 					}
 				}
 
 				self.async_blame.request(blame_params)?;
+
+				if let Some((partial, progress)) =
+					self.async_blame.partial()?
+				{
+					self.file_blame = Some(partial);
+					self.progress = Some(progress);
+					self.clamp_selection();
+					self.update_message()?;
+				}
 			}
 		}
 
 		Ok(())
 	}
 
+	/// fetches the details of the selected line's commit if it changed
+	fn update_message(&mut self) -> Result<()> {
+		let id = if self.show_message {
+			self.selected_commit()
+		} else {
+			None
+		};
+
+		if self.message.as_ref().map(|(message_id, _)| *message_id)
+			!= id
+		{
+			self.message = id
+				.map(|id| {
+					sync::get_commit_details(CWD, id)
+						.map(|details| (id, details))
+				})
+				.transpose()?;
+		}
+
+		Ok(())
+	}
+
+	fn message_text(
+		&self,
+		details: &CommitDetails,
+	) -> Vec<Spans<'static>> {
+		let mut text = vec![
+			Spans::from(Span::styled(
+				details.hash.clone(),
+				self.theme.commit_hash(false),
+			)),
+			Spans::from(Span::styled(
+				format!(
+					"{} <{}>",
+					details.author.name, details.author.email
+				),
+				self.theme.commit_author(false),
+			)),
+			Spans::from(Span::styled(
+				utils::time_to_string(details.author.time, false),
+				self.theme.commit_time(false),
+			)),
+		];
+
+		if let Some(message) = &details.message {
+			text.push(Spans::from(""));
+			text.push(Spans::from(Span::styled(
+				message.subject.clone(),
+				self.theme.text(true, false),
+			)));
+
+			if let Some(body) = &message.body {
+				text.push(Spans::from(""));
+				text.extend(body.lines().map(|line| {
+					Spans::from(Span::styled(
+						line.to_string(),
+						self.theme.text(true, false),
+					))
+				}));
+			}
+		}
+
+		text
+	}
+
+	/// full message of the selected line's commit at the bottom
+	fn draw_message<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		if let Some((_, details)) = &self.message {
+			let text = self.message_text(details);
+
+			let height = (text.len() + 2)
+				.min((area.height / 2).into())
+				.try_into()
+				.unwrap_or_default();
+			let message_area = Rect::new(
+				area.x + 1,
+				area.bottom().saturating_sub(height + 1),
+				area.width.saturating_sub(2),
+				height,
+			);
+
+			f.render_widget(Clear, message_area);
+			f.render_widget(
+				Paragraph::new(text)
+					.block(
+						Block::default()
+							.borders(Borders::ALL)
+							.title(Span::styled(
+								strings::blame_message_title(),
+								self.theme.title(true),
+							))
+							.border_style(self.theme.block(true)),
+					)
+					.wrap(Wrap { trim: false }),
+				message_area,
+			);
+		}
+	}
+
+	/// the line picked in `load` might not exist
+	fn clamp_selection(&mut self) {
+		let max = self.get_max_line_number();
+		let table_state = self.table_state.get_mut();
+		table_state
+			.select(table_state.selected().map(|l| l.min(max)));
+	}
+
+	/// non-default blame options, shown in the title
+	fn options_label(&self) -> String {
+		let mut label = Vec::new();
+		match self.options.detection {
+			BlameDetection::None => (),
+			BlameDetection::Moves => label.push("detect moves"),
+			BlameDetection::Copies => label.push("detect copies"),
+		}
+		if !self.options.ignore_revs {
+			label.push("all revs");
+		}
+
+		if label.is_empty() {
+			String::new()
+		} else {
+			format!(" [{}]", label.join(", "))
+		}
+	}
+
 	///
 	fn get_title(&self) -> String {
+		let title = format!("{}{}", self.title, self.options_label());
+
 		match (
 			self.any_work_pending(),
-			self.file_path.as_ref(),
+			self.params.as_ref().map(|params| &params.file_path),
 			self.file_blame.as_ref(),
 		) {
-			(true, Some(file_path), _) => {
-				format!(
-					"{} -- {} -- <calculating.. (who is to blame?)>",
-					self.title, file_path
-				)
-			}
+			(true, Some(file_path), _) => self.progress.map_or_else(
+				|| {
+					format!(
+						"{} -- {} -- <calculating.. (who is to blame?)>",
+						title, file_path
+					)
+				},
+				|progress| {
+					format!(
+						"{} -- {} -- <calculating.. {}% (who is to blame?)>",
+						title,
+						file_path,
+						progress.percent()
+					)
+				},
+			),
 			(false, Some(file_path), Some(file_blame)) => {
 				format!(
 					"{} -- {} -- {}",
-					self.title,
+					title,
 					file_path,
 					file_blame.commit_id.get_short_string()
 				)
@@ -343,10 +685,10 @@ impl BlameFileComponent {
 			(false, Some(file_path), None) => {
 				format!(
 					"{} -- {} -- <no blame available>",
-					self.title, file_path
+					title, file_path
 				)
 			}
-			_ => format!("{} -- <no blame available>", self.title),
+			_ => format!("{} -- <no blame available>", title),
 		}
 	}
 
@@ -355,6 +697,9 @@ impl BlameFileComponent {
 		self.file_blame
 			.as_ref()
 			.map_or_else(Vec::new, |file_blame| {
+				let age_range =
+					self.heatmap.then(|| get_age_range(file_blame));
+
 				file_blame
 					.lines
 					.iter()
@@ -365,6 +710,7 @@ impl BlameFileComponent {
 							i,
 							(blame_hunk.as_ref(), line.as_ref()),
 							file_blame,
+							age_range,
 						)
 					})
 					.collect()
@@ -377,6 +723,7 @@ impl BlameFileComponent {
 		line_number: usize,
 		hunk_and_line: (Option<&BlameHunk>, &str),
 		file_blame: &FileBlame,
+		age_range: Option<(i64, i64)>,
 	) -> Row {
 		let (hunk_for_line, line) = hunk_and_line;
 
@@ -395,9 +742,18 @@ impl BlameFileComponent {
 		};
 
 		let mut cells = if show_metadata {
-			self.get_metadata_for_line_blame(width, hunk_for_line)
+			self.get_metadata_for_line_blame(
+				width,
+				hunk_for_line,
+				age_range,
+			)
 		} else {
-			vec![Cell::from(""), Cell::from(""), Cell::from("")]
+			vec![
+				Cell::from(""),
+				Cell::from(""),
+				Cell::from(""),
+				Cell::from(""),
+			]
 		};
 
 		let line_number_width = self.get_line_number_width();
@@ -422,6 +778,7 @@ impl BlameFileComponent {
 		&self,
 		width: usize,
 		blame_hunk: Option<&BlameHunk>,
+		age_range: Option<(i64, i64)>,
 	) -> Vec<Cell> {
 		let commit_hash = blame_hunk.map_or_else(
 			|| NO_COMMIT_ID.into(),
@@ -452,12 +809,30 @@ impl BlameFileComponent {
 			})
 			.unwrap_or(false);
 
+		let summary = blame_hunk.map_or_else(String::new, |hunk| {
+			string_width_align(
+				&hunk.summary,
+				get_summary_width(width),
+			)
+		});
+
+		let heat = age_range.and_then(|(oldest, newest)| {
+			blame_hunk.map(|hunk| get_heat(hunk.time, oldest, newest))
+		});
+		let style = |default| {
+			heat.map_or(default, |heat| self.theme.blame_heat(heat))
+		};
+
 		vec![
 			Cell::from(commit_hash).style(
 				self.theme.commit_hash_in_blame(is_blamed_commit),
 			),
-			Cell::from(time).style(self.theme.commit_time(false)),
-			Cell::from(author).style(self.theme.commit_author(false)),
+			Cell::from(time)
+				.style(style(self.theme.commit_time(false))),
+			Cell::from(author)
+				.style(style(self.theme.commit_author(false))),
+			Cell::from(summary)
+				.style(style(self.theme.text(true, false))),
 		]
 	}
 
@@ -522,3 +897,37 @@ impl BlameFileComponent {
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_toggle_heatmap_and_message() {
+		let (sender, _receiver) = crossbeam_channel::unbounded();
+		let key_config = SharedKeyConfig::default();
+		let mut comp = BlameFileComponent::new(
+			&Queue::new(),
+			&sender,
+			"",
+			SharedTheme::default(),
+			key_config.clone(),
+		);
+		comp.show().unwrap();
+
+		comp.event(Event::Key(key_config.keys.blame_heatmap))
+			.unwrap();
+		comp.event(Event::Key(key_config.keys.blame_message))
+			.unwrap();
+		assert!(comp.heatmap);
+		assert!(comp.show_message);
+
+		comp.event(Event::Key(key_config.keys.blame_heatmap))
+			.unwrap();
+		comp.event(Event::Key(key_config.keys.blame_message))
+			.unwrap();
+		assert!(!comp.heatmap);
+		assert!(!comp.show_message);
+		assert!(comp.message.is_none());
+	}
+}
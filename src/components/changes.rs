@@ -1,7 +1,8 @@
 use super::{
 	filetree::FileTreeComponent,
 	utils::filetree::{FileTreeItem, FileTreeItemKind},
-	CommandBlocking, DrawableComponent, SharedOptions,
+	CommandBlocking, DrawableComponent, MatchingAction,
+	SharedOptions,
 };
 use crate::{
 	components::{CommandInfo, Component, EventState},
@@ -12,7 +13,7 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{sync, StatusItem, StatusItemType, CWD};
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyEvent};
 use std::path::Path;
 use tui::{backend::Backend, layout::Rect, Frame};
 
@@ -87,6 +88,16 @@ impl ChangesComponent {
 						StatusItemType::Deleted => {
 							sync::stage_addremoved(CWD, path)?;
 						}
+						StatusItemType::Renamed => {
+							sync::stage_add_file(CWD, path)?;
+							// stage both sides of the rename
+							if let Some(old_path) = &i.old_path {
+								sync::stage_addremoved(
+									CWD,
+									Path::new(old_path),
+								)?;
+							}
+						}
 						_ => sync::stage_add_file(CWD, path)?,
 					};
 
@@ -113,6 +124,14 @@ impl ChangesComponent {
 
 			let path = tree_item.info.full_path.as_str();
 			sync::reset_stage(CWD, path)?;
+			if let FileTreeItemKind::File(StatusItem {
+				status: StatusItemType::Renamed,
+				old_path: Some(old_path),
+				..
+			}) = &tree_item.kind
+			{
+				sync::reset_stage(CWD, old_path)?;
+			}
 			return Ok(true);
 		}
 
@@ -139,12 +158,19 @@ impl ChangesComponent {
 
 	fn dispatch_reset_workdir(&mut self) -> bool {
 		if let Some(tree_item) = self.selection() {
-			let is_folder =
-				matches!(tree_item.kind, FileTreeItemKind::Path(_));
+			let (is_folder, old_path) = match tree_item.kind {
+				FileTreeItemKind::Path(_) => (true, None),
+				FileTreeItemKind::File(item) => {
+					let renamed =
+						item.status == StatusItemType::Renamed;
+					(false, item.old_path.filter(|_| renamed))
+				}
+			};
 			self.queue.push(InternalEvent::ConfirmAction(
 				Action::Reset(ResetItem {
 					path: tree_item.info.full_path,
 					is_folder,
+					old_path,
 				}),
 			));
 
@@ -153,27 +179,98 @@ impl ChangesComponent {
 		false
 	}
 
+	/// sets assume-unchanged or skip-worktree of the selected file
+	fn set_index_flag(&self, assume_unchanged: bool) -> Result<bool> {
+		if let Some(FileTreeItemKind::File(item)) =
+			self.selection().map(|item| item.kind)
+		{
+			if assume_unchanged {
+				sync::set_assume_unchanged(CWD, &item.path, true)?;
+			} else {
+				sync::set_skip_worktree(CWD, &item.path, true)?;
+			}
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+			return Ok(true);
+		}
+
+		Ok(false)
+	}
+
 	fn add_to_ignore(&mut self) -> bool {
 		if let Some(tree_item) = self.selection() {
-			if let Err(e) =
-				sync::add_to_ignore(CWD, &tree_item.info.full_path)
-			{
-				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!(
-						"ignore error:\n{}\nfile:\n{:?}",
-						e, tree_item.info.full_path
-					),
-				));
-			} else {
-				self.queue
-					.push(InternalEvent::Update(NeedsUpdate::ALL));
+			self.queue.push(InternalEvent::OpenIgnoreRule(
+				tree_item.info.full_path,
+			));
 
-				return true;
-			}
+			return true;
+		}
+
+		false
+	}
+
+	fn toggle_executable(&self) -> Result<bool> {
+		if let Some(FileTreeItemKind::File(item)) =
+			self.selection().map(|item| item.kind)
+		{
+			sync::toggle_executable(CWD, &item.path)?;
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+			return Ok(true);
+		}
+
+		Ok(false)
+	}
+
+	/// mode/path changes of the selected index entry
+	fn index_entry_event(&self, e: KeyEvent) -> EventState {
+		if e == self.key_config.keys.status_toggle_executable {
+			try_or_popup!(
+				self,
+				"toggle executable error:",
+				self.toggle_executable()
+			);
+			EventState::Consumed
+		} else if e == self.key_config.keys.move_file {
+			self.move_file().into()
+		} else if e == self.key_config.keys.remove_cached {
+			try_or_popup!(
+				self,
+				"untrack error:",
+				self.remove_cached()
+			);
+			EventState::Consumed
+		} else {
+			EventState::NotConsumed
+		}
+	}
+
+	fn move_file(&self) -> bool {
+		if let Some(tree_item) = self.selection() {
+			self.queue.push(InternalEvent::OpenMoveFile(
+				tree_item.info.full_path,
+			));
+
+			return true;
 		}
 
 		false
 	}
+
+	/// removes the selected file or folder from the index only
+	fn remove_cached(&self) -> Result<bool> {
+		if let Some(tree_item) = self.selection() {
+			sync::remove_from_index(CWD, &tree_item.info.full_path)?;
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+			return Ok(true);
+		}
+
+		Ok(false)
+	}
 }
 
 impl DrawableComponent for ChangesComponent {
@@ -214,6 +311,26 @@ impl Component for ChangesComponent {
 				true,
 				some_selection && self.focused(),
 			));
+			out.push(CommandInfo::new(
+				strings::commands::stage_matching(&self.key_config),
+				true,
+				some_selection && self.focused(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::reset_matching(&self.key_config),
+				true,
+				some_selection && self.focused(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::assume_unchanged(&self.key_config),
+				self.is_file_seleted(),
+				some_selection && self.focused(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::skip_worktree(&self.key_config),
+				self.is_file_seleted(),
+				some_selection && self.focused(),
+			));
 			out.push(CommandInfo::new(
 				strings::commands::ignore_item(&self.key_config),
 				true,
@@ -232,6 +349,22 @@ impl Component for ChangesComponent {
 			));
 		}
 
+		out.push(CommandInfo::new(
+			strings::commands::toggle_executable(&self.key_config),
+			self.is_file_seleted(),
+			some_selection && self.focused(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::move_file(&self.key_config),
+			true,
+			some_selection && self.focused(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::remove_cached(&self.key_config),
+			true,
+			some_selection && self.focused(),
+		));
+
 		CommandBlocking::PassingOn
 	}
 
@@ -273,13 +406,55 @@ impl Component for ChangesComponent {
 					&& self.is_working_dir
 				{
 					Ok(self.dispatch_reset_workdir().into())
+				} else if (e
+					== self.key_config.keys.status_stage_matching
+					|| e == self
+						.key_config
+						.keys
+						.status_reset_matching)
+					&& self.is_working_dir
+					&& !self.is_empty()
+				{
+					let action = if e
+						== self.key_config.keys.status_stage_matching
+					{
+						MatchingAction::Stage
+					} else {
+						MatchingAction::Discard
+					};
+					self.queue.push(
+						InternalEvent::OpenStageMatching(
+							action,
+							self.files.files(),
+						),
+					);
+					Ok(EventState::Consumed)
+				} else if (e
+					== self.key_config.keys.status_assume_unchanged
+					|| e == self.key_config.keys.status_skip_worktree)
+					&& self.is_working_dir
+					&& !self.is_empty()
+				{
+					let assume_unchanged = e
+						== self
+							.key_config
+							.keys
+							.status_assume_unchanged;
+					try_or_popup!(
+						self,
+						"index flags error:",
+						self.set_index_flag(assume_unchanged)
+					);
+					Ok(EventState::Consumed)
 				} else if e == self.key_config.keys.status_ignore_file
 					&& self.is_working_dir
 					&& !self.is_empty()
 				{
 					Ok(self.add_to_ignore().into())
-				} else {
+				} else if self.is_empty() {
 					Ok(EventState::NotConsumed)
+				} else {
+					Ok(self.index_entry_event(e))
 				};
 			}
 		}
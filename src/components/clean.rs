@@ -0,0 +1,188 @@
+use super::{
+	filetree::FileTreeComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{Action, InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, widgets::Clear, Frame};
+
+/// checklist of untracked (and ignored) files to delete
+pub struct CleanComponent {
+	files: FileTreeComponent,
+	include_ignored: bool,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for CleanComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(60, 70, f.size());
+
+			f.render_widget(Clear, area);
+			self.files.draw(f, area)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CleanComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::clean_confirm(&self.key_config),
+				!self.files.checked_files().is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::clean_toggle_item(
+					&self.key_config,
+				),
+				!self.files.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::clean_toggle_ignored(
+					&self.key_config,
+					self.include_ignored,
+				),
+				true,
+				true,
+			));
+			self.files.commands(out, force_all);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.exit_popup {
+					self.hide();
+				} else if e == self.key_config.keys.enter {
+					self.confirm();
+				} else if e == self.key_config.keys.clean_toggle_item
+				{
+					self.files.toggle_checked();
+				} else if e
+					== self.key_config.keys.clean_toggle_ignored
+				{
+					self.include_ignored = !self.include_ignored;
+					self.update()?;
+				} else {
+					self.files.event(ev)?;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+impl CleanComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut files = FileTreeComponent::new(
+			&strings::clean_popup_title(&key_config, false),
+			true,
+			None,
+			theme,
+			key_config.clone(),
+		);
+		files.set_checklist(true);
+
+		Self {
+			files,
+			include_ignored: false,
+			visible: false,
+			queue,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.include_ignored = false;
+		self.update()?;
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn update(&mut self) -> Result<()> {
+		let items =
+			sync::get_clean_candidates(CWD, self.include_ignored)?;
+
+		self.files.set_title(strings::clean_popup_title(
+			&self.key_config,
+			self.include_ignored,
+		));
+		self.files.set_checklist(true);
+		self.files.update(&items)?;
+
+		Ok(())
+	}
+
+	fn confirm(&mut self) {
+		let paths = self
+			.files
+			.checked_files()
+			.into_iter()
+			.map(|item| item.path)
+			.collect::<Vec<_>>();
+
+		if !paths.is_empty() {
+			self.hide();
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::Clean(paths),
+			));
+		}
+	}
+}
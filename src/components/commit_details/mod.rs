@@ -12,8 +12,8 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::CommitTags, AsyncCommitFiles, AsyncGitNotification,
-	CommitFilesParams,
+	sync::{self, CommitId, CommitTags},
+	AsyncCommitFiles, AsyncGitNotification, CommitFilesParams, CWD,
 };
 use compare_details::CompareDetailsComponent;
 use crossbeam_channel::Sender;
@@ -91,6 +91,7 @@ impl CommitDetailsComponent {
 		}
 
 		self.commit = params;
+		self.file_tree.set_revision(params.and_then(Self::revision));
 
 		if let Some(id) = params {
 			if let Some(other) = id.other {
@@ -120,6 +121,17 @@ impl CommitDetailsComponent {
 		Ok(())
 	}
 
+	/// the (newer) commit the shown files are taken from
+	fn revision(params: CommitFilesParams) -> Option<CommitId> {
+		params.other.map_or(Some(params.id), |other| {
+			sync::get_commits_info(CWD, &[params.id, other], 0)
+				.ok()?
+				.into_iter()
+				.max_by_key(|info| info.time)
+				.map(|info| info.id)
+		})
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.git_commit_files.is_pending()
@@ -1,7 +1,7 @@
 use super::{
 	command_pump, event_pump, visibility_blocking, CommandBlocking,
 	CommandInfo, CommitDetailsComponent, Component, DiffComponent,
-	DrawableComponent, EventState,
+	DrawableComponent, EventState, SharedOptions,
 };
 use crate::{
 	accessors, keys::SharedKeyConfig, queue::Queue, strings,
@@ -9,7 +9,7 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, diff::DiffOptions, CommitId},
+	sync::{self, CommitId},
 	AsyncDiff, AsyncGitNotification, CommitFilesParams, DiffParams,
 	DiffType, CWD,
 };
@@ -28,6 +28,7 @@ pub struct CompareCommitsComponent {
 	details: CommitDetailsComponent,
 	git_diff: AsyncDiff,
 	visible: bool,
+	options: SharedOptions,
 	key_config: SharedKeyConfig,
 }
 
@@ -160,6 +161,7 @@ impl CompareCommitsComponent {
 		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			details: CommitDetailsComponent::new(
@@ -177,6 +179,7 @@ impl CompareCommitsComponent {
 			commit_ids: None,
 			git_diff: AsyncDiff::new(sender),
 			visible: false,
+			options,
 			key_config,
 		}
 	}
@@ -226,16 +229,17 @@ impl CompareCommitsComponent {
 				if let Some(f) = self.details.files().selection_file()
 				{
 					let diff_params = DiffParams {
-						path: f.path.clone(),
+						path: f.path,
+						old_path: f.old_path,
 						diff_type: DiffType::Commits(ids),
-						options: DiffOptions::default(),
+						options: self.options.borrow().diff,
 					};
 
 					if let Some((params, last)) =
 						self.git_diff.last()?
 					{
 						if params == diff_params {
-							self.diff.update(f.path, false, last);
+							self.diff.update(params, last);
 							return Ok(());
 						}
 					}
@@ -1,6 +1,6 @@
 use super::{
 	utils::scroll_vertical::VerticalScroll, CommandBlocking,
-	Direction, DrawableComponent, ScrollType,
+	Direction, DrawableComponent, ScrollType, TextInputComponent,
 };
 use crate::{
 	components::{CommandInfo, Component, EventState},
@@ -13,15 +13,23 @@ use crate::{
 use anyhow::Result;
 use asyncgit::{
 	hash,
-	sync::{self, diff::DiffLinePosition},
-	DiffLine, DiffLineType, FileDiff, CWD,
+	sync::{
+		self,
+		diff::{DiffLinePosition, Hunk},
+	},
+	DiffLine, DiffLineType, DiffParams, DiffType, FileDiff, CWD,
 };
 use bytesize::ByteSize;
-use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use crossterm::event::{Event, KeyEvent};
+use std::{
+	borrow::Cow, cell::Cell, cmp, collections::HashSet, ops::Range,
+	path::Path,
+};
 use tui::{
 	backend::Backend,
-	layout::Rect,
+	layout::{
+		Constraint, Direction as LayoutDirection, Layout, Rect,
+	},
 	symbols,
 	text::{Span, Spans},
 	widgets::{Block, Borders, Paragraph},
@@ -33,6 +41,7 @@ struct Current {
 	path: String,
 	is_stage: bool,
 	hash: u64,
+	params: Option<DiffParams>,
 }
 
 ///
@@ -112,6 +121,14 @@ pub struct DiffComponent {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	is_immutable: bool,
+	search: TextInputComponent,
+	search_query: Option<String>,
+	search_matches: Vec<usize>,
+	/// new file line ranges of the hunks the user split
+	split_hunks: Vec<(u32, u32)>,
+	sub_hunks: HashSet<u64>,
+	/// header hashes of marked hunks if hunk marking is enabled
+	marked_hunks: Option<HashSet<u64>>,
 }
 
 impl DiffComponent {
@@ -122,6 +139,15 @@ impl DiffComponent {
 		key_config: SharedKeyConfig,
 		is_immutable: bool,
 	) -> Self {
+		let mut search = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			&strings::diff_search_msg(),
+			false,
+		);
+		search.embed();
+
 		Self {
 			focused: false,
 			queue,
@@ -135,6 +161,12 @@ impl DiffComponent {
 			theme,
 			key_config,
 			is_immutable,
+			search,
+			search_query: None,
+			search_matches: Vec::new(),
+			split_hunks: Vec::new(),
+			sub_hunks: HashSet::new(),
+			marked_hunks: None,
 		}
 	}
 	///
@@ -148,6 +180,27 @@ impl DiffComponent {
 	pub fn current(&self) -> (String, bool) {
 		(self.current.path.clone(), self.current.is_stage)
 	}
+	/// allows marking hunks to select them (e.g. for stashing)
+	pub fn set_hunk_marking(&mut self, enabled: bool) {
+		self.marked_hunks =
+			if enabled { Some(HashSet::new()) } else { None };
+	}
+	/// header hashes of the marked hunks of the current diff
+	pub fn marked_hunks(&self) -> HashSet<u64> {
+		self.marked_hunks.clone().unwrap_or_default()
+	}
+	///
+	pub fn set_marked_hunks(&mut self, hunks: HashSet<u64>) {
+		if self.marked_hunks.is_some() {
+			self.marked_hunks = Some(hunks);
+		}
+	}
+	/// header hash of the selected hunk (`None` for split sub-hunks)
+	pub fn selected_hunk_hash(&self) -> Option<u64> {
+		let hash = self.diff.as_ref()?.hunks[self.selected_hunk?]
+			.header_hash;
+		(!self.is_sub_hunk(hash)).then(|| hash)
+	}
 	///
 	pub fn clear(&mut self, pending: bool) {
 		self.current = Current::default();
@@ -156,28 +209,35 @@ impl DiffComponent {
 		self.selection = Selection::Single(0);
 		self.selected_hunk = None;
 		self.pending = pending;
+		self.search_matches.clear();
+		self.split_hunks.clear();
+		self.sub_hunks.clear();
 	}
 	///
-	pub fn update(
-		&mut self,
-		path: String,
-		is_stage: bool,
-		diff: FileDiff,
-	) {
+	pub fn update(&mut self, params: DiffParams, diff: FileDiff) {
 		self.pending = false;
 
 		let hash = hash(&diff);
 
 		if self.current.hash != hash {
-			let reset_selection = self.current.path != path;
+			let reset_selection = self.current.path != params.path;
+
+			if reset_selection
+				|| self.current.is_stage
+					!= (params.diff_type == DiffType::Stage)
+			{
+				self.split_hunks.clear();
+			}
 
 			self.current = Current {
-				path,
-				is_stage,
+				path: params.path.clone(),
+				is_stage: params.diff_type == DiffType::Stage,
 				hash,
+				params: Some(params),
 			};
 
-			self.diff = Some(diff);
+			self.diff = Some(self.apply_splits(diff));
+			self.update_search_matches();
 
 			if reset_selection {
 				self.scroll.reset();
@@ -193,6 +253,96 @@ impl DiffComponent {
 		}
 	}
 
+	/// replaces hunks the user split with their sub-hunks.
+	/// staging a sub-hunk changes the header of the remaining hunk,
+	/// so split hunks are found by the lines they cover instead
+	fn apply_splits(&mut self, mut diff: FileDiff) -> FileDiff {
+		let split_hunks = diff
+			.hunks
+			.iter()
+			.filter(|hunk| is_split(&self.split_hunks, hunk))
+			.filter_map(new_line_range)
+			.collect::<Vec<_>>();
+		self.split_hunks = split_hunks;
+
+		self.sub_hunks.clear();
+
+		if !self.split_hunks.is_empty() {
+			let mut hunks = Vec::with_capacity(diff.hunks.len());
+			for hunk in diff.hunks {
+				match hunk
+					.split()
+					.filter(|_| is_split(&self.split_hunks, &hunk))
+				{
+					Some(sub_hunks) => {
+						self.sub_hunks.extend(
+							sub_hunks.iter().map(|h| h.header_hash),
+						);
+						hunks.extend(sub_hunks);
+					}
+					None => hunks.push(hunk),
+				}
+			}
+
+			diff.lines = hunks.iter().map(|h| h.lines.len()).sum();
+			diff.hunks = hunks;
+		}
+
+		diff
+	}
+
+	fn can_split_hunk(&self) -> bool {
+		match (&self.diff, self.selected_hunk) {
+			(Some(diff), Some(hunk)) => {
+				!diff.untracked && diff.hunks[hunk].split().is_some()
+			}
+			_ => false,
+		}
+	}
+
+	fn split_hunk(&mut self) {
+		if !self.can_split_hunk() {
+			return;
+		}
+
+		if let (Some(diff), Some(hunk)) =
+			(self.diff.take(), self.selected_hunk)
+		{
+			let hunk_start = diff.hunks[..hunk]
+				.iter()
+				.map(|h| h.lines.len())
+				.sum();
+
+			self.split_hunks
+				.extend(new_line_range(&diff.hunks[hunk]));
+			self.diff = Some(self.apply_splits(diff));
+			self.update_search_matches();
+			self.update_selection(hunk_start);
+		}
+	}
+
+	/// changed lines of hunk `hunk`
+	fn hunk_changes(&self, hunk: usize) -> Vec<DiffLinePosition> {
+		self.diff
+			.as_ref()
+			.map(|diff| {
+				diff.hunks[hunk]
+					.lines
+					.iter()
+					.filter(|line| {
+						line.line_type == DiffLineType::Add
+							|| line.line_type == DiffLineType::Delete
+					})
+					.map(|line| line.position)
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	fn is_sub_hunk(&self, hash: u64) -> bool {
+		self.sub_hunks.contains(&hash)
+	}
+
 	fn move_selection(&mut self, move_type: ScrollType) {
 		if let Some(diff) = &self.diff {
 			let max = diff.lines.saturating_sub(1) as usize;
@@ -272,6 +422,314 @@ impl DiffComponent {
 		}
 	}
 
+	/// `true` while the search query is being typed
+	pub fn is_search_active(&self) -> bool {
+		self.search.is_visible()
+	}
+
+	fn open_search(&mut self) -> Result<()> {
+		self.search.set_text(String::new());
+		self.search.show()?;
+		self.set_search_query(None);
+
+		Ok(())
+	}
+
+	fn close_search(&mut self, keep_query: bool) {
+		self.search.hide();
+		if !keep_query {
+			self.set_search_query(None);
+		}
+	}
+
+	fn update_search(&mut self) {
+		let text = self.search.get_text();
+		let query = if text.is_empty() {
+			None
+		} else {
+			Some(text.to_string())
+		};
+
+		if query != self.search_query {
+			self.set_search_query(query);
+
+			// incremental: jump to first match from current line on
+			let top = self.selection.get_top();
+			if let Some(line) = self
+				.search_matches
+				.iter()
+				.find(|line| **line >= top)
+				.or_else(|| self.search_matches.first())
+				.copied()
+			{
+				self.update_selection(line);
+			}
+		}
+	}
+
+	fn set_search_query(&mut self, query: Option<String>) {
+		self.search_query = query;
+		self.update_search_matches();
+	}
+
+	fn update_search_matches(&mut self) {
+		self.search_matches.clear();
+
+		if let (Some(diff), Some(query)) =
+			(&self.diff, &self.search_query)
+		{
+			self.search_matches.extend(
+				diff.hunks
+					.iter()
+					.flat_map(|hunk| hunk.lines.iter())
+					.enumerate()
+					.filter(|(_, line)| {
+						!find_matches(&line.content, query).is_empty()
+					})
+					.map(|(i, _)| i),
+			);
+		}
+	}
+
+	fn jump_to_match(&mut self, forward: bool) {
+		let current = self.selection.get_top();
+
+		let line = if forward {
+			self.search_matches
+				.iter()
+				.find(|line| **line > current)
+				.or_else(|| self.search_matches.first())
+		} else {
+			self.search_matches
+				.iter()
+				.rev()
+				.find(|line| **line < current)
+				.or_else(|| self.search_matches.last())
+		};
+
+		if let Some(line) = line.copied() {
+			self.update_selection(line);
+		}
+	}
+
+	fn hunk_header_lines(&self) -> Vec<usize> {
+		self.diff
+			.as_ref()
+			.map(|diff| {
+				diff.hunks
+					.iter()
+					.scan(0_usize, |line_cursor, hunk| {
+						let header = *line_cursor;
+						*line_cursor += hunk.lines.len();
+						Some(header)
+					})
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	fn jump_to_hunk(&mut self, forward: bool) {
+		let current = self.selection.get_top();
+		let headers = self.hunk_header_lines();
+
+		let line = if forward {
+			headers.iter().find(|line| **line > current)
+		} else {
+			headers.iter().rev().find(|line| **line < current)
+		};
+
+		if let Some(line) = line.copied() {
+			self.update_selection(line);
+		}
+	}
+
+	fn search_status_text(&self) -> String {
+		let query = self.search_query.as_deref().unwrap_or_default();
+		let top = self.selection.get_top();
+
+		if self.search_matches.is_empty() {
+			format!(
+				"/{} ({})",
+				query,
+				strings::diff_search_no_match()
+			)
+		} else {
+			let current = self
+				.search_matches
+				.iter()
+				.position(|line| *line == top)
+				.map_or_else(
+					|| String::from("-"),
+					|i| (i + 1).to_string(),
+				);
+
+			format!(
+				"/{} ({}/{})",
+				query,
+				current,
+				self.search_matches.len()
+			)
+		}
+	}
+
+	fn draw_search<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		r: Rect,
+	) -> Result<()> {
+		if self.search.is_visible() {
+			self.search.draw(f, r)?;
+		} else {
+			f.render_widget(
+				Paragraph::new(Spans::from(Span::styled(
+					self.search_status_text(),
+					self.theme.text(true, false),
+				))),
+				r,
+			);
+		}
+
+		Ok(())
+	}
+
+	/// handles search and hunk jumping keys, returns true if consumed
+	fn navigation_event(&mut self, e: KeyEvent) -> Result<bool> {
+		if e == self.key_config.keys.diff_search
+			&& self.diff.is_some()
+		{
+			self.open_search()?;
+		} else if e == self.key_config.keys.diff_search_next
+			&& self.search_query.is_some()
+		{
+			self.jump_to_match(true);
+		} else if e == self.key_config.keys.diff_search_prev
+			&& self.search_query.is_some()
+		{
+			self.jump_to_match(false);
+		} else if e == self.key_config.keys.diff_hunk_next {
+			self.jump_to_hunk(true);
+		} else if e == self.key_config.keys.diff_hunk_prev {
+			self.jump_to_hunk(false);
+		} else if e == self.key_config.keys.exit_popup
+			&& self.search_query.is_some()
+		{
+			self.close_search(false);
+		} else {
+			return Ok(false);
+		}
+
+		Ok(true)
+	}
+
+	/// toggles the mark of the selected hunk, returns true if consumed
+	fn mark_hunk_event(&mut self, e: KeyEvent) -> bool {
+		if e != self.key_config.keys.diff_mark_hunk {
+			return false;
+		}
+
+		if let (Some(marked), Some(diff), Some(hunk)) = (
+			self.marked_hunks.as_mut(),
+			&self.diff,
+			self.selected_hunk,
+		) {
+			let hash = diff.hunks[hunk].header_hash;
+			if !self.sub_hunks.contains(&hash)
+				&& !marked.remove(&hash)
+			{
+				marked.insert(hash);
+			}
+
+			return true;
+		}
+
+		false
+	}
+
+	/// split/edit of the selected hunk
+	fn hunk_event(&mut self, e: KeyEvent) -> bool {
+		if self.is_immutable {
+			return false;
+		}
+
+		if e == self.key_config.keys.diff_split_hunk {
+			self.split_hunk();
+		} else if e == self.key_config.keys.diff_edit_hunk
+			&& !self.is_stage()
+		{
+			self.edit_hunk();
+		} else {
+			return false;
+		}
+
+		true
+	}
+
+	fn search_event(&mut self, ev: Event) -> Result<EventState> {
+		if let Event::Key(e) = ev {
+			if e == self.key_config.keys.enter {
+				self.close_search(true);
+			} else if e == self.key_config.keys.exit_popup {
+				self.close_search(false);
+			} else if self.search.event(ev)?.is_consumed() {
+				self.update_search();
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	const fn search_bar_visible(&self) -> bool {
+		self.search_query.is_some()
+	}
+
+	fn copy_patch(&self, hunk_only: bool) -> Result<()> {
+		if let Some(params) = &self.current.params {
+			let hunk = if hunk_only {
+				match (&self.diff, self.selected_hunk) {
+					(Some(diff), Some(hunk)) => {
+						Some(diff.hunks[hunk].header_hash)
+					}
+					_ => return Ok(()),
+				}
+			} else {
+				None
+			};
+
+			let file = params.path.clone();
+			let patch = match params.diff_type {
+				DiffType::Stage | DiffType::WorkDir => {
+					sync::get_diff_patch(
+						CWD,
+						&file,
+						params.diff_type == DiffType::Stage,
+						hunk,
+						Some(params.options),
+					)
+				}
+				DiffType::Commit(id) => sync::get_diff_commit_patch(
+					CWD,
+					id,
+					file,
+					hunk,
+					Some(params.options),
+				),
+				DiffType::Commits(ids) => {
+					sync::get_diff_commits_patch(
+						CWD,
+						ids,
+						file,
+						hunk,
+						Some(params.options),
+					)
+				}
+			};
+
+			crate::clipboard::copy_string(&patch?)?;
+		}
+
+		Ok(())
+	}
+
 	fn find_selected_hunk(
 		diff: &FileDiff,
 		line_selected: usize,
@@ -366,17 +824,28 @@ impl DiffComponent {
 							if line_cursor >= min
 								&& line_cursor <= max
 							{
-								res.push(Self::get_line_to_add(
-									width,
-									line,
-									self.focused()
-										&& self
-											.selection
-											.contains(line_cursor),
-									hunk_selected,
-									i == hunk_len as usize - 1,
-									&self.theme,
-								));
+								res.push(
+									self.add_hunk_mark(
+										Self::get_line_to_add(
+											width,
+											line,
+											self.focused()
+												&& self
+													.selection
+													.contains(
+														line_cursor,
+													),
+											hunk_selected,
+											i == hunk_len as usize
+												- 1,
+											self.search_query
+												.as_deref(),
+											&self.theme,
+										),
+										line,
+										hunk.header_hash,
+									),
+								);
 								lines_added += 1;
 							}
 
@@ -391,12 +860,102 @@ impl DiffComponent {
 		res
 	}
 
+	fn hunk_commands(&self, out: &mut Vec<CommandInfo>) {
+		if !self.is_immutable {
+			out.push(CommandInfo::new(
+				strings::commands::diff_hunk_remove(&self.key_config),
+				self.selected_hunk.is_some(),
+				self.focused && self.is_stage(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_hunk_add(&self.key_config),
+				self.selected_hunk.is_some(),
+				self.focused && !self.is_stage(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_hunk_revert(&self.key_config),
+				self.selected_hunk.is_some(),
+				self.focused && !self.is_stage(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_hunk_split(&self.key_config),
+				self.can_split_hunk(),
+				self.focused,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_hunk_edit(&self.key_config),
+				self.can_edit_hunk(),
+				self.focused && !self.is_stage(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_lines_revert(
+					&self.key_config,
+				),
+				//TODO: only if any modifications are selected
+				true,
+				self.focused && !self.is_stage(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_lines_stage(&self.key_config),
+				//TODO: only if any modifications are selected
+				true,
+				self.focused && !self.is_stage(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_lines_unstage(
+					&self.key_config,
+				),
+				//TODO: only if any modifications are selected
+				true,
+				self.focused && self.is_stage(),
+			));
+		}
+
+		if self.marked_hunks.is_some() {
+			out.push(CommandInfo::new(
+				strings::commands::diff_mark_hunk(&self.key_config),
+				self.selected_hunk.is_some(),
+				self.focused,
+			));
+		}
+	}
+
+	/// prefixes hunk headers with their mark if hunk marking is enabled
+	fn add_hunk_mark<'a>(
+		&self,
+		mut spans: Spans<'a>,
+		line: &DiffLine,
+		hash: u64,
+	) -> Spans<'a> {
+		if let Some(marked) = &self.marked_hunks {
+			if line.line_type == DiffLineType::Header
+				&& !self.is_sub_hunk(hash)
+			{
+				let is_marked = marked.contains(&hash);
+				spans.0.insert(
+					1,
+					Span::styled(
+						Cow::from(if is_marked {
+							"[x] "
+						} else {
+							"[ ] "
+						}),
+						self.theme.option(is_marked),
+					),
+				);
+			}
+		}
+
+		spans
+	}
+
 	fn get_line_to_add<'a>(
 		width: u16,
 		line: &'a DiffLine,
 		selected: bool,
 		selected_hunk: bool,
 		end_of_hunk: bool,
+		search_query: Option<&str>,
 		theme: &SharedTheme,
 	) -> Spans<'a> {
 		let style = theme.diff_hunk_marker(selected_hunk);
@@ -424,13 +983,44 @@ impl DiffComponent {
 			format!("{}\n", line.content)
 		};
 
-		Spans::from(vec![
-			left_side_of_line,
-			Span::styled(
-				Cow::from(tabs_to_spaces(filled)),
-				theme.diff_line(line.line_type, selected),
-			),
-		])
+		let content = tabs_to_spaces(filled);
+		let style = theme.diff_line(line.line_type, selected);
+
+		let matches = search_query
+			.map(|query| find_matches(&content, query))
+			.unwrap_or_default();
+
+		if matches.is_empty() {
+			return Spans::from(vec![
+				left_side_of_line,
+				Span::styled(Cow::from(content), style),
+			]);
+		}
+
+		let match_style =
+			theme.diff_search_match(line.line_type, selected);
+
+		let mut spans = vec![left_side_of_line];
+		let mut last = 0;
+		for range in matches {
+			if range.start > last {
+				spans.push(Span::styled(
+					Cow::from(content[last..range.start].to_string()),
+					style,
+				));
+			}
+			spans.push(Span::styled(
+				Cow::from(content[range.clone()].to_string()),
+				match_style,
+			));
+			last = range.end;
+		}
+		spans.push(Span::styled(
+			Cow::from(content[last..].to_string()),
+			style,
+		));
+
+		Spans::from(spans)
 	}
 
 	const fn hunk_visible(
@@ -458,7 +1048,20 @@ impl DiffComponent {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
 				let hash = diff.hunks[hunk].header_hash;
-				sync::unstage_hunk(CWD, &self.current.path, hash)?;
+				if self.is_sub_hunk(hash) {
+					sync::stage_lines(
+						CWD,
+						&self.current.path,
+						true,
+						&self.hunk_changes(hunk),
+					)?;
+				} else {
+					sync::unstage_hunk(
+						CWD,
+						&self.current.path,
+						hash,
+					)?;
+				}
 				self.queue_update();
 			}
 		}
@@ -476,7 +1079,20 @@ impl DiffComponent {
 					)?;
 				} else {
 					let hash = diff.hunks[hunk].header_hash;
-					sync::stage_hunk(CWD, &self.current.path, hash)?;
+					if self.is_sub_hunk(hash) {
+						sync::stage_lines(
+							CWD,
+							&self.current.path,
+							false,
+							&self.hunk_changes(hunk),
+						)?;
+					} else {
+						sync::stage_hunk(
+							CWD,
+							&self.current.path,
+							hash,
+						)?;
+					}
 				}
 
 				self.queue_update();
@@ -486,6 +1102,32 @@ impl DiffComponent {
 		Ok(())
 	}
 
+	fn can_edit_hunk(&self) -> bool {
+		match (&self.diff, self.selected_hunk) {
+			(Some(diff), Some(hunk)) => {
+				!diff.untracked
+					&& !self.is_sub_hunk(diff.hunks[hunk].header_hash)
+			}
+			_ => false,
+		}
+	}
+
+	fn edit_hunk(&self) {
+		if !self.can_edit_hunk() {
+			return;
+		}
+
+		if let (Some(diff), Some(hunk), Some(params)) =
+			(&self.diff, self.selected_hunk, &self.current.params)
+		{
+			self.queue.push(InternalEvent::EditHunk(
+				self.current.path.clone(),
+				diff.hunks[hunk].header_hash,
+				params.options,
+			));
+		}
+	}
+
 	fn queue_update(&self) {
 		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
 	}
@@ -495,12 +1137,16 @@ impl DiffComponent {
 			if let Some(hunk) = self.selected_hunk {
 				let hash = diff.hunks[hunk].header_hash;
 
-				self.queue.push(InternalEvent::ConfirmAction(
-					Action::ResetHunk(
+				let action = if self.is_sub_hunk(hash) {
+					Action::ResetLines(
 						self.current.path.clone(),
-						hash,
-					),
-				));
+						self.hunk_changes(hunk),
+					)
+				} else {
+					Action::ResetHunk(self.current.path.clone(), hash)
+				};
+
+				self.queue.push(InternalEvent::ConfirmAction(action));
 			}
 		}
 	}
@@ -515,24 +1161,21 @@ impl DiffComponent {
 	}
 
 	fn stage_lines(&self) {
-		if let Some(diff) = &self.diff {
-			//TODO: support untracked files aswell
-			if !diff.untracked {
-				let selected_lines = self.selected_lines();
+		if self.diff.is_some() {
+			let selected_lines = self.selected_lines();
 
-				try_or_popup!(
-					self,
-					"(un)stage lines:",
-					sync::stage_lines(
-						CWD,
-						&self.current.path,
-						self.is_stage(),
-						&selected_lines,
-					)
-				);
+			try_or_popup!(
+				self,
+				"(un)stage lines:",
+				sync::stage_lines(
+					CWD,
+					&self.current.path,
+					self.is_stage(),
+					&selected_lines,
+				)
+			);
 
-				self.queue_update();
-			}
+			self.queue_update();
 		}
 	}
 
@@ -566,6 +1209,7 @@ impl DiffComponent {
 			ResetItem {
 				path: self.current.path.clone(),
 				is_folder: false,
+				old_path: None,
 			},
 		)));
 	}
@@ -591,9 +1235,13 @@ impl DrawableComponent for DiffComponent {
 		f: &mut Frame<B>,
 		r: Rect,
 	) -> Result<()> {
+		let search_bar = u16::from(
+			self.search.is_visible() || self.search_bar_visible(),
+		);
+
 		self.current_size.set((
 			r.width.saturating_sub(2),
-			r.height.saturating_sub(2),
+			r.height.saturating_sub(2 + search_bar),
 		));
 
 		let current_height = self.current_size.get().1;
@@ -605,9 +1253,19 @@ impl DrawableComponent for DiffComponent {
 		);
 
 		let title = format!(
-			"{}{}",
+			"{}{}{}",
 			strings::title_diff(&self.key_config),
-			self.current.path
+			self.current.path,
+			self.diff
+				.as_ref()
+				.and_then(|diff| diff.modes)
+				.map_or_else(String::new, |(old, new)| {
+					strings::diff_mode_change(
+						&self.key_config,
+						old,
+						new,
+					)
+				})
 		);
 
 		let txt = if self.pending {
@@ -636,6 +1294,21 @@ impl DrawableComponent for DiffComponent {
 			self.scroll.draw(f, r, &self.theme);
 		}
 
+		if search_bar > 0 {
+			let chunks = Layout::default()
+				.direction(LayoutDirection::Vertical)
+				.constraints(
+					[Constraint::Min(1), Constraint::Length(1)]
+						.as_ref(),
+				)
+				.split(r.inner(&tui::layout::Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.draw_search(f, chunks[1])?;
+		}
+
 		Ok(())
 	}
 }
@@ -661,45 +1334,7 @@ impl Component for DiffComponent {
 			.hidden(),
 		);
 
-		if !self.is_immutable {
-			out.push(CommandInfo::new(
-				strings::commands::diff_hunk_remove(&self.key_config),
-				self.selected_hunk.is_some(),
-				self.focused && self.is_stage(),
-			));
-			out.push(CommandInfo::new(
-				strings::commands::diff_hunk_add(&self.key_config),
-				self.selected_hunk.is_some(),
-				self.focused && !self.is_stage(),
-			));
-			out.push(CommandInfo::new(
-				strings::commands::diff_hunk_revert(&self.key_config),
-				self.selected_hunk.is_some(),
-				self.focused && !self.is_stage(),
-			));
-			out.push(CommandInfo::new(
-				strings::commands::diff_lines_revert(
-					&self.key_config,
-				),
-				//TODO: only if any modifications are selected
-				true,
-				self.focused && !self.is_stage(),
-			));
-			out.push(CommandInfo::new(
-				strings::commands::diff_lines_stage(&self.key_config),
-				//TODO: only if any modifications are selected
-				true,
-				self.focused && !self.is_stage(),
-			));
-			out.push(CommandInfo::new(
-				strings::commands::diff_lines_unstage(
-					&self.key_config,
-				),
-				//TODO: only if any modifications are selected
-				true,
-				self.focused && self.is_stage(),
-			));
-		}
+		self.hunk_commands(out);
 
 		out.push(CommandInfo::new(
 			strings::commands::copy(&self.key_config),
@@ -707,13 +1342,59 @@ impl Component for DiffComponent {
 			self.focused,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::diff_copy_patch(&self.key_config),
+			self.diff.is_some(),
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_copy_hunk_patch(&self.key_config),
+			self.selected_hunk.is_some(),
+			self.focused,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::diff_search(&self.key_config),
+			self.diff.is_some(),
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_search_next_prev(
+				&self.key_config,
+			),
+			!self.search_matches.is_empty(),
+			self.focused && self.search_query.is_some(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_hunk_next_prev(&self.key_config),
+			self.diff
+				.as_ref()
+				.map_or(false, |diff| diff.hunks.len() > 1),
+			self.focused,
+		));
+
+		if self.search.is_visible() {
+			return CommandBlocking::Blocking;
+		}
+
 		CommandBlocking::PassingOn
 	}
 
 	#[allow(clippy::cognitive_complexity)]
 	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.focused && self.search.is_visible() {
+			return self.search_event(ev);
+		}
+
 		if self.focused {
 			if let Event::Key(e) = ev {
+				if self.navigation_event(e)?
+					|| self.hunk_event(e)
+					|| self.mark_hunk_event(e)
+				{
+					return Ok(EventState::Consumed);
+				}
+
 				return if e == self.key_config.keys.move_down {
 					self.move_selection(ScrollType::Down);
 					Ok(EventState::Consumed)
@@ -769,16 +1450,29 @@ impl Component for DiffComponent {
 					&& !self.is_immutable
 					&& !self.is_stage()
 				{
-					if let Some(diff) = &self.diff {
-						//TODO: reset untracked lines
-						if !diff.untracked {
-							self.reset_lines();
-						}
+					if self.diff.is_some() {
+						self.reset_lines();
 					}
 					Ok(EventState::Consumed)
 				} else if e == self.key_config.keys.copy {
 					self.copy_selection();
 					Ok(EventState::Consumed)
+				} else if e == self.key_config.keys.diff_copy_patch {
+					try_or_popup!(
+						self,
+						"copy patch error:",
+						self.copy_patch(false)
+					);
+					Ok(EventState::Consumed)
+				} else if e
+					== self.key_config.keys.diff_copy_hunk_patch
+				{
+					try_or_popup!(
+						self,
+						"copy patch error:",
+						self.copy_patch(true)
+					);
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
@@ -793,5 +1487,94 @@ impl Component for DiffComponent {
 	}
 	fn focus(&mut self, focus: bool) {
 		self.focused = focus;
+		if !focus {
+			self.search.hide();
+		}
+	}
+}
+
+/// byte ranges of all occurrences of `query` in `text`,
+/// case insensitive unless `query` contains uppercase chars
+fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+	if query.is_empty() {
+		return Vec::new();
+	}
+
+	let ignore_case = !query.chars().any(char::is_uppercase);
+
+	let (text, query) = if ignore_case {
+		(
+			Cow::from(text.to_ascii_lowercase()),
+			Cow::from(query.to_ascii_lowercase()),
+		)
+	} else {
+		(Cow::from(text), Cow::from(query))
+	};
+
+	text.match_indices(query.as_ref())
+		.map(|(start, m)| start..start + m.len())
+		.collect()
+}
+
+/// first and last line of the new file `hunk` covers
+fn new_line_range(hunk: &Hunk) -> Option<(u32, u32)> {
+	let mut lines =
+		hunk.lines.iter().filter_map(|l| l.position.new_lineno);
+	let first = lines.next()?;
+
+	Some((first, lines.next_back().unwrap_or(first)))
+}
+
+/// `hunk` overlaps any of the `split_hunks` line ranges
+fn is_split(split_hunks: &[(u32, u32)], hunk: &Hunk) -> bool {
+	new_line_range(hunk).map_or(false, |(first, last)| {
+		split_hunks
+			.iter()
+			.any(|(start, end)| first <= *end && *start <= last)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{find_matches, is_split};
+	use asyncgit::{
+		sync::diff::{DiffLinePosition, Hunk},
+		DiffLine,
+	};
+
+	#[test]
+	fn test_find_matches() {
+		assert_eq!(
+			find_matches("foo bar foo", "foo"),
+			vec![0..3, 8..11]
+		);
+		assert_eq!(find_matches("Foo bar", "foo"), vec![0..3]);
+		assert!(find_matches("foo bar", "Foo").is_empty());
+		assert!(find_matches("foo bar", "").is_empty());
+	}
+
+	#[test]
+	fn test_is_split() {
+		let hunk = |lines: &[u32]| Hunk {
+			header_hash: 0,
+			lines: lines
+				.iter()
+				.map(|l| DiffLine {
+					position: DiffLinePosition {
+						old_lineno: None,
+						new_lineno: Some(*l),
+					},
+					..DiffLine::default()
+				})
+				.collect(),
+		};
+
+		// the remaining part of a split hunk after staging one of
+		// its sub-hunks keeps being split
+		assert!(is_split(&[(3, 9)], &hunk(&[6, 7, 8, 9])));
+		assert!(is_split(&[(3, 9)], &hunk(&[1, 2, 3])));
+		assert!(!is_split(&[(3, 9)], &hunk(&[12, 13])));
+		assert!(!is_split(&[], &hunk(&[3, 4])));
+		assert!(!is_split(&[(3, 9)], &hunk(&[])));
 	}
 }
@@ -0,0 +1,155 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, InputType,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, CommitId},
+	CWD,
+};
+use crossterm::event::Event;
+use std::path::PathBuf;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+pub struct ExportPatchesComponent {
+	input: TextInputComponent,
+	commits: Vec<CommitId>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ExportPatchesComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for ExportPatchesComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::export_patches_confirm_msg(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.enter {
+					self.export();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl ExportPatchesComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				&strings::export_patches_popup_title(&key_config),
+				&strings::export_patches_popup_msg(&key_config),
+				false,
+			)
+			.with_input_type(InputType::Singleline),
+			commits: Vec::new(),
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, commits: Vec<CommitId>) -> Result<()> {
+		self.commits = commits;
+		self.input.set_title(strings::export_patches_popup_title(
+			&self.key_config,
+		));
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn export(&mut self) {
+		let dir = if self.input.get_text().is_empty() {
+			PathBuf::from(".")
+		} else {
+			PathBuf::from(self.input.get_text())
+		};
+
+		let res = sync::format_patches(CWD, &self.commits, &dir);
+
+		self.hide();
+
+		match res {
+			Ok(files) => {
+				self.input.clear();
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::export_patches_done_msg(
+						files.len(),
+						&dir.to_string_lossy(),
+					),
+				));
+			}
+			Err(e) => {
+				log::error!("export patches: {}", e);
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("export patches error:\n{}", e),
+				));
+			}
+		}
+	}
+}
@@ -9,7 +9,10 @@ use crate::{
 };
 use anyhow::{anyhow, bail, Result};
 use asyncgit::{
-	sync::{get_config_string, utils::repo_work_dir},
+	sync::{
+		self, diff::DiffOptions, get_config_string,
+		utils::repo_work_dir,
+	},
 	CWD,
 };
 use crossterm::{
@@ -19,7 +22,7 @@ use crossterm::{
 };
 use scopeguard::defer;
 use std::ffi::OsStr;
-use std::{env, io, path::Path, process::Command};
+use std::{env, fs, io, path::Path, process::Command};
 use tui::{
 	backend::Backend,
 	layout::Rect,
@@ -116,6 +119,44 @@ impl ExternalEditorComponent {
 
 		Ok(())
 	}
+
+	/// opens the unstaged hunk `hunk` of `path` as a patch in an
+	/// editor and stages the edited result
+	pub fn edit_hunk(
+		path: &str,
+		hunk: u64,
+		options: DiffOptions,
+	) -> Result<()> {
+		let file_path = sync::repo_dir(CWD)?.join("ADD_EDIT.patch");
+
+		fs::write(
+			&file_path,
+			sync::hunk_edit_text(CWD, path, hunk, Some(options))?,
+		)?;
+
+		let edited = Self::open_file_in_editor(&file_path)
+			.and_then(|()| Ok(fs::read_to_string(&file_path)?));
+		fs::remove_file(&file_path)?;
+		let edited = edited?;
+
+		// all lines deleted: abort
+		if edited
+			.lines()
+			.all(|l| l.trim().is_empty() || l.starts_with('#'))
+		{
+			return Ok(());
+		}
+
+		sync::stage_edited_hunk(
+			CWD,
+			path,
+			hunk,
+			Some(options),
+			&edited,
+		)?;
+
+		Ok(())
+	}
 }
 
 impl DrawableComponent for ExternalEditorComponent {
@@ -14,9 +14,12 @@ use crate::{
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{hash, StatusItem, StatusItemType};
+use asyncgit::{hash, sync::CommitId, StatusItem, StatusItemType};
 use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, convert::From, path::Path};
+use std::{
+	borrow::Cow, cell::Cell, collections::HashSet, convert::From,
+	path::Path,
+};
 use tui::{backend::Backend, layout::Rect, text::Span, Frame};
 
 //TODO: rename so that its clear this only works for Statuses
@@ -34,6 +37,10 @@ pub struct FileTreeComponent {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	scroll_top: Cell<usize>,
+	/// unchecked file paths if drawn as checklist
+	unchecked: Option<HashSet<String>>,
+	/// commit the files are blamed at, `None` for HEAD
+	revision: Option<CommitId>,
 }
 
 impl FileTreeComponent {
@@ -56,6 +63,92 @@ impl FileTreeComponent {
 			key_config,
 			scroll_top: Cell::new(0),
 			pending: true,
+			unchecked: None,
+			revision: None,
+		}
+	}
+
+	/// commit the files belong to (to blame them at)
+	pub fn set_revision(&mut self, revision: Option<CommitId>) {
+		self.revision = revision;
+	}
+
+	/// draws a checkbox in front of each item, all checked initially
+	pub fn set_checklist(&mut self, enabled: bool) {
+		self.unchecked =
+			if enabled { Some(HashSet::new()) } else { None };
+	}
+
+	/// checks/unchecks the selected file or all files in the selected
+	/// folder (checks all if any of them was unchecked)
+	pub fn toggle_checked(&mut self) {
+		let selection = self.tree.selected_item();
+		if let (Some(unchecked), Some(selection)) =
+			(self.unchecked.as_mut(), selection)
+		{
+			let paths = self
+				.tree
+				.tree
+				.items()
+				.iter()
+				.filter_map(|item| match &item.kind {
+					FileTreeItemKind::File(f)
+						if is_in_path(
+							&f.path,
+							&selection.info.full_path,
+						) =>
+					{
+						Some(f.path.clone())
+					}
+					_ => None,
+				})
+				.collect::<Vec<_>>();
+
+			if paths.iter().any(|p| unchecked.contains(p)) {
+				for p in &paths {
+					unchecked.remove(p);
+				}
+			} else {
+				unchecked.extend(paths);
+			}
+		}
+	}
+
+	/// all files in the tree that are not unchecked
+	pub fn checked_files(&self) -> Vec<StatusItem> {
+		self.files()
+			.into_iter()
+			.filter(|f| {
+				self.unchecked.as_ref().map_or(true, |unchecked| {
+					!unchecked.contains(&f.path)
+				})
+			})
+			.collect()
+	}
+
+	fn checkbox(&self, full_path: &str) -> &'static str {
+		let unchecked = match &self.unchecked {
+			Some(unchecked) => unchecked,
+			None => return "",
+		};
+
+		let (mut any_checked, mut any_unchecked) = (false, false);
+		for item in self.tree.tree.items() {
+			if let FileTreeItemKind::File(f) = &item.kind {
+				if is_in_path(&f.path, full_path) {
+					if unchecked.contains(&f.path) {
+						any_unchecked = true;
+					} else {
+						any_checked = true;
+					}
+				}
+			}
+		}
+
+		match (any_checked, any_unchecked) {
+			(true, true) => "[-] ",
+			(false, true) => "[ ] ",
+			_ => "[x] ",
 		}
 	}
 
@@ -87,6 +180,22 @@ impl FileTreeComponent {
 		})
 	}
 
+	/// all files in the tree
+	pub fn files(&self) -> Vec<StatusItem> {
+		self.tree
+			.tree
+			.items()
+			.iter()
+			.filter_map(|item| {
+				if let FileTreeItemKind::File(f) = &item.kind {
+					Some(f.clone())
+				} else {
+					None
+				}
+			})
+			.collect()
+	}
+
 	///
 	pub fn show_selection(&mut self, show: bool) {
 		self.show_selection = show;
@@ -136,14 +245,17 @@ impl FileTreeComponent {
 		changed
 	}
 
-	const fn item_status_char(item_type: StatusItemType) -> char {
+	///
+	pub const fn item_status_char(item_type: StatusItemType) -> char {
 		match item_type {
 			StatusItemType::Modified => 'M',
 			StatusItemType::New => '+',
 			StatusItemType::Deleted => '-',
 			StatusItemType::Renamed => 'R',
+			StatusItemType::Copied => 'C',
 			StatusItemType::Typechange => ' ',
 			StatusItemType::Conflicted => '!',
+			StatusItemType::Ignored => 'I',
 		}
 	}
 
@@ -174,6 +286,12 @@ impl FileTreeComponent {
 					.file_name()
 					.and_then(std::ffi::OsStr::to_str)
 					.expect("invalid path.");
+				let file = status_item.old_path.as_ref().map_or_else(
+					|| Cow::from(file),
+					|old_path| {
+						Cow::from(format!("{} -> {}", old_path, file))
+					},
+				);
 
 				let txt = if selected {
 					format!(
@@ -246,6 +364,7 @@ impl FileTreeComponent {
 
 			vec_draw_text_info.push(TextDrawInfo {
 				name: item.info.path.clone(),
+				full_path: item.info.full_path.clone(),
 				indent: item.info.indent,
 				visible: item.info.visible,
 				item_kind: &item.kind,
@@ -300,9 +419,15 @@ impl FileTreeComponent {
 	}
 }
 
+/// true if `path` is `folder_or_file` or inside of it
+fn is_in_path(path: &str, folder_or_file: &str) -> bool {
+	Path::new(path).starts_with(folder_or_file)
+}
+
 /// Used for drawing the `FileTreeComponent`
 struct TextDrawInfo<'a> {
 	name: String,
+	full_path: String,
 	indent: u8,
 	visible: bool,
 	item_kind: &'a FileTreeItemKind,
@@ -361,6 +486,22 @@ impl DrawableComponent for FileTreeComponent {
 						self.show_selection && select == index,
 						&self.theme,
 					)
+					.map(|span| {
+						if self.unchecked.is_some() {
+							Span::styled(
+								Cow::from(format!(
+									"{}{}",
+									self.checkbox(
+										&draw_text_info.full_path
+									),
+									span.content
+								)),
+								span.style,
+							)
+						} else {
+							span
+						}
+					})
 				})
 				.skip(self.scroll_top.get());
 			ui::draw_list(
@@ -411,6 +552,7 @@ impl Component for FileTreeComponent {
 						(Some(queue), Some(status_item)) => {
 							queue.push(InternalEvent::BlameFile(
 								status_item.path,
+								self.revision,
 							));
 
 							Ok(EventState::Consumed)
@@ -470,6 +612,7 @@ mod tests {
 			.map(|a| StatusItem {
 				path: String::from(*a),
 				status: StatusItemType::Modified,
+				old_path: None,
 			})
 			.collect::<Vec<_>>()
 	}
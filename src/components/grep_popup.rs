@@ -0,0 +1,453 @@
+use super::{
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ScrollType, TextInputComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{CommitId, GrepMatch},
+	AsyncGitNotification, AsyncGrepJob, ProgressPercent,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear},
+	Frame,
+};
+
+pub struct GrepPopup {
+	queue: Queue,
+	visible: bool,
+	find_text: TextInputComponent,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+	revision: Option<CommitId>,
+	/// search the working tree instead of `revision`
+	workdir: bool,
+	async_grep: AsyncSingleJob<AsyncGrepJob>,
+	/// search currently running, to cancel it
+	running: Option<AsyncGrepJob>,
+	progress: Option<ProgressPercent>,
+	/// pattern and source `matches` were searched with
+	searched: Option<(String, bool)>,
+	matches: Vec<GrepMatch>,
+	/// the search stopped at `GREP_MAX_MATCHES`
+	limit_reached: bool,
+	selection: usize,
+	scroll: VerticalScroll,
+}
+
+impl GrepPopup {
+	///
+	pub fn new(
+		queue: &Queue,
+		sender: &Sender<AsyncGitNotification>,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut find_text = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			"",
+			&strings::grep_popup_msg(),
+			false,
+		);
+		find_text.embed();
+
+		Self {
+			queue: queue.clone(),
+			visible: false,
+			find_text,
+			theme,
+			key_config,
+			revision: None,
+			workdir: true,
+			async_grep: AsyncSingleJob::new(sender.clone()),
+			running: None,
+			progress: None,
+			searched: None,
+			matches: Vec::new(),
+			limit_reached: false,
+			selection: 0,
+			scroll: VerticalScroll::new(),
+		}
+	}
+
+	/// searches `revision`, or the working tree if `None`
+	pub fn open(&mut self, revision: Option<CommitId>) -> Result<()> {
+		if self.revision != revision {
+			self.revision = revision;
+			self.clear_matches();
+		}
+		self.workdir = revision.is_none();
+
+		self.show()?;
+		self.find_text.show()?;
+
+		Ok(())
+	}
+
+	///
+	pub fn update_git(&mut self, ev: AsyncGitNotification) {
+		if ev == AsyncGitNotification::Grep {
+			if let Some(job) = self.async_grep.take_last() {
+				match job.result() {
+					Some(Ok(Some(result))) => {
+						self.matches = result.matches;
+						self.limit_reached = result.limit_reached;
+						self.selection = 0;
+						self.stopped();
+					}
+					Some(Err(e)) => {
+						self.stopped();
+						self.queue.push(InternalEvent::ShowErrorMsg(
+							format!("grep error:\n{}", e),
+						));
+					}
+					// cancelled
+					Some(Ok(None)) | None => (),
+				}
+			} else {
+				self.progress = self.async_grep.progress();
+			}
+		}
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.async_grep.is_pending()
+	}
+
+	fn clear_matches(&mut self) {
+		self.searched = None;
+		self.matches.clear();
+		self.limit_reached = false;
+		self.selection = 0;
+	}
+
+	fn search(&mut self) {
+		let pattern = self.find_text.get_text().to_string();
+		let revision =
+			if self.workdir { None } else { self.revision };
+
+		self.cancel_search();
+		self.clear_matches();
+		self.progress = Some(ProgressPercent::empty());
+
+		let job = AsyncGrepJob::new(revision, &pattern);
+		self.running = Some(job.clone());
+		self.async_grep.spawn(job);
+		self.searched = Some((pattern, self.workdir));
+	}
+
+	fn cancel_search(&mut self) {
+		self.async_grep.cancel();
+
+		if let Some(job) = self.running.take() {
+			job.cancel();
+
+			// search again next time
+			self.searched = None;
+			self.progress = None;
+		}
+	}
+
+	fn stopped(&mut self) {
+		self.running = None;
+		self.progress = None;
+	}
+
+	fn needs_search(&self) -> bool {
+		!self.find_text.get_text().is_empty()
+			&& self.searched.as_ref().map_or(
+				true,
+				|(pattern, workdir)| {
+					pattern != self.find_text.get_text()
+						|| *workdir != self.workdir
+				},
+			)
+	}
+
+	fn confirm(&mut self) {
+		if self.needs_search() {
+			self.search();
+		} else if let Some(grep_match) =
+			self.matches.get(self.selection)
+		{
+			self.queue.push(InternalEvent::OpenGrepMatch(
+				grep_match.clone(),
+				self.workdir,
+			));
+			self.hide();
+		}
+	}
+
+	fn toggle_workdir(&mut self) {
+		if self.revision.is_some() {
+			self.workdir = !self.workdir;
+
+			if self.searched.is_some() {
+				self.search();
+			}
+		}
+	}
+
+	fn move_selection(&mut self, move_type: ScrollType) -> bool {
+		let new_selection = match move_type {
+			ScrollType::Up => self.selection.saturating_sub(1),
+			ScrollType::Down => self.selection.saturating_add(1),
+			_ => self.selection,
+		};
+
+		let new_selection =
+			new_selection.min(self.matches.len().saturating_sub(1));
+
+		let changed = new_selection != self.selection;
+		self.selection = new_selection;
+		changed
+	}
+
+	fn get_title(&self) -> String {
+		let source = if self.workdir {
+			strings::grep_popup_worktree()
+		} else {
+			self.revision
+				.map(|id| id.get_short_string())
+				.unwrap_or_default()
+		};
+
+		format!(
+			"{} [{}]{}",
+			strings::grep_popup_title(),
+			source,
+			self.progress
+				.map(|p| format!(" ({}%)", p.progress))
+				.unwrap_or_default()
+		)
+	}
+
+	fn draw_matches<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+		let height = usize::from(area.height.saturating_sub(1));
+		let width = usize::from(area.width);
+
+		self.scroll.update(
+			self.selection,
+			self.matches.len(),
+			height,
+		);
+
+		let items = self
+			.matches
+			.iter()
+			.enumerate()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(index, grep_match)| {
+				let selected = index == self.selection;
+				let location = format!(
+					"{}:{}: ",
+					grep_match.path,
+					grep_match.line + 1
+				);
+				let content: String = grep_match
+					.content
+					.trim()
+					.chars()
+					.take(width.saturating_sub(location.len()))
+					.collect();
+
+				Spans::from(vec![
+					Span::styled(
+						location,
+						self.theme.commit_hash(selected),
+					),
+					Span::styled(
+						content,
+						self.theme.text(true, selected),
+					),
+				])
+			});
+
+		ui::draw_list_block(
+			f,
+			area,
+			Block::default()
+				.title(Span::styled(
+					format!(
+						"Hits: {}{}",
+						self.matches.len(),
+						if self.limit_reached {
+							strings::grep_limit_reached()
+						} else {
+							""
+						}
+					),
+					self.theme.title(true),
+				))
+				.borders(Borders::TOP),
+			items,
+		);
+	}
+}
+
+impl DrawableComponent for GrepPopup {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		area: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			const MAX_SIZE: (u16, u16) = (80, 25);
+
+			let any_hits = !self.matches.is_empty();
+
+			let area = ui::centered_rect_absolute(
+				MAX_SIZE.0, MAX_SIZE.1, area,
+			);
+
+			let area = if any_hits {
+				area
+			} else {
+				Layout::default()
+					.direction(Direction::Vertical)
+					.constraints(
+						[
+							Constraint::Length(3),
+							Constraint::Percentage(100),
+						]
+						.as_ref(),
+					)
+					.split(area)[0]
+			};
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::all())
+					.style(self.theme.title(true))
+					.title(Span::styled(
+						self.get_title(),
+						self.theme.title(true),
+					)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Length(1),
+						Constraint::Percentage(100),
+					]
+					.as_ref(),
+				)
+				.split(area.inner(&Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.find_text.draw(f, chunks[0])?;
+
+			if any_hits {
+				self.draw_matches(f, chunks[1]);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for GrepPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				!self.matches.is_empty(),
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::grep_confirm(&self.key_config),
+				!self.find_text.get_text().is_empty(),
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::grep_worktree(&self.key_config),
+				self.revision.is_some(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(
+		&mut self,
+		event: crossterm::event::Event,
+	) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = &event {
+				if *key == self.key_config.keys.exit_popup {
+					self.hide();
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.keys.enter {
+					self.confirm();
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.keys.popup_down {
+					self.move_selection(ScrollType::Down);
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.keys.popup_up {
+					self.move_selection(ScrollType::Up);
+					return Ok(EventState::Consumed);
+				} else if *key == self.key_config.keys.grep_worktree {
+					self.toggle_workdir();
+					return Ok(EventState::Consumed);
+				}
+			}
+
+			self.find_text.event(event)?;
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.cancel_search();
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -0,0 +1,349 @@
+use super::{
+	utils::{scroll_vertical::VerticalScroll, string_width_align},
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, ScrollType,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, IgnoreKind, IgnoreTarget, NewIgnoreRule},
+	CWD,
+};
+use crossterm::event::Event;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	style::Modifier,
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+const KINDS: [IgnoreKind; 3] = [
+	IgnoreKind::Path,
+	IgnoreKind::Extension,
+	IgnoreKind::Directory,
+];
+
+const TARGETS: [IgnoreTarget; 4] = [
+	IgnoreTarget::Root,
+	IgnoreTarget::Nested,
+	IgnoreTarget::InfoExclude,
+	IgnoreTarget::Global,
+];
+
+/// which setting is selected
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Selection {
+	Kind,
+	Target,
+}
+
+/// popup to add an ignore rule for a path with a preview of the
+/// untracked files it hides
+pub struct IgnoreRuleComponent {
+	path: String,
+	kind: usize,
+	target: usize,
+	selection: Selection,
+	rule: Result<NewIgnoreRule, String>,
+	preview: Vec<String>,
+	scroll: VerticalScroll,
+	visible: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for IgnoreRuleComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(60, 60, f.size());
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(Span::styled(
+						strings::ignore_rule_popup_title(
+							&self.key_config,
+							&self.path,
+						),
+						self.theme.title(true),
+					))
+					.border_style(self.theme.block(true)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Length(4), Constraint::Min(1)]
+						.as_ref(),
+				)
+				.margin(1)
+				.split(area);
+
+			f.render_widget(
+				Paragraph::new(
+					self.get_settings_text(chunks[0].width),
+				),
+				chunks[0],
+			);
+
+			self.draw_preview(f, chunks[1]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for IgnoreRuleComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::ignore_rule_confirm(
+					&self.key_config,
+				),
+				self.rule.is_ok(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::navigate_tree(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.exit_popup {
+					self.hide();
+				} else if e == self.key_config.keys.enter {
+					self.confirm();
+				} else if e == self.key_config.keys.move_up
+					|| e == self.key_config.keys.move_down
+				{
+					self.selection = match self.selection {
+						Selection::Kind => Selection::Target,
+						Selection::Target => Selection::Kind,
+					};
+				} else if e == self.key_config.keys.move_right {
+					self.switch(true);
+				} else if e == self.key_config.keys.move_left {
+					self.switch(false);
+				} else if e == self.key_config.keys.popup_down {
+					self.scroll.move_top(ScrollType::Down);
+				} else if e == self.key_config.keys.popup_up {
+					self.scroll.move_top(ScrollType::Up);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+impl IgnoreRuleComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			path: String::new(),
+			kind: 0,
+			target: 0,
+			selection: Selection::Kind,
+			rule: Err(String::new()),
+			preview: Vec::new(),
+			scroll: VerticalScroll::new(),
+			visible: false,
+			queue,
+			theme,
+			key_config,
+		}
+	}
+
+	/// `path` is the file or folder to ignore
+	pub fn open(&mut self, path: String) -> Result<()> {
+		self.path = path;
+		self.kind = 0;
+		self.target = 0;
+		self.selection = Selection::Kind;
+		self.update();
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn switch(&mut self, right: bool) {
+		let (value, count) = match self.selection {
+			Selection::Kind => (&mut self.kind, KINDS.len()),
+			Selection::Target => (&mut self.target, TARGETS.len()),
+		};
+
+		*value = if right {
+			(*value + 1) % count
+		} else {
+			(*value + count - 1) % count
+		};
+
+		self.update();
+	}
+
+	fn update(&mut self) {
+		self.rule = sync::new_ignore_rule(
+			CWD,
+			&self.path,
+			KINDS[self.kind],
+			TARGETS[self.target],
+		)
+		.map_err(|e| e.to_string());
+
+		self.preview = self.rule.as_ref().map_or_else(
+			|_| Vec::new(),
+			|rule| {
+				sync::ignore_rule_preview(CWD, rule)
+					.unwrap_or_default()
+			},
+		);
+		self.scroll.reset();
+	}
+
+	fn confirm(&mut self) {
+		if let Ok(rule) = &self.rule {
+			if let Err(e) = sync::add_ignore_rule(CWD, rule) {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("ignore error:\n{}", e),
+				));
+			}
+
+			self.hide();
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		}
+	}
+
+	fn get_settings_text(&self, width: u16) -> Vec<Spans<'_>> {
+		let half = usize::from(width / 2);
+
+		let entry = |name: &str, value: &str, selected: bool| {
+			Spans::from(vec![
+				Span::styled(
+					string_width_align(name, half),
+					self.theme.text(true, false),
+				),
+				Span::styled(
+					format!("{:^w$}", value, w = half),
+					self.theme.text(true, selected),
+				),
+			])
+		};
+
+		let rule = match &self.rule {
+			Ok(rule) => Span::styled(
+				format!("{}  ->  {}", rule.pattern, rule.file_name),
+				self.theme.text(true, false),
+			),
+			Err(e) => {
+				Span::styled(e.clone(), self.theme.text_danger())
+			}
+		};
+
+		vec![
+			entry(
+				"Ignore",
+				strings::ignore_kind_text(KINDS[self.kind]),
+				self.selection == Selection::Kind,
+			),
+			entry(
+				"Add to",
+				strings::ignore_target_text(TARGETS[self.target]),
+				self.selection == Selection::Target,
+			),
+			Spans::from(rule),
+			Spans::from(Span::styled(
+				strings::ignore_rule_preview_title(
+					&self.key_config,
+					self.preview.len(),
+				),
+				self.theme
+					.text(true, false)
+					.add_modifier(Modifier::UNDERLINED),
+			)),
+		]
+	}
+
+	fn draw_preview<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+		let height = usize::from(r.height);
+		let top = self
+			.scroll
+			.update_no_selection(self.preview.len(), height);
+
+		f.render_widget(
+			Paragraph::new(
+				self.preview
+					.iter()
+					.skip(top)
+					.take(height)
+					.map(|path| {
+						Spans::from(Span::styled(
+							path.as_str(),
+							self.theme.text(true, false),
+						))
+					})
+					.collect::<Vec<_>>(),
+			),
+			r,
+		);
+
+		self.scroll.draw(f, r, &self.theme);
+	}
+}
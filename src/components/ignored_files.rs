@@ -0,0 +1,210 @@
+use super::{
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, FileTreeComponent,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, IgnoredFile},
+	StatusItemType, CWD,
+};
+use crossterm::event::Event;
+use std::{cell::Cell, cmp, path::Path};
+use tui::{
+	backend::Backend,
+	layout::Rect,
+	text::{Span, Spans},
+	Frame,
+};
+
+/// ignored files of the workdir with the rule ignoring each of them
+pub struct IgnoredFilesComponent {
+	files: Vec<IgnoredFile>,
+	selection: usize,
+	scroll_top: Cell<usize>,
+	focused: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for IgnoredFilesComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		r: Rect,
+	) -> Result<()> {
+		let height = usize::from(r.height.saturating_sub(2));
+
+		self.scroll_top.set(ui::calc_scroll_top(
+			self.scroll_top.get(),
+			height,
+			self.selection,
+		));
+
+		ui::draw_list(
+			f,
+			r,
+			&strings::title_ignored(&self.key_config),
+			self.get_text(r.width)
+				.into_iter()
+				.skip(self.scroll_top.get()),
+			self.focused,
+			&self.theme,
+		);
+
+		Ok(())
+	}
+}
+
+impl Component for IgnoredFilesComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		out.push(CommandInfo::new(
+			strings::commands::force_add_item(&self.key_config),
+			!self.files.is_empty(),
+			self.focused || force_all,
+		));
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.focused {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.stage_unstage_item {
+					if let Some(file) = self.files.get(self.selection)
+					{
+						try_or_popup!(
+							self,
+							"force add error:",
+							sync::stage_add_force(
+								CWD,
+								Path::new(&file.path)
+							)
+						);
+						self.queue.push(InternalEvent::Update(
+							NeedsUpdate::ALL,
+						));
+
+						return Ok(EventState::Consumed);
+					}
+				} else if e == self.key_config.keys.move_down {
+					return Ok(self.move_selection(true).into());
+				} else if e == self.key_config.keys.move_up {
+					return Ok(self.move_selection(false).into());
+				}
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
+
+impl IgnoredFilesComponent {
+	///
+	pub const fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			files: Vec::new(),
+			selection: 0,
+			scroll_top: Cell::new(0),
+			focused: false,
+			queue,
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn set_items(&mut self, files: Vec<IgnoredFile>) {
+		self.files = files;
+		self.selection = cmp::min(
+			self.selection,
+			self.files.len().saturating_sub(1),
+		);
+	}
+
+	///
+	pub fn is_empty(&self) -> bool {
+		self.files.is_empty()
+	}
+
+	fn move_selection(&mut self, down: bool) -> bool {
+		let old = self.selection;
+
+		self.selection = if down {
+			cmp::min(
+				self.selection.saturating_add(1),
+				self.files.len().saturating_sub(1),
+			)
+		} else {
+			self.selection.saturating_sub(1)
+		};
+
+		old != self.selection
+	}
+
+	fn get_text(&self, width: u16) -> Vec<Spans<'_>> {
+		self.files
+			.iter()
+			.enumerate()
+			.map(|(idx, file)| {
+				let selected = self.focused && idx == self.selection;
+
+				let rule = file.rule.as_ref().map_or_else(
+					String::new,
+					|rule| {
+						format!(
+							"  {}:{}: {}",
+							rule.source, rule.line, rule.pattern
+						)
+					},
+				);
+
+				let path = format!(
+					"{} {}",
+					FileTreeComponent::item_status_char(
+						StatusItemType::Ignored
+					),
+					file.path
+				);
+
+				let path_width = usize::from(width.saturating_sub(2))
+					.saturating_sub(rule.len())
+					.max(path.len());
+
+				Spans::from(vec![
+					Span::styled(
+						format!("{:w$}", path, w = path_width),
+						self.theme
+							.item(StatusItemType::Ignored, selected),
+					),
+					Span::styled(
+						rule,
+						self.theme.text(false, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,281 @@
+use super::{
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, IndexFlaggedFile},
+	CWD,
+};
+use crossterm::event::Event;
+use std::cmp;
+use tui::{
+	backend::Backend,
+	layout::{Margin, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists files with assume-unchanged/skip-worktree set
+pub struct IndexFlagsComponent {
+	files: Vec<IndexFlaggedFile>,
+	selection: usize,
+	scroll: VerticalScroll,
+	visible: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for IndexFlagsComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(60, 50, f.size());
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(Span::styled(
+						strings::index_flags_popup_title(
+							&self.key_config,
+						),
+						self.theme.title(true),
+					))
+					.border_style(self.theme.block(true)),
+				area,
+			);
+
+			let area = area.inner(&Margin {
+				vertical: 1,
+				horizontal: 1,
+			});
+
+			let height = usize::from(area.height);
+			let top = self.scroll.update(
+				self.selection,
+				self.files.len(),
+				height,
+			);
+
+			f.render_widget(
+				Paragraph::new(
+					self.get_text()
+						.into_iter()
+						.skip(top)
+						.take(height)
+						.collect::<Vec<_>>(),
+				),
+				area,
+			);
+
+			self.scroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for IndexFlagsComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				self.files.len() > 1,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::toggle_assume_unchanged(
+					&self.key_config,
+				),
+				!self.files.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::toggle_skip_worktree(
+					&self.key_config,
+				),
+				!self.files.is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.exit_popup
+					|| e == self.key_config.keys.enter
+				{
+					self.hide();
+				} else if e == self.key_config.keys.move_down {
+					self.selection = cmp::min(
+						self.selection.saturating_add(1),
+						self.files.len().saturating_sub(1),
+					);
+				} else if e == self.key_config.keys.move_up {
+					self.selection = self.selection.saturating_sub(1);
+				} else if e
+					== self.key_config.keys.status_assume_unchanged
+				{
+					self.toggle(true)?;
+				} else if e
+					== self.key_config.keys.status_skip_worktree
+				{
+					self.toggle(false)?;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+impl IndexFlagsComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			files: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			visible: false,
+			queue,
+			theme,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.scroll.reset();
+		self.update()?;
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn update(&mut self) -> Result<()> {
+		self.files = sync::get_index_flagged_files(CWD)?;
+		self.selection = cmp::min(
+			self.selection,
+			self.files.len().saturating_sub(1),
+		);
+
+		Ok(())
+	}
+
+	fn toggle(&mut self, assume_unchanged: bool) -> Result<()> {
+		if let Some(file) = self.files.get(self.selection) {
+			let res = if assume_unchanged {
+				sync::set_assume_unchanged(
+					CWD,
+					&file.path,
+					!file.flags.assume_unchanged,
+				)
+			} else {
+				sync::set_skip_worktree(
+					CWD,
+					&file.path,
+					!file.flags.skip_worktree,
+				)
+			};
+
+			if let Err(e) = res {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("index flags error:\n{}", e),
+				));
+			}
+
+			self.update()?;
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		}
+
+		Ok(())
+	}
+
+	fn get_text(&self) -> Vec<Spans<'_>> {
+		if self.files.is_empty() {
+			return vec![Spans::from(Span::styled(
+				strings::index_flags_empty(&self.key_config),
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.files
+			.iter()
+			.enumerate()
+			.map(|(idx, file)| {
+				let selected = idx == self.selection;
+				Spans::from(vec![
+					Span::styled(
+						format!(
+							"{}{} ",
+							if file.flags.assume_unchanged {
+								'A'
+							} else {
+								' '
+							},
+							if file.flags.skip_worktree {
+								'S'
+							} else {
+								' '
+							}
+						),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						file.path.clone(),
+						self.theme.text(true, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
@@ -1,18 +1,18 @@
 use super::{
 	command_pump, event_pump, visibility_blocking, CommandBlocking,
 	CommandInfo, CommitDetailsComponent, Component, DiffComponent,
-	DrawableComponent, EventState,
+	DrawableComponent, EventState, SharedOptions,
 };
 use crate::{
 	accessors,
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, Queue},
+	queue::{Action, InternalEvent, Queue},
 	strings,
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{diff::DiffOptions, CommitId, CommitTags},
+	sync::{ApplyLocation, CommitId, CommitTags},
 	AsyncDiff, AsyncGitNotification, CommitFilesParams, DiffParams,
 	DiffType,
 };
@@ -33,6 +33,7 @@ pub struct InspectCommitComponent {
 	details: CommitDetailsComponent,
 	git_diff: AsyncDiff,
 	visible: bool,
+	options: SharedOptions,
 	key_config: SharedKeyConfig,
 }
 
@@ -104,6 +105,19 @@ impl Component for InspectCommitComponent {
 				self.diff.focused() || force_all,
 			));
 
+			let file_selected =
+				self.details.files().selection_file().is_some();
+			out.push(CommandInfo::new(
+				strings::commands::restore_workdir(&self.key_config),
+				file_selected,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::restore_index(&self.key_config),
+				file_selected,
+				true,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::inspect_file_tree(
 					&self.key_config,
@@ -137,6 +151,10 @@ impl Component for InspectCommitComponent {
 				{
 					self.details.focus(true);
 					self.diff.focus(false);
+				} else if e == self.key_config.keys.restore_workdir {
+					self.restore(ApplyLocation::WorkDir);
+				} else if e == self.key_config.keys.restore_index {
+					self.restore(ApplyLocation::Index);
 				} else if e == self.key_config.keys.open_file_tree {
 					if let Some(commit) = self.commit_id {
 						self.queue.push(InternalEvent::OpenFileTree(
@@ -180,6 +198,7 @@ impl InspectCommitComponent {
 		sender: &Sender<AsyncGitNotification>,
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
+		options: SharedOptions,
 	) -> Self {
 		Self {
 			queue: queue.clone(),
@@ -199,6 +218,7 @@ impl InspectCommitComponent {
 			tags: None,
 			git_diff: AsyncDiff::new(sender),
 			visible: false,
+			options,
 			key_config,
 		}
 	}
@@ -237,6 +257,27 @@ impl InspectCommitComponent {
 		Ok(())
 	}
 
+	/// restores the selected file (or the selected hunk if the diff
+	/// is focused) after confirmation
+	fn restore(&self, location: ApplyLocation) {
+		if let (Some(id), Some(file)) =
+			(self.commit_id, self.details.files().selection_file())
+		{
+			let hunk = if self.diff.focused() {
+				match self.diff.selected_hunk_hash() {
+					Some(hunk) => Some(hunk),
+					None => return,
+				}
+			} else {
+				None
+			};
+
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::RestoreFile(id, file.path, hunk, location),
+			));
+		}
+	}
+
 	/// called when any tree component changed selection
 	pub fn update_diff(&mut self) -> Result<()> {
 		if self.is_visible() {
@@ -244,16 +285,17 @@ impl InspectCommitComponent {
 				if let Some(f) = self.details.files().selection_file()
 				{
 					let diff_params = DiffParams {
-						path: f.path.clone(),
+						path: f.path,
+						old_path: f.old_path,
 						diff_type: DiffType::Commit(id),
-						options: DiffOptions::default(),
+						options: self.options.borrow().diff,
 					};
 
 					if let Some((params, last)) =
 						self.git_diff.last()?
 					{
 						if params == diff_params {
-							self.diff.update(f.path, false, last);
+							self.diff.update(params, last);
 							return Ok(());
 						}
 					}
@@ -1,6 +1,8 @@
+mod apply_patch;
 mod blame_file;
 mod branchlist;
 mod changes;
+mod clean;
 mod command;
 mod commit;
 mod commit_details;
@@ -9,12 +11,18 @@ mod compare_commits;
 mod create_branch;
 mod cred;
 mod diff;
+mod export_patches;
 mod externaleditor;
 mod fetch;
 mod file_find_popup;
 mod filetree;
+mod grep_popup;
 mod help;
+mod ignore_rule;
+mod ignored_files;
+mod index_flags;
 mod inspect_commit;
+mod move_file;
 mod msg;
 mod options_popup;
 mod pull;
@@ -24,6 +32,10 @@ mod rename_branch;
 mod reset;
 mod revision_files;
 mod revision_files_popup;
+mod reword_stash;
+mod save_file;
+mod stage_matching;
+mod stash_branch;
 mod stashmsg;
 mod syntax_text;
 mod tag_commit;
@@ -32,9 +44,11 @@ mod textinput;
 mod utils;
 
 pub use self::filetree::FileTreeComponent;
+pub use apply_patch::ApplyPatchComponent;
 pub use blame_file::BlameFileComponent;
 pub use branchlist::BranchListComponent;
 pub use changes::ChangesComponent;
+pub use clean::CleanComponent;
 pub use command::{CommandInfo, CommandText};
 pub use commit::CommitComponent;
 pub use commit_details::CommitDetailsComponent;
@@ -42,14 +56,20 @@ pub use commitlist::CommitList;
 pub use compare_commits::CompareCommitsComponent;
 pub use create_branch::CreateBranchComponent;
 pub use diff::DiffComponent;
+pub use export_patches::ExportPatchesComponent;
 pub use externaleditor::ExternalEditorComponent;
 pub use fetch::FetchComponent;
 pub use file_find_popup::FileFindPopup;
+pub use grep_popup::GrepPopup;
 pub use help::HelpComponent;
+pub use ignore_rule::IgnoreRuleComponent;
+pub use ignored_files::IgnoredFilesComponent;
+pub use index_flags::IndexFlagsComponent;
 pub use inspect_commit::InspectCommitComponent;
+pub use move_file::MoveFileComponent;
 pub use msg::MsgComponent;
 pub use options_popup::{
-	AppOption, OptionsPopupComponent, SharedOptions,
+	AppOption, Options, OptionsPopupComponent, SharedOptions,
 };
 pub use pull::PullComponent;
 pub use push::PushComponent;
@@ -58,6 +78,10 @@ pub use rename_branch::RenameBranchComponent;
 pub use reset::ConfirmComponent;
 pub use revision_files::RevisionFilesComponent;
 pub use revision_files_popup::RevisionFilesPopup;
+pub use reword_stash::RewordStashComponent;
+pub use save_file::SaveFileComponent;
+pub use stage_matching::{MatchingAction, StageMatchingComponent};
+pub use stash_branch::StashBranchComponent;
 pub use stashmsg::StashMsgComponent;
 pub use syntax_text::SyntaxTextComponent;
 pub use tag_commit::TagCommitComponent;
@@ -0,0 +1,146 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// popup to move/rename a tracked file or folder (like `git mv`)
+pub struct MoveFileComponent {
+	input: TextInputComponent,
+	path: String,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for MoveFileComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for MoveFileComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::move_file_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.enter {
+					self.move_file();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl MoveFileComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				&strings::move_file_popup_title(&key_config, ""),
+				&strings::move_file_popup_msg(&key_config),
+				true,
+			),
+			path: String::new(),
+			key_config,
+		}
+	}
+
+	/// `path` is the repo relative file or folder to move
+	pub fn open(&mut self, path: String) -> Result<()> {
+		self.input.set_title(strings::move_file_popup_title(
+			&self.key_config,
+			&path,
+		));
+		self.input.set_text(path.clone());
+		self.path = path;
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn move_file(&mut self) {
+		let to = self.input.get_text();
+
+		if to.trim().is_empty() || to == self.path {
+			return;
+		}
+
+		match sync::move_path(CWD, &self.path, to) {
+			Ok(()) => {
+				self.hide();
+				self.input.clear();
+				self.queue
+					.push(InternalEvent::Update(NeedsUpdate::ALL));
+			}
+			Err(e) => {
+				log::error!("move file: {}", e);
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("move file error:\n{}", e),
+				));
+			}
+		}
+	}
+}
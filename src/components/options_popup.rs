@@ -12,7 +12,16 @@ use crate::{
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
-use asyncgit::sync::{diff::DiffOptions, ShowUntrackedFilesConfig};
+use asyncgit::{
+	sync::{
+		self,
+		diff::{
+			DiffAlgorithm, DiffOptions, DEFAULT_SIMILARITY_THRESHOLD,
+		},
+		ShowUntrackedFilesConfig,
+	},
+	CWD,
+};
 use crossterm::event::Event;
 use tui::{
 	backend::Backend,
@@ -26,17 +35,48 @@ use tui::{
 #[derive(Clone, Copy, PartialEq)]
 pub enum AppOption {
 	StatusShowUntracked,
+	StatusShowIgnored,
 	DiffIgnoreWhitespaces,
+	DiffIgnoreWhitespaceChange,
+	DiffIgnoreWhitespaceEol,
+	DiffIgnoreBlankLines,
 	DiffContextLines,
 	DiffInterhunkLines,
+	DiffAlgorithm,
+	DiffRenameThreshold,
+	DiffCopyThreshold,
 }
 
 #[derive(Default, Copy, Clone)]
 pub struct Options {
 	pub status_show_untracked: Option<ShowUntrackedFilesConfig>,
+	pub status_show_ignored: bool,
 	pub diff: DiffOptions,
 }
 
+impl Options {
+	/// default options with the diff algorithm seeded from git config
+	pub fn from_git_config() -> Self {
+		let mut options = Self::default();
+
+		// only the status tab uses the thresholds to detect
+		// renames in the working tree (like `git status`)
+		options.diff.rename_threshold =
+			Some(DEFAULT_SIMILARITY_THRESHOLD);
+
+		match sync::diff_algorithm_config(CWD) {
+			Ok(Some(algorithm)) => options.diff.algorithm = algorithm,
+			Ok(None) => (),
+			Err(e) => log::error!("diff.algorithm config: {}", e),
+		}
+
+		options
+	}
+}
+
+/// step size (in percent) when switching similarity thresholds
+const THRESHOLD_STEP: u16 = 10;
+
 pub type SharedOptions = Rc<RefCell<Options>>;
 
 pub struct OptionsPopupComponent {
@@ -89,30 +129,120 @@ impl OptionsPopupComponent {
 			},
 			self.is_select(AppOption::StatusShowUntracked),
 		);
+		self.add_entry(
+			txt,
+			width,
+			"Show ignored",
+			&self.options.borrow().status_show_ignored.to_string(),
+			self.is_select(AppOption::StatusShowIgnored),
+		);
 		Self::add_header(txt, "");
 
+		let diff = self.options.borrow().diff;
+
 		Self::add_header(txt, "Diff");
 		self.add_entry(
 			txt,
 			width,
 			"Ignore whitespaces",
-			&self.options.borrow().diff.ignore_whitespace.to_string(),
+			&diff.ignore_whitespace.to_string(),
 			self.is_select(AppOption::DiffIgnoreWhitespaces),
 		);
+		self.add_entry(
+			txt,
+			width,
+			"Ignore whitespace change",
+			&diff.ignore_whitespace_change.to_string(),
+			self.is_select(AppOption::DiffIgnoreWhitespaceChange),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Ignore whitespace at eol",
+			&diff.ignore_whitespace_eol.to_string(),
+			self.is_select(AppOption::DiffIgnoreWhitespaceEol),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Ignore blank lines",
+			&diff.ignore_blank_lines.to_string(),
+			self.is_select(AppOption::DiffIgnoreBlankLines),
+		);
 		self.add_entry(
 			txt,
 			width,
 			"Context lines",
-			&self.options.borrow().diff.context.to_string(),
+			&diff.context.to_string(),
 			self.is_select(AppOption::DiffContextLines),
 		);
 		self.add_entry(
 			txt,
 			width,
 			"Inter hunk lines",
-			&self.options.borrow().diff.interhunk_lines.to_string(),
+			&diff.interhunk_lines.to_string(),
 			self.is_select(AppOption::DiffInterhunkLines),
 		);
+		self.add_entry(
+			txt,
+			width,
+			"Algorithm",
+			match diff.algorithm {
+				DiffAlgorithm::Myers => "Myers",
+				DiffAlgorithm::Minimal => "Minimal",
+				DiffAlgorithm::Patience => "Patience",
+			},
+			self.is_select(AppOption::DiffAlgorithm),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Rename threshold",
+			&Self::threshold_text(diff.rename_threshold),
+			self.is_select(AppOption::DiffRenameThreshold),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Copy threshold",
+			&Self::threshold_text(diff.copy_threshold),
+			self.is_select(AppOption::DiffCopyThreshold),
+		);
+	}
+
+	fn threshold_text(threshold: Option<u16>) -> String {
+		threshold.map_or_else(
+			|| String::from("Off"),
+			|threshold| format!("{}%", threshold),
+		)
+	}
+
+	const fn switch_threshold(
+		threshold: Option<u16>,
+		right: bool,
+	) -> Option<u16> {
+		match (threshold, right) {
+			(None, true) => Some(THRESHOLD_STEP),
+			(None, false) => None,
+			(Some(t), true) if t >= 100 => Some(100),
+			(Some(t), true) => Some(t + THRESHOLD_STEP),
+			(Some(t), false) if t <= THRESHOLD_STEP => None,
+			(Some(t), false) => Some(t - THRESHOLD_STEP),
+		}
+	}
+
+	const fn switch_algorithm(
+		algorithm: DiffAlgorithm,
+		right: bool,
+	) -> DiffAlgorithm {
+		match (algorithm, right) {
+			(DiffAlgorithm::Myers, true)
+			| (DiffAlgorithm::Patience, false) => DiffAlgorithm::Minimal,
+			(DiffAlgorithm::Minimal, true)
+			| (DiffAlgorithm::Myers, false) => DiffAlgorithm::Patience,
+			(DiffAlgorithm::Patience, true)
+			| (DiffAlgorithm::Minimal, false) => DiffAlgorithm::Myers,
+		}
 	}
 
 	fn is_select(&self, kind: AppOption) -> bool {
@@ -152,36 +282,79 @@ impl OptionsPopupComponent {
 		if up {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
-					AppOption::DiffInterhunkLines
+					AppOption::DiffCopyThreshold
 				}
-				AppOption::DiffIgnoreWhitespaces => {
+				AppOption::StatusShowIgnored => {
 					AppOption::StatusShowUntracked
 				}
-				AppOption::DiffContextLines => {
+				AppOption::DiffIgnoreWhitespaces => {
+					AppOption::StatusShowIgnored
+				}
+				AppOption::DiffIgnoreWhitespaceChange => {
 					AppOption::DiffIgnoreWhitespaces
 				}
+				AppOption::DiffIgnoreWhitespaceEol => {
+					AppOption::DiffIgnoreWhitespaceChange
+				}
+				AppOption::DiffIgnoreBlankLines => {
+					AppOption::DiffIgnoreWhitespaceEol
+				}
+				AppOption::DiffContextLines => {
+					AppOption::DiffIgnoreBlankLines
+				}
 				AppOption::DiffInterhunkLines => {
 					AppOption::DiffContextLines
 				}
+				AppOption::DiffAlgorithm => {
+					AppOption::DiffInterhunkLines
+				}
+				AppOption::DiffRenameThreshold => {
+					AppOption::DiffAlgorithm
+				}
+				AppOption::DiffCopyThreshold => {
+					AppOption::DiffRenameThreshold
+				}
 			};
 		} else {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
+					AppOption::StatusShowIgnored
+				}
+				AppOption::StatusShowIgnored => {
 					AppOption::DiffIgnoreWhitespaces
 				}
 				AppOption::DiffIgnoreWhitespaces => {
+					AppOption::DiffIgnoreWhitespaceChange
+				}
+				AppOption::DiffIgnoreWhitespaceChange => {
+					AppOption::DiffIgnoreWhitespaceEol
+				}
+				AppOption::DiffIgnoreWhitespaceEol => {
+					AppOption::DiffIgnoreBlankLines
+				}
+				AppOption::DiffIgnoreBlankLines => {
 					AppOption::DiffContextLines
 				}
 				AppOption::DiffContextLines => {
 					AppOption::DiffInterhunkLines
 				}
 				AppOption::DiffInterhunkLines => {
+					AppOption::DiffAlgorithm
+				}
+				AppOption::DiffAlgorithm => {
+					AppOption::DiffRenameThreshold
+				}
+				AppOption::DiffRenameThreshold => {
+					AppOption::DiffCopyThreshold
+				}
+				AppOption::DiffCopyThreshold => {
 					AppOption::StatusShowUntracked
 				}
 			};
 		}
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn switch_option(&mut self, right: bool) {
 		if right {
 			match self.selection {
@@ -205,6 +378,11 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().status_show_untracked =
 						untracked;
 				}
+				AppOption::StatusShowIgnored => {
+					let mut options = self.options.borrow_mut();
+					options.status_show_ignored =
+						!options.status_show_ignored;
+				}
 				AppOption::DiffIgnoreWhitespaces => {
 					let old =
 						self.options.borrow().diff.ignore_whitespace;
@@ -224,6 +402,44 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().diff.interhunk_lines =
 						old.saturating_add(1);
 				}
+				AppOption::DiffIgnoreWhitespaceChange => {
+					let mut options = self.options.borrow_mut();
+					options.diff.ignore_whitespace_change =
+						!options.diff.ignore_whitespace_change;
+				}
+				AppOption::DiffIgnoreWhitespaceEol => {
+					let mut options = self.options.borrow_mut();
+					options.diff.ignore_whitespace_eol =
+						!options.diff.ignore_whitespace_eol;
+				}
+				AppOption::DiffIgnoreBlankLines => {
+					let mut options = self.options.borrow_mut();
+					options.diff.ignore_blank_lines =
+						!options.diff.ignore_blank_lines;
+				}
+				AppOption::DiffAlgorithm => {
+					let mut options = self.options.borrow_mut();
+					options.diff.algorithm = Self::switch_algorithm(
+						options.diff.algorithm,
+						true,
+					);
+				}
+				AppOption::DiffRenameThreshold => {
+					let mut options = self.options.borrow_mut();
+					options.diff.rename_threshold =
+						Self::switch_threshold(
+							options.diff.rename_threshold,
+							true,
+						);
+				}
+				AppOption::DiffCopyThreshold => {
+					let mut options = self.options.borrow_mut();
+					options.diff.copy_threshold =
+						Self::switch_threshold(
+							options.diff.copy_threshold,
+							true,
+						);
+				}
 			};
 		} else {
 			match self.selection {
@@ -247,6 +463,11 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().status_show_untracked =
 						untracked;
 				}
+				AppOption::StatusShowIgnored => {
+					let mut options = self.options.borrow_mut();
+					options.status_show_ignored =
+						!options.status_show_ignored;
+				}
 				AppOption::DiffIgnoreWhitespaces => {
 					let old =
 						self.options.borrow().diff.ignore_whitespace;
@@ -266,6 +487,44 @@ impl OptionsPopupComponent {
 					self.options.borrow_mut().diff.interhunk_lines =
 						old.saturating_sub(1);
 				}
+				AppOption::DiffIgnoreWhitespaceChange => {
+					let mut options = self.options.borrow_mut();
+					options.diff.ignore_whitespace_change =
+						!options.diff.ignore_whitespace_change;
+				}
+				AppOption::DiffIgnoreWhitespaceEol => {
+					let mut options = self.options.borrow_mut();
+					options.diff.ignore_whitespace_eol =
+						!options.diff.ignore_whitespace_eol;
+				}
+				AppOption::DiffIgnoreBlankLines => {
+					let mut options = self.options.borrow_mut();
+					options.diff.ignore_blank_lines =
+						!options.diff.ignore_blank_lines;
+				}
+				AppOption::DiffAlgorithm => {
+					let mut options = self.options.borrow_mut();
+					options.diff.algorithm = Self::switch_algorithm(
+						options.diff.algorithm,
+						false,
+					);
+				}
+				AppOption::DiffRenameThreshold => {
+					let mut options = self.options.borrow_mut();
+					options.diff.rename_threshold =
+						Self::switch_threshold(
+							options.diff.rename_threshold,
+							false,
+						);
+				}
+				AppOption::DiffCopyThreshold => {
+					let mut options = self.options.borrow_mut();
+					options.diff.copy_threshold =
+						Self::switch_threshold(
+							options.diff.copy_threshold,
+							false,
+						);
+				}
 			};
 		}
 
@@ -281,7 +540,7 @@ impl DrawableComponent for OptionsPopupComponent {
 		area: Rect,
 	) -> Result<()> {
 		if self.is_visible() {
-			const SIZE: (u16, u16) = (50, 10);
+			const SIZE: (u16, u16) = (50, 17);
 			let area =
 				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
 
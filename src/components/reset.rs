@@ -8,6 +8,7 @@ use crate::{
 	strings, ui,
 };
 use anyhow::Result;
+use asyncgit::sync::ApplyLocation;
 use crossterm::event::Event;
 use std::borrow::Cow;
 use tui::{
@@ -157,6 +158,14 @@ impl ConfirmComponent {
                     strings::confirm_title_reset(),
                     strings::confirm_msg_reset_lines(lines.len()),
                 ),
+                Action::ResetMatching(paths) => (
+                    strings::confirm_title_reset(),
+                    strings::confirm_msg_reset_matching(paths),
+                ),
+                Action::Clean(paths) => (
+                    strings::confirm_title_clean(),
+                    strings::confirm_msg_clean(paths),
+                ),
                 Action::DeleteLocalBranch(branch_ref) => (
                     strings::confirm_title_delete_branch(
                         &self.key_config,
@@ -204,6 +213,22 @@ impl ConfirmComponent {
 				Action::AbortRebase => (
                     strings::confirm_title_abortrebase(),
                     strings::confirm_msg_abortrebase(),
+                ),
+				Action::AbortApplyMailbox => (
+                    strings::confirm_title_abort_apply_mailbox(),
+                    strings::confirm_msg_abort_apply_mailbox(),
+                ),
+				Action::RestoreFile(_, path, hunk, location) => (
+                    strings::confirm_title_restore(),
+                    strings::confirm_msg_restore(path, hunk.is_some(), *location),
+                ),
+				Action::SaveRevisionFile(_, path, target) if path == target => (
+                    strings::confirm_title_restore(),
+                    strings::confirm_msg_restore(path, false, ApplyLocation::WorkDir),
+                ),
+				Action::SaveRevisionFile(_, path, target) => (
+                    strings::confirm_title_save_file(),
+                    strings::confirm_msg_save_file(path, target),
                 ),
             };
 		}
@@ -5,14 +5,14 @@ use super::{
 };
 use crate::{
 	keys::SharedKeyConfig,
-	queue::{InternalEvent, Queue},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
 	strings::{self, order, symbol},
 	ui::{self, common_nav, style::SharedTheme},
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, CommitId, TreeFile},
+	sync::{self, CommitId, GrepMatch, TreeFile},
 	CWD,
 };
 use crossbeam_channel::Sender;
@@ -45,6 +45,7 @@ pub struct RevisionFilesComponent {
 	tree: FileTree,
 	scroll: VerticalScroll,
 	revision: Option<CommitId>,
+	is_head: bool,
 	focus: Focus,
 	key_config: SharedKeyConfig,
 }
@@ -69,6 +70,7 @@ impl RevisionFilesComponent {
 			theme,
 			files: Vec::new(),
 			revision: None,
+			is_head: false,
 			focus: Focus::Tree,
 			key_config,
 		}
@@ -87,6 +89,9 @@ impl RevisionFilesComponent {
 			self.revision = Some(commit);
 		}
 
+		self.is_head =
+			sync::get_head(CWD).map_or(false, |head| head == commit);
+
 		Ok(())
 	}
 
@@ -136,16 +141,94 @@ impl RevisionFilesComponent {
 					.strip_prefix("./")
 					.unwrap_or_default()
 					.to_string(),
+				self.revision,
 			));
 			true
 		})
 	}
 
+	/// repo relative path of the selected file
+	fn selected_path(&self) -> Option<String> {
+		self.tree.selected_file().map(|file| {
+			file.full_path_str()
+				.strip_prefix("./")
+				.unwrap_or_default()
+				.to_string()
+		})
+	}
+
+	fn move_file(&self) -> bool {
+		self.selected_path().map_or(false, |path| {
+			self.queue.push(InternalEvent::OpenMoveFile(path));
+			true
+		})
+	}
+
+	fn remove_cached(&self) -> bool {
+		self.selected_path().map_or(false, |path| {
+			if let Err(e) = sync::remove_from_index(CWD, &path) {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("untrack error:\n{}", e),
+				));
+			}
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+			true
+		})
+	}
+
+	fn save_file(&self) -> bool {
+		match (self.revision, self.selected_path()) {
+			(Some(id), Some(path)) => {
+				self.queue
+					.push(InternalEvent::OpenSaveFile(id, path));
+				true
+			}
+			_ => false,
+		}
+	}
+
+	fn restore_file(&self) -> bool {
+		match (self.revision, self.selected_path()) {
+			(Some(id), Some(path)) => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::SaveRevisionFile(id, path.clone(), path),
+				));
+				true
+			}
+			_ => false,
+		}
+	}
+
 	fn open_finder(&self) {
 		self.queue
 			.push(InternalEvent::OpenFileFinder(self.files.clone()));
 	}
 
+	fn open_grep(&self) {
+		self.queue.push(InternalEvent::OpenGrep(self.revision));
+	}
+
+	/// shows the file of `grep_match` scrolled to its line
+	pub fn grep_match(
+		&mut self,
+		grep_match: &GrepMatch,
+		workdir: bool,
+	) {
+		self.find_file(&Some(Path::new("./").join(&grep_match.path)));
+
+		if workdir {
+			self.current_file
+				.load_workdir_file(grep_match.path.clone());
+		} else {
+			self.selection_changed();
+		}
+		self.current_file.scroll_to_line(grep_match.line);
+
+		self.focus = Focus::File;
+		self.current_file.focus(true);
+		self.focus(true);
+	}
+
 	pub fn find_file(&mut self, file: &Option<PathBuf>) {
 		if let Some(file) = file {
 			self.tree.collapse_but_root();
@@ -271,6 +354,35 @@ impl Component for RevisionFilesComponent {
 				)
 				.order(order::NAV),
 			);
+			if self.is_head {
+				out.push(CommandInfo::new(
+					strings::commands::move_file(&self.key_config),
+					self.tree.selected_file().is_some(),
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::remove_cached(
+						&self.key_config,
+					),
+					self.tree.selected_file().is_some(),
+					true,
+				));
+			}
+			out.push(CommandInfo::new(
+				strings::commands::save_file(&self.key_config),
+				self.tree.selected_file().is_some(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::restore_workdir(&self.key_config),
+				self.tree.selected_file().is_some(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::file_grep(&self.key_config),
+				true,
+				true,
+			));
 			tree_nav_cmds(&self.tree, &self.key_config, out);
 		} else {
 			self.current_file.commands(out, force_all);
@@ -309,11 +421,39 @@ impl Component for RevisionFilesComponent {
 					self.focus(false);
 					return Ok(EventState::Consumed);
 				}
+			} else if (key == self.key_config.keys.move_file
+				|| key == self.key_config.keys.remove_cached)
+				&& is_tree_focused
+				&& self.is_head
+			{
+				let handled = if key == self.key_config.keys.move_file
+				{
+					self.move_file()
+				} else {
+					self.remove_cached()
+				};
+				return Ok(handled.into());
+			} else if (key == self.key_config.keys.save_file
+				|| key == self.key_config.keys.restore_workdir)
+				&& is_tree_focused
+			{
+				let handled = if key == self.key_config.keys.save_file
+				{
+					self.save_file()
+				} else {
+					self.restore_file()
+				};
+				return Ok(handled.into());
 			} else if key == self.key_config.keys.file_find {
 				if is_tree_focused {
 					self.open_finder();
 					return Ok(EventState::Consumed);
 				}
+			} else if key == self.key_config.keys.file_grep {
+				if is_tree_focused {
+					self.open_grep();
+					return Ok(EventState::Consumed);
+				}
 			} else if !is_tree_focused {
 				return self.current_file.event(event);
 			}
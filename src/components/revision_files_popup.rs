@@ -13,7 +13,7 @@ use crate::{
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::Result;
-use asyncgit::sync::CommitId;
+use asyncgit::sync::{CommitId, GrepMatch};
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, widgets::Clear, Frame};
@@ -65,6 +65,16 @@ impl RevisionFilesPopup {
 	pub fn file_finder_update(&mut self, file: &Option<PathBuf>) {
 		self.files.find_file(file);
 	}
+
+	pub fn grep_match(
+		&mut self,
+		grep_match: &GrepMatch,
+		workdir: bool,
+	) {
+		if self.is_visible() {
+			self.files.grep_match(grep_match, workdir);
+		}
+	}
 }
 
 impl DrawableComponent for RevisionFilesPopup {
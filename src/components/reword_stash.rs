@@ -0,0 +1,153 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, CommitId},
+	CWD,
+};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// popup to change the message of an existing stash
+pub struct RewordStashComponent {
+	input: TextInputComponent,
+	stash: Option<CommitId>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for RewordStashComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for RewordStashComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::reword_stash_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.enter {
+					self.reword();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl RewordStashComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				&strings::reword_stash_popup_title(&key_config),
+				&strings::reword_stash_popup_msg(&key_config),
+				true,
+			),
+			stash: None,
+			key_config,
+		}
+	}
+
+	/// opens the popup with the current message of `stash`
+	pub fn open(&mut self, stash: CommitId) -> Result<()> {
+		let msg = sync::get_commit_details(CWD, stash)?
+			.message
+			.map(sync::CommitMessage::combine)
+			.unwrap_or_default();
+
+		self.stash = Some(stash);
+		self.input.set_text(msg);
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn reword(&mut self) {
+		let msg = self.input.get_text().trim();
+
+		if let Some(stash) = self.stash {
+			if msg.is_empty() {
+				return;
+			}
+
+			match sync::stash_reword(CWD, stash, msg) {
+				Ok(_) => {
+					self.hide();
+					self.input.clear();
+					self.queue.push(InternalEvent::Update(
+						NeedsUpdate::ALL,
+					));
+				}
+				Err(e) => {
+					log::error!("reword stash: {}", e);
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("reword stash error:\n{}", e),
+					));
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,144 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{Action, InternalEvent, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::CommitId;
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// popup to save a file of a revision to a chosen path
+pub struct SaveFileComponent {
+	input: TextInputComponent,
+	revision: Option<(CommitId, String)>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for SaveFileComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for SaveFileComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::save_file_confirm(
+					&self.key_config,
+				),
+				!self.input.get_text().trim().is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.enter {
+					self.save_file();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl SaveFileComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				&strings::save_file_popup_title(&key_config, ""),
+				&strings::save_file_popup_msg(&key_config),
+				true,
+			),
+			revision: None,
+			key_config,
+		}
+	}
+
+	/// `path` is the repo relative file in commit `id` to save
+	pub fn open(&mut self, id: CommitId, path: String) -> Result<()> {
+		self.input.set_title(strings::save_file_popup_title(
+			&self.key_config,
+			&path,
+		));
+		self.input.set_text(path.clone());
+		self.revision = Some((id, path));
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn save_file(&mut self) {
+		let target = self.input.get_text().trim();
+
+		if target.is_empty() {
+			return;
+		}
+
+		if let Some((id, path)) = &self.revision {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::SaveRevisionFile(
+					*id,
+					path.clone(),
+					target.to_string(),
+				),
+			));
+		}
+
+		self.hide();
+		self.input.clear();
+	}
+}
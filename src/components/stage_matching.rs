@@ -0,0 +1,341 @@
+use super::{
+	filetree::FileTreeComponent, textinput::TextInputComponent,
+	utils::scroll_vertical::VerticalScroll, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, InputType, ScrollType,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{sync, StatusItem, StatusItemType, CWD};
+use crossterm::event::Event;
+use std::path::Path;
+use tui::{
+	backend::Backend,
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Span, Spans},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// what to do with the workdir changes matching a pattern
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum MatchingAction {
+	Stage,
+	Discard,
+}
+
+pub struct StageMatchingComponent {
+	input: TextInputComponent,
+	action: MatchingAction,
+	items: Vec<StatusItem>,
+	matches: Result<Vec<StatusItem>, String>,
+	scroll: VerticalScroll,
+	visible: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for StageMatchingComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		_rect: Rect,
+	) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(60, 60, f.size());
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(Span::styled(
+						strings::stage_matching_popup_title(
+							&self.key_config,
+							self.action == MatchingAction::Stage,
+						),
+						self.theme.title(true),
+					))
+					.border_style(self.theme.block(true)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[Constraint::Length(3), Constraint::Min(1)]
+						.as_ref(),
+				)
+				.margin(1)
+				.split(area);
+
+			self.input.draw(f, chunks[0])?;
+
+			self.draw_preview(f, chunks[1]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for StageMatchingComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::stage_matching_confirm(
+					&self.key_config,
+					self.action == MatchingAction::Stage,
+				),
+				self.matches
+					.as_ref()
+					.map_or(false, |m| !m.is_empty()),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.exit_popup {
+					self.hide();
+				} else if e == self.key_config.keys.enter {
+					self.confirm();
+				} else if e == self.key_config.keys.popup_down {
+					self.scroll.move_top(ScrollType::Down);
+				} else if e == self.key_config.keys.popup_up {
+					self.scroll.move_top(ScrollType::Up);
+				} else if self.input.event(ev)?.is_consumed() {
+					self.update_matches();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl StageMatchingComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		let mut input = TextInputComponent::new(
+			theme.clone(),
+			key_config.clone(),
+			&strings::stage_matching_pattern_title(&key_config),
+			&strings::stage_matching_pattern_msg(&key_config),
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		input.embed();
+
+		Self {
+			input,
+			action: MatchingAction::Stage,
+			items: Vec::new(),
+			matches: Ok(Vec::new()),
+			scroll: VerticalScroll::new(),
+			visible: false,
+			queue,
+			theme,
+			key_config,
+		}
+	}
+
+	/// `items` are the workdir changes to match against
+	pub fn open(
+		&mut self,
+		action: MatchingAction,
+		items: Vec<StatusItem>,
+	) -> Result<()> {
+		self.action = action;
+		self.items = items;
+		self.update_matches();
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn update_matches(&mut self) {
+		let pattern = self.input.get_text().trim();
+
+		self.matches = if pattern.is_empty() {
+			Ok(Vec::new())
+		} else {
+			sync::filter_by_pathspec(
+				pattern,
+				self.items.clone(),
+				|item| item.path.as_str(),
+			)
+			.map_err(|e| e.to_string())
+		};
+		self.scroll.reset();
+	}
+
+	fn confirm(&mut self) {
+		let matches = match &self.matches {
+			Ok(matches) if !matches.is_empty() => matches.clone(),
+			_ => return,
+		};
+
+		self.hide();
+		self.input.clear();
+		self.matches = Ok(Vec::new());
+
+		match self.action {
+			MatchingAction::Stage => {
+				if let Err(e) = Self::stage(&matches) {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("stage matching error:\n{}", e),
+					));
+				}
+				self.queue
+					.push(InternalEvent::Update(NeedsUpdate::ALL));
+			}
+			MatchingAction::Discard => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::ResetMatching(
+						matches
+							.into_iter()
+							.flat_map(|i| {
+								// the source of a rename is restored
+								// before its target is removed
+								let renamed = i.status
+									== StatusItemType::Renamed;
+								let old_path =
+									i.old_path.filter(|_| renamed);
+								old_path
+									.into_iter()
+									.chain(Some(i.path))
+							})
+							.collect(),
+					),
+				));
+			}
+		}
+	}
+
+	fn stage(items: &[StatusItem]) -> Result<()> {
+		for item in items {
+			let path = Path::new(item.path.as_str());
+			match item.status {
+				StatusItemType::Deleted => {
+					sync::stage_addremoved(CWD, path)?;
+				}
+				StatusItemType::Renamed => {
+					sync::stage_add_file(CWD, path)?;
+					// stage both sides of the rename
+					if let Some(old_path) = &item.old_path {
+						sync::stage_addremoved(
+							CWD,
+							Path::new(old_path),
+						)?;
+					}
+				}
+				_ => sync::stage_add_file(CWD, path)?,
+			}
+		}
+
+		Ok(())
+	}
+
+	fn preview_text(&self) -> Vec<Spans<'_>> {
+		match &self.matches {
+			Err(e) => vec![Spans::from(Span::styled(
+				e.clone(),
+				self.theme.text_danger(),
+			))],
+			Ok(matches) if matches.is_empty() => {
+				vec![Spans::from(Span::styled(
+					strings::stage_matching_no_matches(
+						&self.key_config,
+					),
+					self.theme.text(false, false),
+				))]
+			}
+			Ok(matches) => matches
+				.iter()
+				.map(|item| {
+					Spans::from(vec![
+						Span::styled(
+							format!(
+								"{} ",
+								FileTreeComponent::item_status_char(
+									item.status
+								)
+							),
+							self.theme.item(item.status, false),
+						),
+						Span::styled(
+							item.path.clone(),
+							self.theme.text(true, false),
+						),
+					])
+				})
+				.collect(),
+		}
+	}
+
+	fn draw_preview<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+		let txt = self.preview_text();
+
+		let height = usize::from(r.height);
+		let top = self.scroll.update_no_selection(txt.len(), height);
+
+		f.render_widget(
+			Paragraph::new(
+				txt.into_iter()
+					.skip(top)
+					.take(height)
+					.collect::<Vec<_>>(),
+			),
+			r,
+		);
+
+		self.scroll.draw(f, r, &self.theme);
+	}
+}
@@ -0,0 +1,151 @@
+use super::{
+	textinput::TextInputComponent, visibility_blocking,
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	keys::SharedKeyConfig,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, CommitId},
+	CWD,
+};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// popup to create a branch from a stash (like `git stash branch`)
+pub struct StashBranchComponent {
+	input: TextInputComponent,
+	stash: Option<CommitId>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for StashBranchComponent {
+	fn draw<B: Backend>(
+		&self,
+		f: &mut Frame<B>,
+		rect: Rect,
+	) -> Result<()> {
+		self.input.draw(f, rect)?;
+
+		Ok(())
+	}
+}
+
+impl Component for StashBranchComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::create_branch_confirm_msg(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if e == self.key_config.keys.enter {
+					self.create_branch();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl StashBranchComponent {
+	///
+	pub fn new(
+		queue: Queue,
+		theme: SharedTheme,
+		key_config: SharedKeyConfig,
+	) -> Self {
+		Self {
+			queue,
+			input: TextInputComponent::new(
+				theme,
+				key_config.clone(),
+				&strings::stash_branch_popup_title(&key_config),
+				&strings::create_branch_popup_msg(&key_config),
+				true,
+			),
+			stash: None,
+			key_config,
+		}
+	}
+
+	///
+	pub fn open(&mut self, stash: CommitId) -> Result<()> {
+		self.stash = Some(stash);
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn create_branch(&mut self) {
+		let name = self.input.get_text();
+
+		if let Some(stash) = self.stash {
+			if name.is_empty() {
+				return;
+			}
+
+			let res = sync::stash_branch(CWD, stash, name);
+
+			self.input.clear();
+			self.hide();
+
+			match res {
+				Ok(_) => {
+					self.queue.push(InternalEvent::Update(
+						NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+					));
+					self.queue.push(InternalEvent::TabSwitch);
+				}
+				Err(e) => {
+					log::error!("stash branch: {}", e);
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("stash branch error:\n{}", e),
+					));
+				}
+			}
+		}
+	}
+}
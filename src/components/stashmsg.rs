@@ -11,12 +11,17 @@ use crate::{
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{sync, CWD};
+use asyncgit::{
+	sync::{self, StashPath},
+	CWD,
+};
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct StashMsgComponent {
 	options: StashingOptions,
+	/// files (and hunks) to stash, everything if `None`
+	paths: Option<Vec<StashPath>>,
 	input: TextInputComponent,
 	queue: Queue,
 	key_config: SharedKeyConfig,
@@ -63,17 +68,8 @@ impl Component for StashMsgComponent {
 
 			if let Event::Key(e) = ev {
 				if e == self.key_config.keys.enter {
-					match sync::stash_save(
-						CWD,
-						if self.input.get_text().is_empty() {
-							None
-						} else {
-							Some(self.input.get_text())
-						},
-						self.options.stash_untracked,
-						self.options.keep_index,
-					) {
-						Ok(_) => {
+					match self.stash() {
+						Ok(()) => {
 							self.input.clear();
 							self.hide();
 
@@ -129,6 +125,7 @@ impl StashMsgComponent {
 	) -> Self {
 		Self {
 			options: StashingOptions::default(),
+			paths: None,
 			queue,
 			input: TextInputComponent::new(
 				theme,
@@ -142,7 +139,36 @@ impl StashMsgComponent {
 	}
 
 	///
-	pub fn options(&mut self, options: StashingOptions) {
+	pub fn options(
+		&mut self,
+		options: StashingOptions,
+		paths: Option<Vec<StashPath>>,
+	) {
 		self.options = options;
+		self.paths = paths;
+	}
+
+	fn stash(&self) -> asyncgit::Result<()> {
+		let msg = self.input.get_text();
+		let msg = if msg.is_empty() { None } else { Some(msg) };
+
+		if let Some(paths) = &self.paths {
+			sync::stash_save_paths(
+				CWD,
+				msg,
+				paths,
+				self.options.stash_untracked,
+				self.options.keep_index,
+			)?;
+		} else {
+			sync::stash_save(
+				CWD,
+				msg,
+				self.options.stash_untracked,
+				self.options.keep_index,
+			)?;
+		}
+
+		Ok(())
 	}
 }
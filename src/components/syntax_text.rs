@@ -22,7 +22,11 @@ use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use filetreelist::MoveSelection;
 use itertools::Either;
-use std::{cell::Cell, convert::From, path::Path};
+use std::{
+	cell::Cell,
+	convert::{From, TryInto},
+	path::Path,
+};
 use tui::{
 	backend::Backend,
 	layout::Rect,
@@ -37,6 +41,8 @@ pub struct SyntaxTextComponent {
 	syntax_progress: Option<ProgressPercent>,
 	key_config: SharedKeyConfig,
 	paragraph_state: Cell<ParagraphState>,
+	/// `current_file` is the working tree version
+	workdir: bool,
 	focused: bool,
 	theme: SharedTheme,
 }
@@ -53,6 +59,7 @@ impl SyntaxTextComponent {
 			syntax_progress: None,
 			current_file: None,
 			paragraph_state: Cell::new(ParagraphState::default()),
+			workdir: false,
 			focused: false,
 			key_config,
 			theme,
@@ -102,38 +109,72 @@ impl SyntaxTextComponent {
 
 	///
 	pub fn load_file(&mut self, path: String, item: &TreeFile) {
-		let already_loaded = self
-			.current_file
-			.as_ref()
-			.map(|(current_file, _)| current_file == &path)
-			.unwrap_or_default();
+		let already_loaded = !self.workdir
+			&& self
+				.current_file
+				.as_ref()
+				.map(|(current_file, _)| current_file == &path)
+				.unwrap_or_default();
 
 		if !already_loaded {
 			//TODO: fetch file content async aswell
-			match sync::tree_file_content(CWD, item) {
-				Ok(content) => {
-					let content = tabs_to_spaces(content);
-					self.syntax_progress =
-						Some(ProgressPercent::empty());
-					self.async_highlighting.spawn(
-						AsyncSyntaxJob::new(
-							content.clone(),
-							path.clone(),
-						),
-					);
-
-					self.current_file =
-						Some((path, Either::Right(content)));
-				}
-				Err(e) => {
-					self.current_file = Some((
-						path,
-						Either::Right(format!(
-							"error loading file: {}",
-							e
-						)),
-					));
-				}
+			self.workdir = false;
+			self.set_content(
+				path,
+				sync::tree_file_content(CWD, item),
+			);
+		}
+	}
+
+	/// shows the working tree version of `path` instead of a blob
+	pub fn load_workdir_file(&mut self, path: String) {
+		let content = sync::utils::repo_work_dir(CWD)
+			.map_err(anyhow::Error::from)
+			.and_then(|work_dir| {
+				Ok(std::fs::read_to_string(
+					Path::new(&work_dir).join(&path),
+				)?)
+			});
+
+		self.workdir = true;
+		self.set_content(path, content);
+	}
+
+	/// scrolls `line` to the top once the file is drawn
+	pub fn scroll_to_line(&self, line: usize) {
+		let mut state = self.paragraph_state.get();
+		state.set_scroll(ScrollPos {
+			x: 0,
+			y: line.try_into().unwrap_or(u16::MAX),
+		});
+		self.paragraph_state.set(state);
+	}
+
+	fn set_content<E: std::fmt::Display>(
+		&mut self,
+		path: String,
+		content: std::result::Result<String, E>,
+	) {
+		match content {
+			Ok(content) => {
+				let content = tabs_to_spaces(content);
+				self.syntax_progress = Some(ProgressPercent::empty());
+				self.async_highlighting.spawn(AsyncSyntaxJob::new(
+					content.clone(),
+					path.clone(),
+				));
+
+				self.current_file =
+					Some((path, Either::Right(content)));
+			}
+			Err(e) => {
+				self.current_file = Some((
+					path,
+					Either::Right(format!(
+						"error loading file: {}",
+						e
+					)),
+				));
 			}
 		}
 	}
@@ -200,11 +241,16 @@ impl DrawableComponent for SyntaxTextComponent {
 		);
 
 		let title = format!(
-			"{}{}",
+			"{}{}{}",
 			self.current_file
 				.as_ref()
 				.map(|(name, _)| name.clone())
 				.unwrap_or_default(),
+			if self.workdir {
+				format!(" [{}]", strings::grep_popup_worktree())
+			} else {
+				String::new()
+			},
 			self.syntax_progress
 				.map(|p| format!(" ({}%)", p.progress))
 				.unwrap_or_default()
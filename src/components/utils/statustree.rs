@@ -440,6 +440,7 @@ mod tests {
 			.map(|a| StatusItem {
 				path: String::from(*a),
 				status: StatusItemType::Modified,
+				old_path: None,
 			})
 			.collect::<Vec<_>>()
 	}
@@ -74,10 +74,48 @@ pub struct KeysList {
 	pub abort_merge: KeyEvent,
 	pub undo_commit: KeyEvent,
 	pub stage_unstage_item: KeyEvent,
+	pub diff_search: KeyEvent,
+	pub diff_search_next: KeyEvent,
+	pub diff_search_prev: KeyEvent,
+	pub diff_hunk_next: KeyEvent,
+	pub diff_hunk_prev: KeyEvent,
+	pub diff_copy_patch: KeyEvent,
+	pub diff_copy_hunk_patch: KeyEvent,
+	pub log_export_patches: KeyEvent,
+	pub status_apply_patch: KeyEvent,
+	pub diff_edit_hunk: KeyEvent,
+	pub diff_split_hunk: KeyEvent,
+	pub status_stage_matching: KeyEvent,
+	pub status_reset_matching: KeyEvent,
+	pub status_assume_unchanged: KeyEvent,
+	pub status_skip_worktree: KeyEvent,
+	pub status_index_flags: KeyEvent,
+	pub status_clean: KeyEvent,
+	pub clean_toggle_item: KeyEvent,
+	pub clean_toggle_ignored: KeyEvent,
+	pub move_file: KeyEvent,
+	pub remove_cached: KeyEvent,
+	pub status_toggle_executable: KeyEvent,
+	pub stashing_toggle_item: KeyEvent,
+	pub diff_mark_hunk: KeyEvent,
+	pub stash_branch: KeyEvent,
+	pub stash_reword: KeyEvent,
+	pub restore_workdir: KeyEvent,
+	pub restore_index: KeyEvent,
+	pub blame_parent: KeyEvent,
+	pub blame_back: KeyEvent,
+	pub blame_detection: KeyEvent,
+	pub blame_ignore_revs: KeyEvent,
+	pub blame_heatmap: KeyEvent,
+	pub blame_message: KeyEvent,
+	pub file_grep: KeyEvent,
+	pub grep_worktree: KeyEvent,
+	pub save_file: KeyEvent,
 }
 
 #[rustfmt::skip]
 impl Default for KeysList {
+	#[allow(clippy::too_many_lines)]
 	fn default() -> Self {
 		Self {
 			tab_status: KeyEvent { code: KeyCode::Char('1'), modifiers: KeyModifiers::empty()},
@@ -150,6 +188,43 @@ impl Default for KeysList {
 			open_file_tree: KeyEvent { code: KeyCode::Char('F'), modifiers: KeyModifiers::SHIFT},
 			file_find: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty()},
 			stage_unstage_item: KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::empty()},
+			diff_search: KeyEvent { code: KeyCode::Char('/'), modifiers: KeyModifiers::empty()},
+			diff_search_next: KeyEvent { code: KeyCode::Char('n'), modifiers: KeyModifiers::empty()},
+			diff_search_prev: KeyEvent { code: KeyCode::Char('N'), modifiers: KeyModifiers::SHIFT},
+			diff_hunk_next: KeyEvent { code: KeyCode::Char(']'), modifiers: KeyModifiers::empty()},
+			diff_hunk_prev: KeyEvent { code: KeyCode::Char('['), modifiers: KeyModifiers::empty()},
+			diff_copy_patch: KeyEvent { code: KeyCode::Char('Y'), modifiers: KeyModifiers::SHIFT},
+			diff_copy_hunk_patch: KeyEvent { code: KeyCode::Char('y'), modifiers: KeyModifiers::CONTROL},
+			log_export_patches: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
+			status_apply_patch: KeyEvent { code: KeyCode::Char('I'), modifiers: KeyModifiers::SHIFT},
+			diff_edit_hunk: KeyEvent { code: KeyCode::Char('E'), modifiers: KeyModifiers::SHIFT},
+			diff_split_hunk: KeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT},
+			status_stage_matching: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::CONTROL},
+			status_reset_matching: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::CONTROL},
+			status_assume_unchanged: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::empty()},
+			status_skip_worktree: KeyEvent { code: KeyCode::Char('W'), modifiers: KeyModifiers::SHIFT},
+			status_index_flags: KeyEvent { code: KeyCode::Char('H'), modifiers: KeyModifiers::SHIFT},
+			status_clean: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
+			clean_toggle_item: KeyEvent { code: KeyCode::Char(' '), modifiers: KeyModifiers::empty()},
+			clean_toggle_ignored: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
+			move_file: KeyEvent { code: KeyCode::Char('M'), modifiers: KeyModifiers::SHIFT},
+			remove_cached: KeyEvent { code: KeyCode::Char('K'), modifiers: KeyModifiers::SHIFT},
+			status_toggle_executable: KeyEvent { code: KeyCode::Char('x'), modifiers: KeyModifiers::empty()},
+			stashing_toggle_item: KeyEvent { code: KeyCode::Char(' '), modifiers: KeyModifiers::empty()},
+			diff_mark_hunk: KeyEvent { code: KeyCode::Char(' '), modifiers: KeyModifiers::empty()},
+			stash_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
+			stash_reword: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+			restore_workdir: KeyEvent { code: KeyCode::Char('O'), modifiers: KeyModifiers::SHIFT},
+			restore_index: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::CONTROL},
+			blame_parent: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::empty()},
+			blame_back: KeyEvent { code: KeyCode::Backspace, modifiers: KeyModifiers::empty()},
+			blame_detection: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
+			blame_ignore_revs: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
+			blame_heatmap: KeyEvent { code: KeyCode::Char('h'), modifiers: KeyModifiers::empty()},
+			blame_message: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty()},
+			file_grep: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::empty()},
+			grep_worktree: KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::CONTROL},
+			save_file: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
 		}
 	}
 }
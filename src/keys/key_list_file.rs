@@ -78,6 +78,43 @@ pub struct KeysListFile {
 	pub abort_merge: Option<KeyEvent>,
 	pub undo_commit: Option<KeyEvent>,
 	pub stage_unstage_item: Option<KeyEvent>,
+	pub diff_search: Option<KeyEvent>,
+	pub diff_search_next: Option<KeyEvent>,
+	pub diff_search_prev: Option<KeyEvent>,
+	pub diff_hunk_next: Option<KeyEvent>,
+	pub diff_hunk_prev: Option<KeyEvent>,
+	pub diff_copy_patch: Option<KeyEvent>,
+	pub diff_copy_hunk_patch: Option<KeyEvent>,
+	pub log_export_patches: Option<KeyEvent>,
+	pub status_apply_patch: Option<KeyEvent>,
+	pub diff_edit_hunk: Option<KeyEvent>,
+	pub diff_split_hunk: Option<KeyEvent>,
+	pub status_stage_matching: Option<KeyEvent>,
+	pub status_reset_matching: Option<KeyEvent>,
+	pub status_assume_unchanged: Option<KeyEvent>,
+	pub status_skip_worktree: Option<KeyEvent>,
+	pub status_index_flags: Option<KeyEvent>,
+	pub status_clean: Option<KeyEvent>,
+	pub clean_toggle_item: Option<KeyEvent>,
+	pub clean_toggle_ignored: Option<KeyEvent>,
+	pub move_file: Option<KeyEvent>,
+	pub remove_cached: Option<KeyEvent>,
+	pub status_toggle_executable: Option<KeyEvent>,
+	pub stashing_toggle_item: Option<KeyEvent>,
+	pub diff_mark_hunk: Option<KeyEvent>,
+	pub stash_branch: Option<KeyEvent>,
+	pub stash_reword: Option<KeyEvent>,
+	pub restore_workdir: Option<KeyEvent>,
+	pub restore_index: Option<KeyEvent>,
+	pub blame_parent: Option<KeyEvent>,
+	pub blame_back: Option<KeyEvent>,
+	pub blame_detection: Option<KeyEvent>,
+	pub blame_ignore_revs: Option<KeyEvent>,
+	pub blame_heatmap: Option<KeyEvent>,
+	pub blame_message: Option<KeyEvent>,
+	pub file_grep: Option<KeyEvent>,
+	pub grep_worktree: Option<KeyEvent>,
+	pub save_file: Option<KeyEvent>,
 }
 
 impl KeysListFile {
@@ -89,6 +126,7 @@ impl KeysListFile {
 	}
 
 	#[rustfmt::skip]
+	#[allow(clippy::too_many_lines)]
 	pub fn get_list(self) -> KeysList {
 		let default = KeysList::default();
 
@@ -163,6 +201,43 @@ impl KeysListFile {
 			abort_merge: self.abort_merge.unwrap_or(default.abort_merge),
 			undo_commit: self.undo_commit.unwrap_or(default.undo_commit),
 			stage_unstage_item: self.stage_unstage_item.unwrap_or(default.stage_unstage_item),
+			diff_search: self.diff_search.unwrap_or(default.diff_search),
+			diff_search_next: self.diff_search_next.unwrap_or(default.diff_search_next),
+			diff_search_prev: self.diff_search_prev.unwrap_or(default.diff_search_prev),
+			diff_hunk_next: self.diff_hunk_next.unwrap_or(default.diff_hunk_next),
+			diff_hunk_prev: self.diff_hunk_prev.unwrap_or(default.diff_hunk_prev),
+			diff_copy_patch: self.diff_copy_patch.unwrap_or(default.diff_copy_patch),
+			diff_copy_hunk_patch: self.diff_copy_hunk_patch.unwrap_or(default.diff_copy_hunk_patch),
+			log_export_patches: self.log_export_patches.unwrap_or(default.log_export_patches),
+			status_apply_patch: self.status_apply_patch.unwrap_or(default.status_apply_patch),
+			diff_edit_hunk: self.diff_edit_hunk.unwrap_or(default.diff_edit_hunk),
+			diff_split_hunk: self.diff_split_hunk.unwrap_or(default.diff_split_hunk),
+			status_stage_matching: self.status_stage_matching.unwrap_or(default.status_stage_matching),
+			status_reset_matching: self.status_reset_matching.unwrap_or(default.status_reset_matching),
+			status_assume_unchanged: self.status_assume_unchanged.unwrap_or(default.status_assume_unchanged),
+			status_skip_worktree: self.status_skip_worktree.unwrap_or(default.status_skip_worktree),
+			status_index_flags: self.status_index_flags.unwrap_or(default.status_index_flags),
+			status_clean: self.status_clean.unwrap_or(default.status_clean),
+			clean_toggle_item: self.clean_toggle_item.unwrap_or(default.clean_toggle_item),
+			clean_toggle_ignored: self.clean_toggle_ignored.unwrap_or(default.clean_toggle_ignored),
+			move_file: self.move_file.unwrap_or(default.move_file),
+			remove_cached: self.remove_cached.unwrap_or(default.remove_cached),
+			status_toggle_executable: self.status_toggle_executable.unwrap_or(default.status_toggle_executable),
+			stashing_toggle_item: self.stashing_toggle_item.unwrap_or(default.stashing_toggle_item),
+			diff_mark_hunk: self.diff_mark_hunk.unwrap_or(default.diff_mark_hunk),
+			stash_branch: self.stash_branch.unwrap_or(default.stash_branch),
+			stash_reword: self.stash_reword.unwrap_or(default.stash_reword),
+			restore_workdir: self.restore_workdir.unwrap_or(default.restore_workdir),
+			restore_index: self.restore_index.unwrap_or(default.restore_index),
+			blame_parent: self.blame_parent.unwrap_or(default.blame_parent),
+			blame_back: self.blame_back.unwrap_or(default.blame_back),
+			blame_detection: self.blame_detection.unwrap_or(default.blame_detection),
+			blame_ignore_revs: self.blame_ignore_revs.unwrap_or(default.blame_ignore_revs),
+			blame_heatmap: self.blame_heatmap.unwrap_or(default.blame_heatmap),
+			blame_message: self.blame_message.unwrap_or(default.blame_message),
+			file_grep: self.file_grep.unwrap_or(default.file_grep),
+			grep_worktree: self.grep_worktree.unwrap_or(default.grep_worktree),
+			save_file: self.save_file.unwrap_or(default.save_file),
 		}
 	}
 }
@@ -1,6 +1,14 @@
-use crate::{components::AppOption, tabs::StashingOptions};
-use asyncgit::sync::{
-	diff::DiffLinePosition, CommitId, CommitTags, TreeFile,
+use crate::{
+	components::{AppOption, MatchingAction},
+	tabs::StashingOptions,
+};
+use asyncgit::{
+	sync::{
+		diff::{DiffLinePosition, DiffOptions},
+		ApplyLocation, CommitId, CommitTags, GrepMatch, StashPath,
+		TreeFile,
+	},
+	StatusItem,
 };
 use bitflags::bitflags;
 use std::{
@@ -27,6 +35,8 @@ pub struct ResetItem {
 	pub path: String,
 	/// are talking about a folder here? otherwise it's a single file
 	pub is_folder: bool,
+	/// source of a renamed file, restored as well
+	pub old_path: Option<String>,
 }
 
 ///
@@ -34,15 +44,26 @@ pub enum Action {
 	Reset(ResetItem),
 	ResetHunk(String, u64),
 	ResetLines(String, Vec<DiffLinePosition>),
+	ResetMatching(Vec<String>),
+	Clean(Vec<String>),
 	StashDrop(Vec<CommitId>),
 	StashPop(CommitId),
 	DeleteLocalBranch(String),
 	DeleteRemoteBranch(String),
 	DeleteTag(String),
 	ForcePush(String, bool),
-	PullMerge { incoming: usize, rebase: bool },
+	PullMerge {
+		incoming: usize,
+		rebase: bool,
+	},
 	AbortMerge,
 	AbortRebase,
+	AbortApplyMailbox,
+	/// file (or hunk) of a commit to restore
+	RestoreFile(CommitId, String, Option<u64>, ApplyLocation),
+	/// file of a commit to save to a (repo relative) target path,
+	/// restores the working tree file if target and path are equal
+	SaveRevisionFile(CommitId, String, String),
 }
 
 ///
@@ -54,13 +75,15 @@ pub enum InternalEvent {
 	///
 	ShowErrorMsg(String),
 	///
+	ShowInfoMsg(String),
+	///
 	Update(NeedsUpdate),
 	///
 	StatusLastFileMoved,
 	/// open commit msg input
 	OpenCommit,
 	///
-	PopupStashing(StashingOptions),
+	PopupStashing(StashingOptions, Option<Vec<StashPath>>),
 	///
 	TabSwitch,
 	///
@@ -68,13 +91,15 @@ pub enum InternalEvent {
 	///
 	CompareCommits(CommitId, Option<CommitId>),
 	///
+	ExportPatches(Vec<CommitId>),
+	///
 	SelectCommitInRevlog(CommitId),
 	///
 	TagCommit(CommitId),
 	///
 	Tags,
 	///
-	BlameFile(String),
+	BlameFile(String, Option<CommitId>),
 	///
 	CreateBranch,
 	///
@@ -83,6 +108,8 @@ pub enum InternalEvent {
 	SelectBranch,
 	///
 	OpenExternalEditor(Option<String>),
+	/// edit hunk (file path, hunk hash) in external editor and stage result
+	EditHunk(String, u64, DiffOptions),
 	///
 	Push(String, bool, bool),
 	///
@@ -99,6 +126,28 @@ pub enum InternalEvent {
 	FileFinderChanged(Option<PathBuf>),
 	///
 	FetchRemotes,
+	///
+	OpenApplyPatch,
+	/// stage/discard workdir changes matching a pattern
+	OpenStageMatching(MatchingAction, Vec<StatusItem>),
+	/// list of assume-unchanged/skip-worktree files
+	OpenIndexFlags,
+	///
+	OpenClean,
+	/// ignore rule popup for a file or folder
+	OpenIgnoreRule(String),
+	/// move/rename popup for a tracked file or folder
+	OpenMoveFile(String),
+	/// create branch from stash popup
+	OpenStashBranch(CommitId),
+	/// edit message of a stash popup
+	OpenRewordStash(CommitId),
+	/// open grep popup searching a revision or the working tree
+	OpenGrep(Option<CommitId>),
+	/// open a grep match in the file viewer, from the working tree if set
+	OpenGrepMatch(GrepMatch, bool),
+	/// open popup to save a file of a revision to disk
+	OpenSaveFile(CommitId, String),
 }
 
 /// single threaded simple queue for components to communicate with each other
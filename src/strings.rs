@@ -1,4 +1,6 @@
-use asyncgit::sync::CommitId;
+use asyncgit::sync::{
+	ApplyLocation, CommitId, IgnoreKind, IgnoreTarget,
+};
 
 use crate::keys::SharedKeyConfig;
 
@@ -33,6 +35,12 @@ pub mod symbol {
 	pub const EMPTY_STR: &str = "";
 }
 
+pub fn diff_search_msg() -> String {
+	"search..".to_string()
+}
+pub fn diff_search_no_match() -> String {
+	"no match".to_string()
+}
 pub fn title_branches() -> String {
 	"Branches".to_string()
 }
@@ -45,6 +53,16 @@ pub fn title_status(_key_config: &SharedKeyConfig) -> String {
 pub fn title_diff(_key_config: &SharedKeyConfig) -> String {
 	"Diff: ".to_string()
 }
+pub fn diff_mode_change(
+	_key_config: &SharedKeyConfig,
+	old: u32,
+	new: u32,
+) -> String {
+	format!(" (mode {:o} -> {:o})", old, new)
+}
+pub fn title_ignored(_key_config: &SharedKeyConfig) -> String {
+	"Ignored Files".to_string()
+}
 pub fn title_index(_key_config: &SharedKeyConfig) -> String {
 	"Staged Changes".to_string()
 }
@@ -87,6 +105,9 @@ pub fn msg_opening_editor(_key_config: &SharedKeyConfig) -> String {
 pub fn msg_title_error(_key_config: &SharedKeyConfig) -> String {
 	"Error".to_string()
 }
+pub fn msg_title_info(_key_config: &SharedKeyConfig) -> String {
+	"Info".to_string()
+}
 pub fn commit_title() -> String {
 	"Commit".to_string()
 }
@@ -167,6 +188,59 @@ pub fn confirm_msg_abortrebase() -> String {
 	"This will revert all uncommitted changes. Are you sure?"
 		.to_string()
 }
+pub fn confirm_title_abort_apply_mailbox() -> String {
+	"Abort applying patches?".to_string()
+}
+pub fn confirm_msg_abort_apply_mailbox() -> String {
+	"This resets HEAD and the files changed by the applied patches to where they were before, other changes are kept. Are you sure?"
+		.to_string()
+}
+pub const fn grep_limit_reached() -> &'static str {
+	" (limit reached, refine the pattern)"
+}
+pub fn grep_popup_title() -> String {
+	"Grep".to_string()
+}
+pub fn grep_popup_msg() -> String {
+	"regex, (?i) to ignore case..".to_string()
+}
+pub fn grep_popup_worktree() -> String {
+	"worktree".to_string()
+}
+pub fn blame_message_title() -> String {
+	"Commit".to_string()
+}
+pub fn blame_no_parent() -> String {
+	"the commit that changed this line has no parent".to_string()
+}
+pub fn confirm_title_save_file() -> String {
+	"Save".to_string()
+}
+pub fn confirm_msg_save_file(path: &str, target: &str) -> String {
+	format!(
+		"save '{}' of this revision as '{}'? an existing file gets overwritten.",
+		path, target
+	)
+}
+pub fn confirm_title_restore() -> String {
+	"Restore".to_string()
+}
+pub fn confirm_msg_restore(
+	path: &str,
+	hunk: bool,
+	location: ApplyLocation,
+) -> String {
+	format!(
+		"restore {}'{}' of this revision into the {}? local changes to it get overwritten.",
+		if hunk { "hunk of " } else { "" },
+		path,
+		match location {
+			ApplyLocation::WorkDir => "working tree",
+			ApplyLocation::Index => "index",
+			ApplyLocation::Both => "working tree and index",
+		}
+	)
+}
 pub fn confirm_msg_reset() -> String {
 	"confirm file reset?".to_string()
 }
@@ -176,6 +250,23 @@ pub fn confirm_msg_reset_lines(lines: usize) -> String {
 		lines
 	)
 }
+pub fn confirm_msg_reset_matching(paths: &[String]) -> String {
+	format!(
+		"are you sure you want to discard changes of {} files?\n{}",
+		paths.len(),
+		paths.join("\n")
+	)
+}
+pub fn confirm_title_clean() -> String {
+	"Clean".to_string()
+}
+pub fn confirm_msg_clean(paths: &[String]) -> String {
+	format!(
+		"are you sure you want to delete {} untracked/ignored files? this cannot be undone!\n{}",
+		paths.len(),
+		paths.join("\n")
+	)
+}
 pub fn confirm_msg_stashdrop(
 	_key_config: &SharedKeyConfig,
 	ids: &[CommitId],
@@ -291,6 +382,162 @@ pub fn create_branch_popup_msg(
 ) -> String {
 	"type branch name".to_string()
 }
+pub fn stash_branch_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Branch from Stash".to_string()
+}
+pub fn reword_stash_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Reword Stash".to_string()
+}
+pub fn reword_stash_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"new stash message".to_string()
+}
+pub fn export_patches_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Export Patches".to_string()
+}
+pub fn export_patches_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"target directory (default: workdir)".to_string()
+}
+pub fn export_patches_done_msg(count: usize, dir: &str) -> String {
+	format!("{} patch file(s) written to '{}'", count, dir)
+}
+pub fn apply_patch_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Apply Patch".to_string()
+}
+pub fn apply_patch_file_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Patch File".to_string()
+}
+pub fn apply_patch_file_msg(_key_config: &SharedKeyConfig) -> String {
+	"path to .patch, .diff or mbox file".to_string()
+}
+pub fn apply_patch_mode(_key_config: &SharedKeyConfig) -> String {
+	"Apply to: ".to_string()
+}
+pub fn apply_patch_preview_hint(
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"[{}] to preview, [{}] to change where to apply",
+		key_config.get_hint(key_config.keys.enter),
+		key_config.get_hint(key_config.keys.tab_toggle),
+	)
+}
+pub fn apply_patch_done_msg(rejected: &[String]) -> String {
+	if rejected.is_empty() {
+		"patch applied".to_string()
+	} else {
+		format!(
+			"patch applied, {} rejected:\n{}",
+			rejected.len(),
+			rejected.join("\n")
+		)
+	}
+}
+pub fn apply_mailbox_done_msg() -> String {
+	"all patches applied".to_string()
+}
+pub fn apply_mailbox_stopped_msg(
+	key_config: &SharedKeyConfig,
+	current: usize,
+	total: usize,
+	subject: &str,
+	patch_file: &str,
+) -> String {
+	format!(
+		"patch {}/{} '{}' does not apply.\n\nresolve manually (patch: {}), stage the result and continue [{}] or abort [{}]",
+		current,
+		total,
+		subject,
+		patch_file,
+		key_config.get_hint(key_config.keys.rebase_branch),
+		key_config.get_hint(key_config.keys.abort_merge),
+	)
+}
+pub fn stage_matching_popup_title(
+	_key_config: &SharedKeyConfig,
+	stage: bool,
+) -> String {
+	if stage {
+		"Stage Matching".to_string()
+	} else {
+		"Discard Matching".to_string()
+	}
+}
+pub fn stage_matching_pattern_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Pattern".to_string()
+}
+pub fn stage_matching_pattern_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"pathspec or glob (e.g. src/**/*.rs)".to_string()
+}
+pub fn stage_matching_no_matches(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"no matching changes".to_string()
+}
+pub fn clean_popup_title(
+	_key_config: &SharedKeyConfig,
+	include_ignored: bool,
+) -> String {
+	if include_ignored {
+		"Clean untracked and ignored files".to_string()
+	} else {
+		"Clean untracked files".to_string()
+	}
+}
+pub fn ignore_rule_popup_title(
+	_key_config: &SharedKeyConfig,
+	path: &str,
+) -> String {
+	format!("Ignore '{}'", path)
+}
+pub fn ignore_rule_preview_title(
+	_key_config: &SharedKeyConfig,
+	count: usize,
+) -> String {
+	format!("Untracked files hidden by this rule ({}):", count)
+}
+pub const fn ignore_kind_text(kind: IgnoreKind) -> &'static str {
+	match kind {
+		IgnoreKind::Path => "Path",
+		IgnoreKind::Extension => "Extension",
+		IgnoreKind::Directory => "Directory",
+	}
+}
+pub const fn ignore_target_text(
+	target: IgnoreTarget,
+) -> &'static str {
+	match target {
+		IgnoreTarget::Root => ".gitignore",
+		IgnoreTarget::Nested => "nested .gitignore",
+		IgnoreTarget::InfoExclude => ".git/info/exclude",
+		IgnoreTarget::Global => "global excludes",
+	}
+}
+pub fn index_flags_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Assume-Unchanged (A) / Skip-Worktree (S)".to_string()
+}
+pub fn index_flags_empty(_key_config: &SharedKeyConfig) -> String {
+	"no files with assume-unchanged or skip-worktree set".to_string()
+}
 pub fn username_popup_title(_key_config: &SharedKeyConfig) -> String {
 	"Username".to_string()
 }
@@ -315,6 +562,25 @@ pub fn rename_branch_popup_msg(
 	"new branch name".to_string()
 }
 
+pub fn move_file_popup_title(
+	_key_config: &SharedKeyConfig,
+	path: &str,
+) -> String {
+	format!("Move '{}'", path)
+}
+pub fn move_file_popup_msg(_key_config: &SharedKeyConfig) -> String {
+	"new path".to_string()
+}
+pub fn save_file_popup_title(
+	_key_config: &SharedKeyConfig,
+	path: &str,
+) -> String {
+	format!("Save '{}'", path)
+}
+pub fn save_file_popup_msg(_key_config: &SharedKeyConfig) -> String {
+	"target path".to_string()
+}
+
 pub mod commit {
 	use crate::keys::SharedKeyConfig;
 
@@ -392,6 +658,38 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn file_grep(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Grep [{}]",
+				key_config.get_hint(key_config.keys.file_grep)
+			),
+			"search file contents for a regex",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn grep_confirm(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search/Open [{}]",
+				key_config.get_hint(key_config.keys.enter)
+			),
+			"search for the pattern or open the selected match",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn grep_worktree(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Worktree [{}]",
+				key_config.get_hint(key_config.keys.grep_worktree)
+			),
+			"toggle searching the working tree instead of the revision",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn toggle_tabs_direct(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -554,6 +852,42 @@ pub mod commands {
 			CMD_GROUP_DIFF,
 		)
 	}
+	pub fn diff_hunk_split(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Split hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_split_hunk),
+			),
+			"split selected hunk at unchanged lines into smaller hunks",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_mark_hunk(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Mark hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_mark_hunk),
+			),
+			"toggle stashing only the marked hunks of this file",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_edit(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_edit_hunk),
+			),
+			"edit selected hunk in external editor and stage the result",
+			CMD_GROUP_DIFF,
+		)
+	}
 	pub fn diff_lines_revert(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -590,6 +924,67 @@ pub mod commands {
 			CMD_GROUP_DIFF,
 		)
 	}
+	pub fn diff_copy_patch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy patch [{}]",
+				key_config.get_hint(key_config.keys.diff_copy_patch),
+			),
+			"copy diff of file to clipboard as patch",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_copy_hunk_patch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy hunk patch [{}]",
+				key_config
+					.get_hint(key_config.keys.diff_copy_hunk_patch),
+			),
+			"copy selected hunk to clipboard as patch",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_search(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config.get_hint(key_config.keys.diff_search),
+			),
+			"search text in diff",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_search_next_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next/prev match [{}/{}]",
+				key_config.get_hint(key_config.keys.diff_search_next),
+				key_config.get_hint(key_config.keys.diff_search_prev),
+			),
+			"jump to next or previous search match",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_next_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next/prev hunk [{}/{}]",
+				key_config.get_hint(key_config.keys.diff_hunk_next),
+				key_config.get_hint(key_config.keys.diff_hunk_prev),
+			),
+			"jump to next or previous hunk header",
+			CMD_GROUP_DIFF,
+		)
+	}
 	pub fn diff_hunk_remove(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -671,89 +1066,355 @@ pub mod commands {
 		)
 	}
 
-	pub fn select_staging(
+	pub fn continue_apply_mailbox(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"To stage [{}]",
-				key_config.get_hint(key_config.keys.toggle_workarea),
+				"Continue apply [{}]",
+				key_config.get_hint(key_config.keys.rebase_branch),
 			),
-			"focus/select staging area",
+			"commit staged changes for the stopped patch and apply the remaining ones",
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn select_unstaged(
+
+	pub fn abort_apply_mailbox(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"To unstaged [{}]",
-				key_config.get_hint(key_config.keys.toggle_workarea),
+				"Abort apply [{}]",
+				key_config.get_hint(key_config.keys.abort_merge),
 			),
-			"focus/select unstaged area",
+			"abort applying patches",
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn undo_commit(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn status_apply_patch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Undo Commit [{}]",
-				key_config.get_hint(key_config.keys.undo_commit),
+				"Apply Patch [{}]",
+				key_config
+					.get_hint(key_config.keys.status_apply_patch),
 			),
-			"undo last commit",
+			"apply patch or mailbox file",
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn commit_open(key_config: &SharedKeyConfig) -> CommandText {
+
+	pub fn apply_patch_preview(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Commit [{}]",
-				key_config.get_hint(key_config.keys.open_commit),
+				"Preview [{}]",
+				key_config.get_hint(key_config.keys.enter),
 			),
-			"open commit popup (available in non-empty stage)",
-			CMD_GROUP_COMMIT,
+			"preview patch hunks",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn commit_open_editor(
+
+	pub fn stage_matching(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Open editor [{}]",
+				"Stage matching [{}]",
 				key_config
-					.get_hint(key_config.keys.open_commit_editor),
+					.get_hint(key_config.keys.status_stage_matching),
 			),
-			"open commit editor (available in commit popup)",
-			CMD_GROUP_COMMIT,
+			"stage all changes matching a pathspec/glob",
+			CMD_GROUP_CHANGES,
 		)
 	}
-	pub fn commit_enter(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn reset_matching(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Commit [{}]",
-				key_config.get_hint(key_config.keys.enter),
+				"Reset matching [{}]",
+				key_config
+					.get_hint(key_config.keys.status_reset_matching),
 			),
-			"commit (available when commit message is non-empty)",
-			CMD_GROUP_COMMIT,
+			"discard all changes matching a pathspec/glob",
+			CMD_GROUP_CHANGES,
 		)
-		.hide_help()
 	}
-	pub fn commit_amend(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn force_add_item(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Amend [{}]",
-				key_config.get_hint(key_config.keys.commit_amend),
+				"Force add [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
 			),
-			"amend last commit (available in commit popup)",
-			CMD_GROUP_COMMIT,
+			"stage ignored file or folder (like `git add -f`)",
+			CMD_GROUP_CHANGES,
 		)
 	}
-	pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn ignore_rule_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Edit [{}]",
-				key_config.get_hint(key_config.keys.edit_file),
+				"Add rule [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"add ignore rule to the chosen file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn status_clean(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Clean [{}]",
+				key_config.get_hint(key_config.keys.status_clean),
+			),
+			"delete untracked (and ignored) files",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn clean_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Delete checked [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"delete all checked files",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn clean_toggle_item(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Check/Uncheck [{}]",
+				key_config
+					.get_hint(key_config.keys.clean_toggle_item),
+			),
+			"check or uncheck selected file or folder",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn clean_toggle_ignored(
+		key_config: &SharedKeyConfig,
+		include_ignored: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} ignored [{}]",
+				if include_ignored {
+					"Exclude"
+				} else {
+					"Include"
+				},
+				key_config
+					.get_hint(key_config.keys.clean_toggle_ignored),
+			),
+			"include or exclude ignored files",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn assume_unchanged(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Assume unchanged [{}]",
+				key_config.get_hint(
+					key_config.keys.status_assume_unchanged
+				),
+			),
+			"hide local changes of file by setting assume-unchanged",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn skip_worktree(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Skip worktree [{}]",
+				key_config
+					.get_hint(key_config.keys.status_skip_worktree),
+			),
+			"hide local changes of file by setting skip-worktree",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn status_index_flags(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Hidden files [{}]",
+				key_config
+					.get_hint(key_config.keys.status_index_flags),
+			),
+			"list files with assume-unchanged or skip-worktree set",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn toggle_assume_unchanged(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle assume-unchanged [{}]",
+				key_config.get_hint(
+					key_config.keys.status_assume_unchanged
+				),
+			),
+			"set or clear assume-unchanged of selected file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn toggle_skip_worktree(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle skip-worktree [{}]",
+				key_config
+					.get_hint(key_config.keys.status_skip_worktree),
+			),
+			"set or clear skip-worktree of selected file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn stage_matching_confirm(
+		key_config: &SharedKeyConfig,
+		stage: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if stage { "Stage" } else { "Discard" },
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"apply to all matching changes",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn apply_patch_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"apply patch",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn apply_patch_mode(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Mode [{}]",
+				key_config.get_hint(key_config.keys.tab_toggle),
+			),
+			"switch between workdir, index, both and mailbox (commits)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn select_staging(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"To stage [{}]",
+				key_config.get_hint(key_config.keys.toggle_workarea),
+			),
+			"focus/select staging area",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_unstaged(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"To unstaged [{}]",
+				key_config.get_hint(key_config.keys.toggle_workarea),
+			),
+			"focus/select unstaged area",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn undo_commit(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Undo Commit [{}]",
+				key_config.get_hint(key_config.keys.undo_commit),
+			),
+			"undo last commit",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn commit_open(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commit [{}]",
+				key_config.get_hint(key_config.keys.open_commit),
+			),
+			"open commit popup (available in non-empty stage)",
+			CMD_GROUP_COMMIT,
+		)
+	}
+	pub fn commit_open_editor(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open editor [{}]",
+				key_config
+					.get_hint(key_config.keys.open_commit_editor),
+			),
+			"open commit editor (available in commit popup)",
+			CMD_GROUP_COMMIT,
+		)
+	}
+	pub fn commit_enter(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commit [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"commit (available when commit message is non-empty)",
+			CMD_GROUP_COMMIT,
+		)
+		.hide_help()
+	}
+	pub fn commit_amend(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Amend [{}]",
+				key_config.get_hint(key_config.keys.commit_amend),
+			),
+			"amend last commit (available in commit popup)",
+			CMD_GROUP_COMMIT,
+		)
+	}
+	pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit [{}]",
+				key_config.get_hint(key_config.keys.edit_file),
 			),
 			"edit the currently selected file in an external editor",
 			CMD_GROUP_CHANGES,
@@ -823,6 +1484,76 @@ pub mod commands {
 			CMD_GROUP_CHANGES,
 		)
 	}
+	pub fn toggle_executable(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle +x [{}]",
+				key_config
+					.get_hint(key_config.keys.status_toggle_executable),
+			),
+			"toggle executable bit of file in the index (like git update-index --chmod)",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn move_file(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Move [{}]",
+				key_config.get_hint(key_config.keys.move_file),
+			),
+			"move or rename tracked file or path (like git mv)",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn save_file(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Save [{}]",
+				key_config.get_hint(key_config.keys.save_file),
+			),
+			"save selected file of this revision to a path",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn save_file_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Save [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"save file to the entered path",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn move_file_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Move [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"move file",
+			CMD_GROUP_CHANGES,
+		)
+		.hide_help()
+	}
+	pub fn remove_cached(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Untrack [{}]",
+				key_config.get_hint(key_config.keys.remove_cached),
+			),
+			"remove file or path from index but keep it in the workdir (like git rm --cached)",
+			CMD_GROUP_CHANGES,
+		)
+	}
 
 	pub fn diff_focus_left(
 		key_config: &SharedKeyConfig,
@@ -909,6 +1640,19 @@ pub mod commands {
 			CMD_GROUP_STASHING,
 		)
 	}
+	pub fn stashing_toggle_item(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Toggle File [{}]",
+				key_config
+					.get_hint(key_config.keys.stashing_toggle_item),
+			),
+			"toggle stashing the selected file or folder",
+			CMD_GROUP_STASHING,
+		)
+	}
 	pub fn stashing_confirm_msg(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -963,6 +1707,43 @@ pub mod commands {
 			CMD_GROUP_STASHES,
 		)
 	}
+	pub fn stashlist_branch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Branch [{}]",
+				key_config.get_hint(key_config.keys.stash_branch),
+			),
+			"create branch at the stash base, apply and drop the stash there",
+			CMD_GROUP_STASHES,
+		)
+	}
+	pub fn stashlist_reword(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reword [{}]",
+				key_config.get_hint(key_config.keys.stash_reword),
+			),
+			"change the message of the selected stash",
+			CMD_GROUP_STASHES,
+		)
+	}
+	pub fn reword_stash_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reword [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"store stash with new message",
+			CMD_GROUP_STASHES,
+		)
+		.hide_help()
+	}
 	pub fn stashlist_inspect(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -988,6 +1769,75 @@ pub mod commands {
 		)
 	}
 
+	pub fn blame_parent(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Blame Parent [{}]",
+				key_config.get_hint(key_config.keys.blame_parent),
+			),
+			"blame the file in the parent of the commit that changed the selected line",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn blame_back(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Back [{}]",
+				key_config.get_hint(key_config.keys.blame_back),
+			),
+			"go back to the previously blamed revision",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn blame_detection(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Detect moves [{}]",
+				key_config.get_hint(key_config.keys.blame_detection),
+			),
+			"cycle detection of moved and copied lines (off, moves within the file, copies from files of the same commit)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn blame_ignore_revs(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Ignore revs [{}]",
+				key_config
+					.get_hint(key_config.keys.blame_ignore_revs),
+			),
+			"toggle skipping commits listed in blame.ignoreRevsFile",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn blame_heatmap(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Heatmap [{}]",
+				key_config.get_hint(key_config.keys.blame_heatmap),
+			),
+			"toggle tinting line metadata by commit age",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn blame_message(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Message [{}]",
+				key_config.get_hint(key_config.keys.blame_message),
+			),
+			"toggle full message of the commit of the selected line",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn commit_details_open(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1023,6 +1873,55 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn log_export_patches(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Export Patches [{}]",
+				key_config
+					.get_hint(key_config.keys.log_export_patches),
+			),
+			"write marked (or selected) commits as patch files",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn export_patches_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Export [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"export patches",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn restore_workdir(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Restore [{}]",
+				key_config.get_hint(key_config.keys.restore_workdir),
+			),
+			"restore selected file (or hunk) of this revision into the working tree",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn restore_index(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Restore to Index [{}]",
+				key_config.get_hint(key_config.keys.restore_index),
+			),
+			"restore selected file (or hunk) of this revision into the index",
+			CMD_GROUP_LOG,
+		)
+	}
 	pub fn inspect_file_tree(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -17,7 +17,10 @@ use crate::{
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::Result;
-use asyncgit::{sync, CWD};
+use asyncgit::{
+	sync::{self, GrepMatch},
+	CWD,
+};
 use crossbeam_channel::Sender;
 
 pub struct FilesTab {
@@ -74,6 +77,16 @@ impl FilesTab {
 	pub fn file_finder_update(&mut self, file: &Option<PathBuf>) {
 		self.files.find_file(file);
 	}
+
+	pub fn grep_match(
+		&mut self,
+		grep_match: &GrepMatch,
+		workdir: bool,
+	) {
+		if self.is_visible() {
+			self.files.grep_match(grep_match, workdir);
+		}
+	}
 }
 
 impl DrawableComponent for FilesTab {
@@ -273,6 +273,18 @@ impl Component for Revlog {
 				} else if k == self.key_config.keys.tags {
 					self.queue.push(InternalEvent::Tags);
 					return Ok(EventState::Consumed);
+				} else if k == self.key_config.keys.log_export_patches
+				{
+					let ids = if self.list.marked_count() > 0 {
+						self.list.marked().to_vec()
+					} else {
+						self.selected_commit().into_iter().collect()
+					};
+					if !ids.is_empty() {
+						self.queue
+							.push(InternalEvent::ExportPatches(ids));
+						return Ok(EventState::Consumed);
+					}
 				} else if k == self.key_config.keys.compare_commits
 					&& self.list.marked_count() > 0
 				{
@@ -359,6 +371,13 @@ impl Component for Revlog {
 			self.visible || force_all,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::log_export_patches(&self.key_config),
+			self.list.marked_count() > 0
+				|| self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::open_tags_popup(&self.key_config),
 			true,
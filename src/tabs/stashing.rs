@@ -2,8 +2,8 @@ use crate::{
 	accessors,
 	components::{
 		command_pump, event_pump, visibility_blocking,
-		CommandBlocking, CommandInfo, Component, DrawableComponent,
-		EventState, FileTreeComponent,
+		CommandBlocking, CommandInfo, Component, DiffComponent,
+		DrawableComponent, EventState, FileTreeComponent,
 	},
 	keys::SharedKeyConfig,
 	queue::{InternalEvent, Queue},
@@ -12,12 +12,21 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, status::StatusType},
-	AsyncGitNotification, AsyncStatus, StatusParams, CWD,
+	sync::{
+		self,
+		diff::{DiffOptions, FileDiff},
+		status::StatusType,
+		StashPath,
+	},
+	AsyncDiff, AsyncGitNotification, AsyncStatus, DiffParams,
+	DiffType, StatusItemType, StatusParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use std::borrow::Cow;
+use std::{
+	borrow::Cow,
+	collections::{HashMap, HashSet},
+};
 use tui::{
 	layout::{Alignment, Constraint, Direction, Layout},
 	text::{Span, Spans},
@@ -32,6 +41,11 @@ pub struct StashingOptions {
 
 pub struct Stashing {
 	index: FileTreeComponent,
+	diff: DiffComponent,
+	git_diff: AsyncDiff,
+	/// marked hunks per file, only these hunks get stashed
+	marked_hunks: HashMap<String, HashSet<u64>>,
+	focus_diff: bool,
 	visible: bool,
 	options: StashingOptions,
 	theme: SharedTheme,
@@ -41,7 +55,7 @@ pub struct Stashing {
 }
 
 impl Stashing {
-	accessors!(self, [index]);
+	accessors!(self, [index, diff]);
 
 	///
 	pub fn new(
@@ -50,14 +64,29 @@ impl Stashing {
 		theme: SharedTheme,
 		key_config: SharedKeyConfig,
 	) -> Self {
+		let mut index = FileTreeComponent::new(
+			&strings::stashing_files_title(&key_config),
+			true,
+			Some(queue.clone()),
+			theme.clone(),
+			key_config.clone(),
+		);
+		index.set_checklist(true);
+
+		let mut diff = DiffComponent::new(
+			queue.clone(),
+			theme.clone(),
+			key_config.clone(),
+			true,
+		);
+		diff.set_hunk_marking(true);
+
 		Self {
-			index: FileTreeComponent::new(
-				&strings::stashing_files_title(&key_config),
-				true,
-				Some(queue.clone()),
-				theme.clone(),
-				key_config.clone(),
-			),
+			index,
+			diff,
+			git_diff: AsyncDiff::new(sender),
+			marked_hunks: HashMap::new(),
+			focus_diff: false,
 			visible: false,
 			options: StashingOptions {
 				keep_index: false,
@@ -75,7 +104,11 @@ impl Stashing {
 		if self.is_visible() {
 			self.git_status
 				//TODO: support options
-				.fetch(&StatusParams::new(StatusType::Both, None))?;
+				.fetch(&StatusParams::new(
+					StatusType::Both,
+					None,
+					DiffOptions::default(),
+				))?;
 		}
 
 		Ok(())
@@ -83,7 +116,7 @@ impl Stashing {
 
 	///
 	pub fn anything_pending(&self) -> bool {
-		self.git_status.is_pending()
+		self.git_status.is_pending() || self.git_diff.is_pending()
 	}
 
 	///
@@ -91,14 +124,145 @@ impl Stashing {
 		&mut self,
 		ev: AsyncGitNotification,
 	) -> Result<()> {
-		if self.is_visible() && ev == AsyncGitNotification::Status {
-			let status = self.git_status.last()?;
-			self.index.update(&status.items)?;
+		if self.is_visible() {
+			match ev {
+				AsyncGitNotification::Status => {
+					let status = self.git_status.last()?;
+					self.index.update(&status.items)?;
+					self.marked_hunks.retain(|path, _| {
+						status.items.iter().any(|i| &i.path == path)
+					});
+					self.update_diff()?;
+				}
+				AsyncGitNotification::Diff => self.update_diff()?,
+				_ => (),
+			}
 		}
 
 		Ok(())
 	}
 
+	fn update_diff(&mut self) -> Result<()> {
+		if let Some(item) = self.index.selection_file() {
+			let diff_params = DiffParams {
+				path: item.path.clone(),
+				old_path: None,
+				diff_type: DiffType::WorkDir,
+				options: DiffOptions::default(),
+			};
+
+			if self.diff.current() == (item.path, false) {
+				if let Some((params, last)) = self.git_diff.last()? {
+					if params == diff_params {
+						self.show_diff(params, last);
+					} else {
+						self.request_diff(diff_params)?;
+					}
+				}
+			} else {
+				self.request_diff(diff_params)?;
+			}
+		} else {
+			self.diff.clear(false);
+		}
+
+		Ok(())
+	}
+
+	fn request_diff(
+		&mut self,
+		diff_params: DiffParams,
+	) -> Result<()> {
+		if let Some(diff) =
+			self.git_diff.request(diff_params.clone())?
+		{
+			self.show_diff(diff_params, diff);
+		} else {
+			self.diff.clear(true);
+		}
+
+		Ok(())
+	}
+
+	fn show_diff(&mut self, params: DiffParams, diff: FileDiff) {
+		let marked = self
+			.marked_hunks
+			.get(&params.path)
+			.map(|marked| {
+				marked
+					.iter()
+					.filter(|hash| {
+						diff.hunks
+							.iter()
+							.any(|h| h.header_hash == **hash)
+					})
+					.copied()
+					.collect()
+			})
+			.unwrap_or_default();
+
+		self.diff.update(params, diff);
+		self.diff.set_marked_hunks(marked);
+	}
+
+	fn store_marked_hunks(&mut self) {
+		let (path, _) = self.diff.current();
+		let marked = self.diff.marked_hunks();
+		if marked.is_empty() {
+			self.marked_hunks.remove(&path);
+		} else {
+			self.marked_hunks.insert(path, marked);
+		}
+	}
+
+	fn switch_focus(&mut self, focus_diff: bool) -> Result<()> {
+		self.focus_diff = focus_diff;
+		self.index.focus(!focus_diff);
+		self.diff.focus(focus_diff);
+		self.update_diff()
+	}
+
+	/// the files (and hunks) to stash if not everything gets stashed
+	fn stash_paths(&self) -> Option<Vec<StashPath>> {
+		let checked = self.index.checked_files();
+
+		if checked.len() == self.index.files().len()
+			&& self.marked_hunks.is_empty()
+		{
+			return None;
+		}
+
+		Some(
+			checked
+				.into_iter()
+				.flat_map(|item| {
+					// both sides of a rename have to be stashed
+					let renamed =
+						item.status == StatusItemType::Renamed;
+					let old_path = item
+						.old_path
+						.filter(|_| renamed)
+						.map(|path| StashPath { path, hunks: None });
+
+					let hunks = self
+						.marked_hunks
+						.get(&item.path)
+						.map(|hunks| hunks.iter().copied().collect());
+
+					std::iter::once(StashPath {
+						path: item.path,
+						hunks,
+					})
+					.chain(old_path)
+				})
+				.collect(),
+		)
+	}
+
+	fn can_stash(&self) -> bool {
+		!self.index.checked_files().is_empty()
+	}
+
 	fn get_option_text(&self) -> Vec<Spans> {
 		let bracket_open = Span::raw(Cow::from("["));
 		let bracket_close = Span::raw(Cow::from("]"));
@@ -142,7 +306,11 @@ impl DrawableComponent for Stashing {
 		let chunks = Layout::default()
 			.direction(Direction::Horizontal)
 			.constraints(
-				[Constraint::Min(1), Constraint::Length(22)].as_ref(),
+				[
+					Constraint::Percentage(40),
+					Constraint::Percentage(60),
+				]
+				.as_ref(),
 			)
 			.split(rect);
 
@@ -163,6 +331,7 @@ impl DrawableComponent for Stashing {
 		);
 
 		self.index.draw(f, chunks[0])?;
+		self.diff.draw(f, right_chunks[1])?;
 
 		Ok(())
 	}
@@ -183,9 +352,36 @@ impl Component for Stashing {
 
 			out.push(CommandInfo::new(
 				strings::commands::stashing_save(&self.key_config),
-				self.visible && !self.index.is_empty(),
+				self.visible && self.can_stash(),
 				self.visible || force_all,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::stashing_toggle_item(
+					&self.key_config,
+				),
+				!self.index.is_empty(),
+				(self.visible && !self.focus_diff) || force_all,
+			));
+			out.push(
+				CommandInfo::new(
+					strings::commands::diff_focus_left(
+						&self.key_config,
+					),
+					true,
+					(self.visible && self.focus_diff) || force_all,
+				)
+				.order(strings::order::NAV),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::diff_focus_right(
+						&self.key_config,
+					),
+					self.index.is_file_seleted(),
+					(self.visible && !self.focus_diff) || force_all,
+				)
+				.order(strings::order::NAV),
+			);
 			out.push(CommandInfo::new(
 				strings::commands::stashing_toggle_indexed(
 					&self.key_config,
@@ -213,17 +409,39 @@ impl Component for Stashing {
 			if event_pump(ev, self.components_mut().as_mut_slice())?
 				.is_consumed()
 			{
+				if self.focus_diff {
+					self.store_marked_hunks();
+				}
+				self.update_diff()?;
 				return Ok(EventState::Consumed);
 			}
 
 			if let Event::Key(k) = ev {
 				return if k == self.key_config.keys.stashing_save
-					&& !self.index.is_empty()
+					&& self.can_stash()
 				{
 					self.queue.push(InternalEvent::PopupStashing(
 						self.options,
+						self.stash_paths(),
 					));
 
+					Ok(EventState::Consumed)
+				} else if k
+					== self.key_config.keys.stashing_toggle_item
+					&& !self.focus_diff
+				{
+					self.index.toggle_checked();
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.focus_right
+					&& !self.focus_diff
+					&& self.index.is_file_seleted()
+				{
+					self.switch_focus(true)?;
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.focus_left
+					&& self.focus_diff
+				{
+					self.switch_focus(false)?;
 					Ok(EventState::Consumed)
 				} else if k
 					== self.key_config.keys.stashing_toggle_index
@@ -90,6 +90,18 @@ impl StashList {
 		}
 	}
 
+	fn branch_stash(&self) {
+		if let Some(e) = self.list.selected_entry() {
+			self.queue.push(InternalEvent::OpenStashBranch(e.id));
+		}
+	}
+
+	fn reword_stash(&self) {
+		if let Some(e) = self.list.selected_entry() {
+			self.queue.push(InternalEvent::OpenRewordStash(e.id));
+		}
+	}
+
 	fn inspect(&mut self) {
 		if let Some(e) = self.list.selected_entry() {
 			self.queue.push(InternalEvent::InspectCommit(e.id, None));
@@ -162,6 +174,16 @@ impl Component for StashList {
 				selection_valid,
 				true,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::stashlist_branch(&self.key_config),
+				selection_valid,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::stashlist_reword(&self.key_config),
+				selection_valid,
+				true,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::stashlist_inspect(
 					&self.key_config,
@@ -190,6 +212,10 @@ impl Component for StashList {
 					self.apply_stash();
 				} else if k == self.key_config.keys.stash_drop {
 					self.drop_stash();
+				} else if k == self.key_config.keys.stash_branch {
+					self.branch_stash();
+				} else if k == self.key_config.keys.stash_reword {
+					self.reword_stash();
 				} else if k == self.key_config.keys.stash_open {
 					self.inspect();
 				}
@@ -4,7 +4,7 @@ use crate::{
 		command_pump, event_pump, visibility_blocking,
 		ChangesComponent, CommandBlocking, CommandInfo, Component,
 		DiffComponent, DrawableComponent, EventState,
-		FileTreeItemKind, SharedOptions,
+		FileTreeItemKind, IgnoredFilesComponent, SharedOptions,
 	},
 	keys::SharedKeyConfig,
 	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
@@ -13,11 +13,12 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
 	cached,
-	sync::{self, status::StatusType, RepoState},
+	sync::{self, status::StatusType, ApplyMailboxState, RepoState},
 	sync::{BranchCompare, CommitId},
-	AsyncDiff, AsyncGitNotification, AsyncStatus, DiffParams,
-	DiffType, StatusParams, CWD,
+	AsyncDiff, AsyncGitNotification, AsyncIgnoredJob, AsyncStatus,
+	DiffParams, DiffType, StatusItem, StatusParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -35,6 +36,7 @@ enum Focus {
 	WorkDir,
 	Diff,
 	Stage,
+	Ignored,
 }
 
 /// focus can toggle between workdir and stage
@@ -42,7 +44,7 @@ impl Focus {
 	const fn toggled_focus(&self) -> Self {
 		match self {
 			Self::WorkDir => Self::Stage,
-			Self::Stage => Self::WorkDir,
+			Self::Stage | Self::Ignored => Self::WorkDir,
 			Self::Diff => Self::Diff,
 		}
 	}
@@ -61,10 +63,12 @@ pub struct Status {
 	diff_target: DiffTarget,
 	index: ChangesComponent,
 	index_wd: ChangesComponent,
+	ignored: IgnoredFilesComponent,
 	diff: DiffComponent,
 	git_diff: AsyncDiff,
 	git_status_workdir: AsyncStatus,
 	git_status_stage: AsyncStatus,
+	git_ignored: AsyncSingleJob<AsyncIgnoredJob>,
 	git_branch_state: Option<BranchCompare>,
 	git_branch_name: cached::BranchName,
 	queue: Queue,
@@ -110,26 +114,37 @@ impl DrawableComponent for Status {
 			)
 			.split(rects[0]);
 
+		let show_ignored = self.options.borrow().status_show_ignored;
+
 		let left_chunks = Layout::default()
 			.direction(Direction::Vertical)
-			.constraints(
-				if self.diff_target == DiffTarget::WorkingDir {
-					[
-						Constraint::Percentage(60),
-						Constraint::Percentage(40),
-					]
-				} else {
-					[
-						Constraint::Percentage(40),
-						Constraint::Percentage(60),
-					]
-				}
-				.as_ref(),
-			)
+			.constraints(match (self.diff_target, show_ignored) {
+				(DiffTarget::WorkingDir, false) => vec![
+					Constraint::Percentage(60),
+					Constraint::Percentage(40),
+				],
+				(DiffTarget::Stage, false) => vec![
+					Constraint::Percentage(40),
+					Constraint::Percentage(60),
+				],
+				(DiffTarget::WorkingDir, true) => vec![
+					Constraint::Percentage(45),
+					Constraint::Percentage(30),
+					Constraint::Percentage(25),
+				],
+				(DiffTarget::Stage, true) => vec![
+					Constraint::Percentage(30),
+					Constraint::Percentage(45),
+					Constraint::Percentage(25),
+				],
+			})
 			.split(chunks[0]);
 
 		self.index_wd.draw(f, left_chunks[0])?;
 		self.index.draw(f, left_chunks[1])?;
+		if show_ignored {
+			self.ignored.draw(f, left_chunks[2])?;
+		}
 		self.diff.draw(f, chunks[1])?;
 		self.draw_branch_state(f, &left_chunks);
 
@@ -142,7 +157,7 @@ impl DrawableComponent for Status {
 }
 
 impl Status {
-	accessors!(self, [index, index_wd, diff]);
+	accessors!(self, [index, index_wd, ignored, diff]);
 
 	///
 	pub fn new(
@@ -175,6 +190,11 @@ impl Status {
 				key_config.clone(),
 				options.clone(),
 			),
+			ignored: IgnoredFilesComponent::new(
+				queue.clone(),
+				theme.clone(),
+				key_config.clone(),
+			),
 			diff: DiffComponent::new(
 				queue.clone(),
 				theme,
@@ -184,6 +204,7 @@ impl Status {
 			git_diff: AsyncDiff::new(sender),
 			git_status_workdir: AsyncStatus::new(sender.clone()),
 			git_status_stage: AsyncStatus::new(sender.clone()),
+			git_ignored: AsyncSingleJob::new(sender.clone()),
 			git_action_executed: false,
 			git_branch_state: None,
 			git_branch_name: cached::BranchName::new(CWD),
@@ -260,6 +281,16 @@ impl Status {
 					String::new()
 				}
 			}
+			RepoState::ApplyMailbox => {
+				if let Ok(p) = sync::apply_mailbox_progress(CWD) {
+					format!(
+						"Patch: {}/{} Subject: {}",
+						p.current, p.total, p.subject,
+					)
+				} else {
+					String::new()
+				}
+			}
 			_ => format!("{:?}", state),
 		}
 	}
@@ -303,7 +334,7 @@ impl Status {
 		match self.focus {
 			Focus::WorkDir => self.index_wd.is_file_seleted(),
 			Focus::Stage => self.index.is_file_seleted(),
-			Focus::Diff => false,
+			Focus::Diff | Focus::Ignored => false,
 		}
 	}
 
@@ -315,6 +346,8 @@ impl Status {
 		if self.focus != f {
 			self.focus = f;
 
+			self.ignored.focus(self.focus == Focus::Ignored);
+
 			match self.focus {
 				Focus::WorkDir => {
 					self.set_diff_target(DiffTarget::WorkingDir);
@@ -330,6 +363,11 @@ impl Status {
 
 					self.diff.focus(true);
 				}
+				Focus::Ignored => {
+					self.index.focus_select(false);
+					self.index_wd.focus_select(false);
+					self.diff.focus(false);
+				}
 			};
 
 			self.update_diff()?;
@@ -349,6 +387,11 @@ impl Status {
 	}
 
 	pub fn selected_path(&self) -> Option<(String, bool)> {
+		self.selected_file()
+			.map(|(item, is_stage)| (item.path, is_stage))
+	}
+
+	fn selected_file(&self) -> Option<(StatusItem, bool)> {
 		let (idx, is_stage) = match self.diff_target {
 			DiffTarget::Stage => (&self.index, true),
 			DiffTarget::WorkingDir => (&self.index_wd, false),
@@ -356,7 +399,7 @@ impl Status {
 
 		if let Some(item) = idx.selection() {
 			if let FileTreeItemKind::File(i) = item.kind {
-				return Some((i.path, is_stage));
+				return Some((i, is_stage));
 			}
 		}
 		None
@@ -368,28 +411,69 @@ impl Status {
 
 		if self.is_visible() {
 			let config = self.options.borrow().status_show_untracked;
+			let options = self.options.borrow().diff;
 
 			self.git_diff.refresh()?;
 			self.git_status_workdir.fetch(&StatusParams::new(
 				StatusType::WorkingDir,
 				config,
+				options,
 			))?;
 			self.git_status_stage.fetch(&StatusParams::new(
 				StatusType::Stage,
 				config,
+				options,
 			))?;
 
 			self.branch_compare();
+			self.update_ignored()?;
+		}
+
+		Ok(())
+	}
+
+	fn update_ignored(&mut self) -> Result<()> {
+		if self.options.borrow().status_show_ignored {
+			self.git_ignored.spawn(AsyncIgnoredJob::new());
+		} else {
+			self.git_ignored.cancel();
+			self.ignored.set_items(Vec::new());
+
+			if self.focus == Focus::Ignored {
+				self.switch_focus(Focus::WorkDir)?;
+			}
 		}
 
 		Ok(())
 	}
 
+	fn update_ignored_files(&mut self) {
+		if let Some(job) = self.git_ignored.take_last() {
+			match job.result() {
+				Some(Ok(files))
+					if self.options.borrow().status_show_ignored =>
+				{
+					self.ignored.set_items(files);
+				}
+				Some(Err(e)) => {
+					log::error!("ignored files error: {}", e);
+				}
+				_ => (),
+			}
+		}
+	}
+
+	/// `true` while a search query is typed in the diff
+	pub fn is_diff_search_active(&self) -> bool {
+		self.diff.is_search_active()
+	}
+
 	///
 	pub fn anything_pending(&self) -> bool {
 		self.git_diff.is_pending()
 			|| self.git_status_stage.is_pending()
 			|| self.git_status_workdir.is_pending()
+			|| self.git_ignored.is_pending()
 	}
 
 	///
@@ -400,6 +484,9 @@ impl Status {
 		match ev {
 			AsyncGitNotification::Diff => self.update_diff()?,
 			AsyncGitNotification::Status => self.update_status()?,
+			AsyncGitNotification::Ignored => {
+				self.update_ignored_files();
+			}
 			AsyncGitNotification::Push
 			| AsyncGitNotification::Pull
 			| AsyncGitNotification::CommitFiles => {
@@ -440,7 +527,11 @@ impl Status {
 
 	///
 	pub fn update_diff(&mut self) -> Result<()> {
-		if let Some((path, is_stage)) = self.selected_path() {
+		if self.focus == Focus::Ignored {
+			self.diff.clear(false);
+		} else if let Some((item, is_stage)) = self.selected_file() {
+			let path = item.path;
+
 			let diff_type = if is_stage {
 				DiffType::Stage
 			} else {
@@ -449,29 +540,26 @@ impl Status {
 
 			let diff_params = DiffParams {
 				path: path.clone(),
+				old_path: item.old_path,
 				diff_type,
 				options: self.options.borrow().diff,
 			};
 
-			if self.diff.current() == (path.clone(), is_stage) {
+			if self.diff.current() == (path, is_stage) {
 				// we are already showing a diff of the right file
 				// maybe the diff changed (outside file change)
 				if let Some((params, last)) = self.git_diff.last()? {
 					if params == diff_params {
 						// all params match, so we might need to update
-						self.diff.update(path, is_stage, last);
+						self.diff.update(params, last);
 					} else {
 						// params changed, we need to request the right diff
-						self.request_diff(
-							diff_params,
-							path,
-							is_stage,
-						)?;
+						self.request_diff(diff_params)?;
 					}
 				}
 			} else {
 				// we dont show the right diff right now, so we need to request
-				self.request_diff(diff_params, path, is_stage)?;
+				self.request_diff(diff_params)?;
 			}
 		} else {
 			self.diff.clear(false);
@@ -483,11 +571,11 @@ impl Status {
 	fn request_diff(
 		&mut self,
 		diff_params: DiffParams,
-		path: String,
-		is_stage: bool,
 	) -> Result<(), anyhow::Error> {
-		if let Some(diff) = self.git_diff.request(diff_params)? {
-			self.diff.update(path, is_stage, diff);
+		if let Some(diff) =
+			self.git_diff.request(diff_params.clone())?
+		{
+			self.diff.update(diff_params, diff);
 		} else {
 			self.diff.clear(true);
 		}
@@ -497,7 +585,19 @@ impl Status {
 
 	/// called after confirmation
 	pub fn reset(&mut self, item: &ResetItem) -> bool {
-		if let Err(e) = sync::reset_workdir(CWD, item.path.as_str()) {
+		// restore the source of a rename before its untracked
+		// target gets removed
+		let res = item
+			.old_path
+			.as_ref()
+			.map_or(Ok(()), |old_path| {
+				sync::reset_workdir(CWD, old_path)
+			})
+			.and_then(|()| {
+				sync::reset_workdir(CWD, item.path.as_str())
+			});
+
+		if let Err(e) = res {
 			self.queue.push(InternalEvent::ShowErrorMsg(format!(
 				"reset failed:\n{}",
 				e
@@ -570,6 +670,11 @@ impl Status {
 			== RepoState::Rebase
 	}
 
+	fn pending_apply_mailbox() -> bool {
+		sync::repo_state(CWD).unwrap_or(RepoState::Clean)
+			== RepoState::ApplyMailbox
+	}
+
 	pub fn abort_merge(&self) {
 		try_or_popup!(self, "abort merge", sync::abort_merge(CWD));
 	}
@@ -590,6 +695,38 @@ impl Status {
 		);
 	}
 
+	pub fn abort_apply_mailbox(&self) {
+		try_or_popup!(
+			self,
+			"abort apply",
+			sync::abort_apply_mailbox(CWD)
+		);
+	}
+
+	fn continue_apply_mailbox(&self) {
+		match sync::continue_apply_mailbox(CWD) {
+			Ok(ApplyMailboxState::Finished) => (),
+			Ok(ApplyMailboxState::Stopped) => {
+				if let Ok(p) = sync::apply_mailbox_progress(CWD) {
+					self.queue.push(InternalEvent::ShowInfoMsg(
+						strings::apply_mailbox_stopped_msg(
+							&self.key_config,
+							p.current,
+							p.total,
+							&p.subject,
+							&p.patch_file.to_string_lossy(),
+						),
+					));
+				}
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("continue apply:\n{}", e),
+				));
+			}
+		}
+	}
+
 	fn commands_nav(
 		&self,
 		out: &mut Vec<CommandInfo>,
@@ -638,6 +775,43 @@ impl Status {
 		self.index.focused()
 			&& !self.index.is_empty()
 			&& !Self::pending_rebase()
+			&& !Self::pending_apply_mailbox()
+	}
+
+	fn repo_state_commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) {
+		out.push(CommandInfo::new(
+			strings::commands::abort_merge(&self.key_config),
+			true,
+			Self::can_abort_merge() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::continue_rebase(&self.key_config),
+			true,
+			Self::pending_rebase() || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::abort_rebase(&self.key_config),
+			true,
+			Self::pending_rebase() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::continue_apply_mailbox(
+				&self.key_config,
+			),
+			true,
+			Self::pending_apply_mailbox() || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::abort_apply_mailbox(&self.key_config),
+			true,
+			Self::pending_apply_mailbox() || force_all,
+		));
 	}
 }
 
@@ -698,21 +872,27 @@ impl Component for Status {
 					|| force_all,
 			));
 
+			self.repo_state_commands(out, force_all);
+
 			out.push(CommandInfo::new(
-				strings::commands::abort_merge(&self.key_config),
+				strings::commands::status_clean(&self.key_config),
 				true,
-				Self::can_abort_merge() || force_all,
+				!focus_on_diff || force_all,
 			));
-
 			out.push(CommandInfo::new(
-				strings::commands::continue_rebase(&self.key_config),
+				strings::commands::status_index_flags(
+					&self.key_config,
+				),
 				true,
-				Self::pending_rebase() || force_all,
+				!focus_on_diff || force_all,
 			));
 			out.push(CommandInfo::new(
-				strings::commands::abort_rebase(&self.key_config),
+				strings::commands::status_apply_patch(
+					&self.key_config,
+				),
 				true,
-				Self::pending_rebase() || force_all,
+				(!Self::repo_state_unclean() && !focus_on_diff)
+					|| force_all,
 			));
 		}
 
@@ -789,6 +969,22 @@ impl Component for Status {
 					&& !self.index_wd.is_empty()
 				{
 					self.switch_focus(Focus::WorkDir).map(Into::into)
+				} else if k == self.key_config.keys.move_down
+					&& (self.focus == Focus::Stage
+						|| (self.focus == Focus::WorkDir
+							&& self.index.is_empty()))
+					&& !self.ignored.is_empty()
+				{
+					self.switch_focus(Focus::Ignored).map(Into::into)
+				} else if k == self.key_config.keys.move_up
+					&& self.focus == Focus::Ignored
+				{
+					self.switch_focus(if self.index.is_empty() {
+						Focus::WorkDir
+					} else {
+						Focus::Stage
+					})
+					.map(Into::into)
 				} else if k == self.key_config.keys.select_branch
 					&& !self.is_focus_on_diff()
 				{
@@ -842,6 +1038,38 @@ impl Component for Status {
 						NeedsUpdate::ALL,
 					));
 					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.abort_merge
+					&& Self::pending_apply_mailbox()
+				{
+					self.queue.push(InternalEvent::ConfirmAction(
+						Action::AbortApplyMailbox,
+					));
+
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.rebase_branch
+					&& Self::pending_apply_mailbox()
+				{
+					self.continue_apply_mailbox();
+					self.queue.push(InternalEvent::Update(
+						NeedsUpdate::ALL,
+					));
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.status_clean
+					&& !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::OpenClean);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.status_index_flags
+					&& !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::OpenIndexFlags);
+					Ok(EventState::Consumed)
+				} else if k == self.key_config.keys.status_apply_patch
+					&& !self.is_focus_on_diff()
+					&& !Self::repo_state_unclean()
+				{
+					self.queue.push(InternalEvent::OpenApplyPatch);
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
@@ -137,13 +137,16 @@ impl Theme {
 			StatusItemType::Deleted => {
 				Style::default().fg(self.diff_file_removed)
 			}
-			StatusItemType::Renamed => {
+			StatusItemType::Renamed | StatusItemType::Copied => {
 				Style::default().fg(self.diff_file_moved)
 			}
 			StatusItemType::Conflicted => Style::default()
 				.fg(self.diff_file_modified)
 				.add_modifier(Modifier::BOLD),
 			StatusItemType::Typechange => Style::default(),
+			StatusItemType::Ignored => {
+				Style::default().fg(self.disabled_fg)
+			}
 		};
 
 		self.apply_select(style, selected)
@@ -212,6 +215,15 @@ impl Theme {
 		self.apply_select(style, selected)
 	}
 
+	pub fn diff_search_match(
+		&self,
+		typ: DiffLineType,
+		selected: bool,
+	) -> Style {
+		self.diff_line(typ, selected)
+			.add_modifier(Modifier::REVERSED | Modifier::BOLD)
+	}
+
 	pub fn text_danger(&self) -> Style {
 		Style::default().fg(self.danger_fg)
 	}
@@ -260,6 +272,22 @@ impl Theme {
 		)
 	}
 
+	/// line metadata tinted by age, `heat` goes from 0 for the oldest
+	/// to 100 for the most recent change of the blamed file
+	pub fn blame_heat(&self, heat: usize) -> Style {
+		let colors = [
+			self.disabled_fg,
+			Color::Blue,
+			Color::Cyan,
+			Color::Green,
+			Color::Yellow,
+			Color::Red,
+		];
+
+		Style::default()
+			.fg(colors[heat.min(100) * (colors.len() - 1) / 100])
+	}
+
 	pub fn commit_hash_in_blame(
 		&self,
 		is_blamed_commit: bool,